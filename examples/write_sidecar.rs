@@ -26,6 +26,7 @@ fn main() -> Result<(), Error> {
             WriteOptions {
                 write_statistics: true,
                 version: Version::V2,
+                write_page_crc: false,
             },
             None,
         );
@@ -1,11 +1,16 @@
-//! API to read and use bloom filters
+//! API to read, write and use bloom filters
 mod hash;
+mod prune;
 mod read;
 mod split_block;
 
 pub use hash::{hash_byte, hash_native};
+pub use prune::BloomFilters;
 pub use read::read;
-pub use split_block::{insert, is_in_set};
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub use read::read_async;
+pub use split_block::{insert, is_in_set, optimal_num_of_bytes};
 
 #[cfg(test)]
 mod tests {
@@ -68,4 +73,26 @@ mod tests {
         ];
         assert_eq!(bitset, expected);
     }
+
+    #[test]
+    fn optimal_size() {
+        // smallest bitset is a single block
+        assert_eq!(optimal_num_of_bytes(0, 0.01).unwrap(), 32);
+        assert_eq!(optimal_num_of_bytes(10, 0.01).unwrap(), 32);
+        // rounded up to the next power of two
+        assert_eq!(optimal_num_of_bytes(1_000, 0.01).unwrap(), 2048);
+        assert_eq!(
+            optimal_num_of_bytes(1_000_000, 0.01).unwrap(),
+            2 * 1024 * 1024
+        );
+        // capped at 128MiB
+        assert_eq!(
+            optimal_num_of_bytes(usize::MAX, 0.01).unwrap(),
+            128 * 1024 * 1024
+        );
+        // the false positive probability must be in (0, 1)
+        assert!(optimal_num_of_bytes(10, 0.0).is_err());
+        assert!(optimal_num_of_bytes(10, 1.0).is_err());
+        assert!(optimal_num_of_bytes(10, f64::NAN).is_err());
+    }
 }
@@ -0,0 +1,117 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::io::{Read, Seek};
+
+use crate::error::{Error, Result};
+use crate::metadata::FileMetaData;
+use crate::read::predicate::column_index;
+use crate::read::Value;
+
+use super::{hash_byte, hash_native, is_in_set, read};
+
+/// The bloom filters of the column chunks of a file, read on demand and cached.
+///
+/// It is bound to the [`FileMetaData`] of the file; reuse it across lookups, so that each
+/// bloom filter is read once.
+#[derive(Debug, Clone)]
+pub struct BloomFilters<'a> {
+    metadata: &'a FileMetaData,
+    // (row group, column) -> bitset, or `None` if the column chunk has no supported filter
+    bitsets: HashMap<(usize, usize), Option<Vec<u8>>>,
+}
+
+impl<'a> BloomFilters<'a> {
+    /// Returns a new, empty, [`BloomFilters`] of the file with `metadata`.
+    pub fn new(metadata: &'a FileMetaData) -> Self {
+        Self {
+            metadata,
+            bitsets: HashMap::new(),
+        }
+    }
+
+    /// Returns the bitset of the bloom filter of the `column`th column of the `row_group`th
+    /// row group of the file, reading it from `reader` unless it is cached.
+    /// Returns `None` if the column chunk has no bloom filter or its algorithm is not supported.
+    /// # Error
+    /// Errors if the bloom filter can't be read.
+    /// # Panics
+    /// Panics if the file has no such column chunk.
+    pub fn get<R: Read + Seek>(
+        &mut self,
+        reader: &mut R,
+        row_group: usize,
+        column: usize,
+    ) -> Result<Option<&[u8]>> {
+        let bitset = match self.bitsets.entry((row_group, column)) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let mut bitset = vec![];
+                read(
+                    &self.metadata.row_groups[row_group].columns()[column],
+                    reader,
+                    &mut bitset,
+                )?;
+                entry.insert((!bitset.is_empty()).then_some(bitset))
+            }
+        };
+        Ok(bitset.as_deref())
+    }
+
+    /// Returns the indices of the row groups of the file that can be skipped when looking
+    /// for rows whose column at `path` equals any of `values`, i.e. those whose bloom filter
+    /// contains none of them.
+    ///
+    /// Row groups whose column chunk has no bloom filter are never skipped. Values are
+    /// expressed in the physical type of the column (see [`Value`]); boolean columns can't
+    /// be pruned.
+    /// # Error
+    /// Errors if `path` is not a primitive column of the schema, if a value does not match
+    /// the physical type of the column or if a bloom filter can't be read.
+    pub fn prune_row_groups<R: Read + Seek>(
+        &mut self,
+        reader: &mut R,
+        path: &[String],
+        values: &[Value],
+    ) -> Result<Vec<usize>> {
+        let metadata = self.metadata;
+        let column = column_index(metadata, path)?;
+        let physical_type = &metadata.schema().columns()[column]
+            .descriptor
+            .primitive_type
+            .physical_type;
+        if let Some(value) = values.iter().find(|x| !x.is_of(physical_type)) {
+            return Err(Error::InvalidParameter(format!(
+                "The value {:?} can't be looked up in a column of physical type {:?}",
+                value, physical_type
+            )));
+        }
+        let hashes = values.iter().map(hash).collect::<Option<Vec<_>>>();
+        let hashes = if let Some(hashes) = hashes {
+            hashes
+        } else {
+            return Ok(vec![]);
+        };
+
+        let mut skipped = vec![];
+        for row_group in 0..metadata.row_groups.len() {
+            if let Some(bitset) = self.get(reader, row_group, column)? {
+                if !hashes.iter().any(|hash| is_in_set(bitset, *hash)) {
+                    skipped.push(row_group)
+                }
+            }
+        }
+        Ok(skipped)
+    }
+}
+
+/// The hash of `value` in a bloom filter, or `None` if it can't be in one
+fn hash(value: &Value) -> Option<u64> {
+    match value {
+        Value::Boolean(_) => None,
+        Value::Int32(x) => Some(hash_native(*x)),
+        Value::Int64(x) => Some(hash_native(*x)),
+        Value::Float(x) => Some(hash_native(*x)),
+        Value::Double(x) => Some(hash_native(*x)),
+        Value::Bytes(x) => Some(hash_byte(x)),
+    }
+}
@@ -1,12 +1,26 @@
 use std::io::{Read, Seek, SeekFrom};
 
+#[cfg(feature = "async")]
+use futures::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt};
+#[cfg(feature = "async")]
+use parquet_format_safe::thrift::protocol::TCompactInputStreamProtocol;
+
 use parquet_format_safe::{
     thrift::protocol::TCompactInputProtocol, BloomFilterAlgorithm, BloomFilterCompression,
     BloomFilterHeader, SplitBlockAlgorithm, Uncompressed,
 };
 
+#[cfg(all(feature = "async", feature = "encryption"))]
+use crate::encryption::read_module_async;
+#[cfg(feature = "encryption")]
+use crate::encryption::{read_module, ModuleType};
 use crate::{error::Error, metadata::ColumnChunkMetaData};
 
+fn is_supported(header: &BloomFilterHeader) -> bool {
+    header.algorithm == BloomFilterAlgorithm::BLOCK(SplitBlockAlgorithm {})
+        && header.compression == BloomFilterCompression::UNCOMPRESSED(Uncompressed {})
+}
+
 /// Reads the bloom filter associated to [`ColumnChunkMetaData`] into `bitset`.
 /// Results in an empty `bitset` if there is no associated bloom filter or the algorithm is not supported.
 /// # Error
@@ -16,6 +30,8 @@ pub fn read<R: Read + Seek>(
     mut reader: &mut R,
     bitset: &mut Vec<u8>,
 ) -> Result<(), Error> {
+    // a column that can't be decrypted may have no bloom filter in its metadata
+    column_metadata.check_decryption()?;
     let offset = column_metadata.metadata().bloom_filter_offset;
 
     let offset = if let Some(offset) = offset {
@@ -26,15 +42,86 @@ pub fn read<R: Read + Seek>(
     };
     reader.seek(SeekFrom::Start(offset))?;
 
+    #[cfg(feature = "encryption")]
+    if let Some(decryptor) = column_metadata.decryptor() {
+        // [encrypted header][encrypted bitset]
+        let module = read_module(reader, 1024)?;
+        let header = decryptor.decrypt(ModuleType::BloomFilterHeader, None, &module)?;
+        let mut prot = TCompactInputProtocol::new(header.as_slice(), usize::MAX);
+        let header = BloomFilterHeader::read_from_in_protocol(&mut prot)?;
+
+        bitset.clear();
+        if is_supported(&header) {
+            let length: usize = header.num_bytes.try_into()?;
+            let module = read_module(reader, length + 1024)?;
+            *bitset = decryptor.decrypt(ModuleType::BloomFilterBitset, None, &module)?;
+        }
+        return Ok(());
+    }
+
     // deserialize header
     let mut prot = TCompactInputProtocol::new(&mut reader, usize::MAX); // max is ok since `BloomFilterHeader` never allocates
     let header = BloomFilterHeader::read_from_in_protocol(&mut prot)?;
 
-    if header.algorithm != BloomFilterAlgorithm::BLOCK(SplitBlockAlgorithm {}) {
+    if !is_supported(&header) {
         bitset.clear();
         return Ok(());
     }
-    if header.compression != BloomFilterCompression::UNCOMPRESSED(Uncompressed {}) {
+
+    let length: usize = header.num_bytes.try_into()?;
+
+    bitset.clear();
+    bitset.try_reserve(length)?;
+    reader.by_ref().take(length as u64).read_to_end(bitset)?;
+
+    Ok(())
+}
+
+/// Asynchronously reads the bloom filter associated to [`ColumnChunkMetaData`] into `bitset`.
+/// Results in an empty `bitset` if there is no associated bloom filter or the algorithm is not supported.
+/// # Error
+/// Errors if the column contains no metadata or the filter can't be read or deserialized.
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub async fn read_async<R: AsyncRead + AsyncSeek + Send + Unpin>(
+    column_metadata: &ColumnChunkMetaData,
+    reader: &mut R,
+    bitset: &mut Vec<u8>,
+) -> Result<(), Error> {
+    // a column that can't be decrypted may have no bloom filter in its metadata
+    column_metadata.check_decryption()?;
+    let offset = column_metadata.metadata().bloom_filter_offset;
+
+    let offset = if let Some(offset) = offset {
+        offset as u64
+    } else {
+        bitset.clear();
+        return Ok(());
+    };
+    reader.seek(SeekFrom::Start(offset)).await?;
+
+    #[cfg(feature = "encryption")]
+    if let Some(decryptor) = column_metadata.decryptor() {
+        // [encrypted header][encrypted bitset]
+        let module = read_module_async(reader, 1024).await?;
+        let header = decryptor.decrypt(ModuleType::BloomFilterHeader, None, &module)?;
+        let mut prot = TCompactInputProtocol::new(header.as_slice(), usize::MAX);
+        let header = BloomFilterHeader::read_from_in_protocol(&mut prot)?;
+
+        bitset.clear();
+        if is_supported(&header) {
+            let length: usize = header.num_bytes.try_into()?;
+            let module = read_module_async(reader, length + 1024).await?;
+            *bitset = decryptor.decrypt(ModuleType::BloomFilterBitset, None, &module)?;
+        }
+        return Ok(());
+    }
+
+    // deserialize header
+    let mut prot = TCompactInputStreamProtocol::new(&mut *reader, usize::MAX); // max is ok since `BloomFilterHeader` never allocates
+    let header = BloomFilterHeader::stream_from_in_protocol(&mut prot).await?;
+
+    if !is_supported(&header) {
         bitset.clear();
         return Ok(());
     }
@@ -43,7 +130,7 @@ pub fn read<R: Read + Seek>(
 
     bitset.clear();
     bitset.try_reserve(length)?;
-    reader.by_ref().take(length as u64).read_to_end(bitset)?;
+    reader.take(length as u64).read_to_end(bitset).await?;
 
     Ok(())
 }
@@ -1,5 +1,7 @@
 use std::convert::TryInto;
 
+use crate::error::{Error, Result};
+
 /// magic numbers taken from https://github.com/apache/parquet-format/blob/master/BloomFilter.md
 const SALT: [u32; 8] = [
     1203114875, 1150766481, 2284105051, 2729912477, 1884591559, 770785867, 2667333959, 1550580529,
@@ -80,3 +82,36 @@ pub fn insert(bitset: &mut [u8], hash: u64) {
         unload_block(block_mask, mut_slice)
     }
 }
+
+/// The minimum number of bytes of a bitset, corresponding to a single block
+const LOWER_BOUND_BYTES: usize = 32;
+/// The maximum number of bytes of a bitset, as declared by parquet-mr
+const UPPER_BOUND_BYTES: usize = 128 * 1024 * 1024;
+
+/// Returns the optimal number of bytes of a bitset holding `num_distinct_values` distinct values
+/// with a false positive probability `fpp`.
+///
+/// The result is a power of two between 32 bytes and 128MiB, so that it can be used
+/// to initialize a bitset (`vec![0; optimal_num_of_bytes(ndv, fpp)?]`) that values are
+/// [`insert`]ed to.
+/// # Errors
+/// Errors iff `fpp` is not in the interval `(0, 1)`.
+pub fn optimal_num_of_bytes(num_distinct_values: usize, fpp: f64) -> Result<usize> {
+    if !(fpp > 0.0 && fpp < 1.0) {
+        return Err(Error::InvalidParameter(format!(
+            "The false positive probability must be in the interval (0, 1) but it is {}",
+            fpp
+        )));
+    }
+    // see https://github.com/apache/parquet-format/blob/master/BloomFilter.md#sizing-an-sbbf
+    let num_bits = -8.0 * num_distinct_values as f64 / (1.0 - fpp.powf(1.0 / 8.0)).ln();
+    let num_bytes = (num_bits / 8.0).ceil();
+    let num_bytes = if num_bytes >= UPPER_BOUND_BYTES as f64 {
+        UPPER_BOUND_BYTES
+    } else {
+        num_bytes as usize
+    };
+    Ok(num_bytes
+        .next_power_of_two()
+        .clamp(LOWER_BOUND_BYTES, UPPER_BOUND_BYTES))
+}
@@ -5,6 +5,9 @@ pub use super::parquet_bridge::{
 
 use crate::error::{Error, Result};
 
+#[cfg(feature = "lzo")]
+mod lzo;
+
 fn inner_compress<G: Fn(usize) -> Result<usize>, F: Fn(&[u8], &mut [u8]) -> Result<usize>>(
     input: &[u8],
     output: &mut Vec<u8>,
@@ -119,6 +122,16 @@ pub fn compress(
             crate::error::Feature::Zstd,
             "compress to zstd".to_string(),
         )),
+        #[cfg(feature = "lzo")]
+        CompressionOptions::Lzo => {
+            lzo::compress(input_buf, output_buf);
+            Ok(())
+        }
+        #[cfg(not(feature = "lzo"))]
+        CompressionOptions::Lzo => Err(Error::FeatureNotActive(
+            crate::error::Feature::Lzo,
+            "compress to lzo".to_string(),
+        )),
         CompressionOptions::Uncompressed => {
             Err(general_err!("Compressing without compression is not valid"))
         }
@@ -212,13 +225,16 @@ pub fn decompress(compression: Compression, input_buf: &[u8], output_buf: &mut [
             crate::error::Feature::Zstd,
             "decompress with zstd".to_string(),
         )),
+        #[cfg(feature = "lzo")]
+        Compression::Lzo => lzo::decompress(input_buf, output_buf),
+        #[cfg(not(feature = "lzo"))]
+        Compression::Lzo => Err(Error::FeatureNotActive(
+            crate::error::Feature::Lzo,
+            "decompress with lzo".to_string(),
+        )),
         Compression::Uncompressed => {
             Err(general_err!("Compressing without compression is not valid"))
         }
-        _ => Err(general_err!(
-            "Compression {:?} is not yet supported",
-            compression
-        )),
     }
 }
 
@@ -384,6 +400,12 @@ mod tests {
         test_codec(CompressionOptions::Lz4Raw);
     }
 
+    #[cfg(feature = "lzo")]
+    #[test]
+    fn test_codec_lzo() {
+        test_codec(CompressionOptions::Lzo);
+    }
+
     #[test]
     fn test_codec_zstd_default() {
         test_codec(CompressionOptions::Zstd(None));
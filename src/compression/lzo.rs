@@ -0,0 +1,435 @@
+//! An implementation of the LZO1X format and of the framing of the Hadoop `LzoCodec`.
+//!
+//! The LZO1X stream is a sequence of instructions, each either a run of literals or a match
+//! (a copy of bytes already decompressed), ending with an end-of-stream marker. The 2 lowest
+//! bits of every match declare the number (0-3) of literals following it; longer runs of
+//! literals are separate instructions. The instruction of a match depends on its distance:
+//! * `M1` (`0-15`): a match of 2 bytes after 1-3 literals, or of 3 bytes after 4 or more;
+//! * `M2` (`64-255`): a match of 3-8 bytes up to 2048 bytes away;
+//! * `M3` (`32-63`): a match up to 16384 bytes away;
+//! * `M4` (`16-31`): a match up to 49151 bytes away, or the end of the stream.
+use crate::error::{Error, Result};
+
+const M2_MAX_LENGTH: usize = 8;
+const M2_MAX_DISTANCE: usize = 0x0800;
+const M3_MAX_LENGTH: usize = 33;
+const M3_MAX_DISTANCE: usize = 0x4000;
+const M4_MAX_LENGTH: usize = 9;
+const M4_MAX_DISTANCE: usize = 0xbfff;
+
+const M3_MARKER: u8 = 32;
+const M4_MARKER: u8 = 16;
+
+/// The end-of-stream marker, a `M4` match of distance 0
+const END_OF_STREAM: [u8; 3] = [M4_MARKER | 1, 0, 0];
+
+/// The maximum number of bytes compressed in a Hadoop block, the default buffer size of
+/// Hadoop's `LzoCodec`.
+const HADOOP_BLOCK_SIZE: usize = 256 * 1024;
+
+const HASH_BITS: u32 = 14;
+
+fn out_of_spec(reason: &str) -> Error {
+    Error::OutOfSpec(format!("Invalid LZO stream: {}", reason))
+}
+
+struct Decoder<'a> {
+    input: &'a [u8],
+    output: &'a mut [u8],
+    position: usize,
+    length: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn next(&mut self) -> Result<usize> {
+        let byte = self
+            .input
+            .get(self.position)
+            .ok_or_else(|| out_of_spec("it ended before its end-of-stream marker"))?;
+        self.position += 1;
+        Ok(*byte as usize)
+    }
+
+    fn next_u16(&mut self) -> Result<usize> {
+        Ok(self.next()? | (self.next()? << 8))
+    }
+
+    /// Reads the extension of a length: a byte of value `255 * zeros + byte` after `zeros`
+    /// zero bytes.
+    fn extended_length(&mut self, base: usize) -> Result<usize> {
+        let mut length = base;
+        loop {
+            match self.next()? {
+                0 => length += 255,
+                byte => return Ok(length + byte),
+            }
+        }
+    }
+
+    fn copy_literals(&mut self, length: usize) -> Result<()> {
+        let literals = self
+            .input
+            .get(self.position..self.position + length)
+            .ok_or_else(|| out_of_spec("it ended before its end-of-stream marker"))?;
+        self.output
+            .get_mut(self.length..self.length + length)
+            .ok_or_else(|| out_of_spec("it is larger than its declared size"))?
+            .copy_from_slice(literals);
+        self.position += length;
+        self.length += length;
+        Ok(())
+    }
+
+    fn copy_match(&mut self, distance: usize, length: usize) -> Result<()> {
+        if distance > self.length {
+            return Err(out_of_spec("a match is before the start of the stream"));
+        }
+        if self.length + length > self.output.len() {
+            return Err(out_of_spec("it is larger than its declared size"));
+        }
+        let start = self.length - distance;
+        if distance >= length {
+            self.output.copy_within(start..start + length, self.length);
+        } else {
+            // the match overlaps the bytes it writes
+            for i in 0..length {
+                self.output[self.length + i] = self.output[start + i];
+            }
+        }
+        self.length += length;
+        Ok(())
+    }
+}
+
+/// Decompresses a LZO1X stream into `output`, returning the number of bytes written.
+pub fn decompress_block(input: &[u8], output: &mut [u8]) -> Result<usize> {
+    let mut decoder = Decoder {
+        input,
+        output,
+        position: 0,
+        length: 0,
+    };
+
+    // the number of literals after the last instruction, up to 4
+    let mut state = 0;
+    // a stream starting with 1-238 literals starts with their number + 17
+    if let Some(&first) = input.first() {
+        if first > 17 {
+            decoder.position = 1;
+            let length = first as usize - 17;
+            decoder.copy_literals(length)?;
+            state = length.min(4);
+        }
+    }
+
+    loop {
+        let instruction = decoder.next()?;
+        let (distance, length, literals) = if instruction < 16 {
+            if state == 0 {
+                let length = match instruction {
+                    0 => decoder.extended_length(15)?,
+                    length => length,
+                };
+                decoder.copy_literals(length + 3)?;
+                state = 4;
+                continue;
+            }
+            let distance = 1 + (instruction >> 2) + (decoder.next()? << 2);
+            if state == 4 {
+                (distance + M2_MAX_DISTANCE, 3, instruction & 3)
+            } else {
+                (distance, 2, instruction & 3)
+            }
+        } else if instruction >= 64 {
+            let distance = 1 + ((instruction >> 2) & 7) + (decoder.next()? << 3);
+            (distance, (instruction >> 5) + 1, instruction & 3)
+        } else if instruction >= 32 {
+            let length = match instruction & 31 {
+                0 => decoder.extended_length(31)?,
+                length => length,
+            };
+            let value = decoder.next_u16()?;
+            (1 + (value >> 2), length + 2, value & 3)
+        } else {
+            let length = match instruction & 7 {
+                0 => decoder.extended_length(7)?,
+                length => length,
+            };
+            let value = decoder.next_u16()?;
+            let distance = ((instruction & 8) << 11) + (value >> 2);
+            if distance == 0 {
+                break;
+            }
+            (distance + M3_MAX_DISTANCE, length + 2, value & 3)
+        };
+        decoder.copy_match(distance, length)?;
+        decoder.copy_literals(literals)?;
+        state = literals;
+    }
+
+    if decoder.position != input.len() {
+        return Err(out_of_spec("it has bytes after its end-of-stream marker"));
+    }
+    Ok(decoder.length)
+}
+
+/// Writes a run of literals to `output`, whose LZO1X stream started at `start`.
+fn write_literals(output: &mut Vec<u8>, start: usize, literals: &[u8]) {
+    let length = literals.len();
+    if length == 0 {
+        return;
+    }
+    if output.len() == start && length <= 238 {
+        output.push(17 + length as u8);
+    } else if output.len() > start && length <= 3 {
+        // declared by the 2 lowest bits of the previous match
+        let position = output.len() - 2;
+        output[position] |= length as u8;
+    } else if length <= 18 {
+        output.push(length as u8 - 3);
+    } else {
+        output.push(0);
+        write_extended_length(output, length - 18);
+    }
+    output.extend_from_slice(literals);
+}
+
+fn write_extended_length(output: &mut Vec<u8>, mut length: usize) {
+    while length > 255 {
+        output.push(0);
+        length -= 255;
+    }
+    output.push(length as u8);
+}
+
+/// Writes a match of at least 3 bytes to `output`.
+fn write_match(output: &mut Vec<u8>, distance: usize, length: usize) {
+    if length <= M2_MAX_LENGTH && distance <= M2_MAX_DISTANCE {
+        let distance = distance - 1;
+        output.push((((length - 1) << 5) | ((distance & 7) << 2)) as u8);
+        output.push((distance >> 3) as u8);
+        return;
+    }
+    let distance = if distance <= M3_MAX_DISTANCE {
+        if length <= M3_MAX_LENGTH {
+            output.push(M3_MARKER | (length - 2) as u8);
+        } else {
+            output.push(M3_MARKER);
+            write_extended_length(output, length - M3_MAX_LENGTH);
+        }
+        distance - 1
+    } else {
+        let distance = distance - M3_MAX_DISTANCE;
+        let marker = M4_MARKER | ((distance >> 11) & 8) as u8;
+        if length <= M4_MAX_LENGTH {
+            output.push(marker | (length - 2) as u8);
+        } else {
+            output.push(marker);
+            write_extended_length(output, length - M4_MAX_LENGTH);
+        }
+        distance
+    };
+    output.push((distance << 2) as u8);
+    output.push((distance >> 6) as u8);
+}
+
+/// Compresses `input` to a LZO1X stream appended to `output`.
+pub fn compress_block(input: &[u8], output: &mut Vec<u8>) {
+    let start = output.len();
+    // the position + 1 of the last sequence of 4 bytes with a given hash
+    let mut table = vec![0usize; 1 << HASH_BITS];
+
+    let mut literals = 0;
+    let mut position = 0;
+    while position + 4 <= input.len() {
+        let sequence = &input[position..position + 4];
+        let hash = u32::from_le_bytes(sequence.try_into().unwrap()).wrapping_mul(2654435761)
+            >> (32 - HASH_BITS);
+        let candidate = std::mem::replace(&mut table[hash as usize], position + 1);
+
+        if candidate > 0 {
+            let candidate = candidate - 1;
+            let distance = position - candidate;
+            if distance <= M4_MAX_DISTANCE && &input[candidate..candidate + 4] == sequence {
+                let length = 4 + input[position + 4..]
+                    .iter()
+                    .zip(&input[candidate + 4..])
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                write_literals(output, start, &input[literals..position]);
+                write_match(output, distance, length);
+                position += length;
+                literals = position;
+                continue;
+            }
+        }
+        position += 1;
+    }
+    write_literals(output, start, &input[literals..]);
+    output.extend_from_slice(&END_OF_STREAM);
+}
+
+fn read_u32(input: &mut &[u8]) -> Result<usize> {
+    if input.len() < 4 {
+        return Err(out_of_spec("not enough bytes for a Hadoop frame"));
+    }
+    let (bytes, remaining) = input.split_at(4);
+    *input = remaining;
+    Ok(u32::from_be_bytes(bytes.try_into().unwrap()) as usize)
+}
+
+/// Decompresses the blocks of the Hadoop `LzoCodec` into `output`.
+///
+/// Every block starts with its big-endian `u32` decompressed size followed by one or more
+/// chunks, each a big-endian `u32` compressed size followed by a LZO1X stream.
+fn decompress_hadoop(mut input: &[u8], mut output: &mut [u8]) -> Result<()> {
+    while !input.is_empty() {
+        let block_size = read_u32(&mut input)?;
+        if block_size > output.len() {
+            return Err(out_of_spec("not enough bytes to hold the Hadoop block"));
+        }
+        let (block, remaining) = std::mem::take(&mut output).split_at_mut(block_size);
+        output = remaining;
+
+        let mut length = 0;
+        while length < block_size {
+            let chunk_size = read_u32(&mut input)?;
+            if chunk_size > input.len() {
+                return Err(out_of_spec("not enough bytes for a Hadoop chunk"));
+            }
+            let (chunk, remaining) = input.split_at(chunk_size);
+            input = remaining;
+            length += decompress_block(chunk, &mut block[length..])?;
+        }
+    }
+    if !output.is_empty() {
+        return Err(out_of_spec("it is smaller than its declared size"));
+    }
+    Ok(())
+}
+
+/// Returns whether `input`, decompressed to `length` bytes, is framed by the Hadoop
+/// `LzoCodec`, i.e. whether it starts with the decompressed size of a non-empty block of at most
+/// `length` bytes followed by the compressed size of its first chunk, at most the remaining
+/// bytes. The first instruction of a LZO1X stream of more than 3 literals has a first byte
+/// larger than 17, so that it is never a valid block size; only streams starting with a long
+/// run of literals (a first byte of 0) are ambiguous.
+fn is_hadoop(input: &[u8], length: usize) -> bool {
+    if input.is_empty() {
+        return length == 0;
+    }
+    if input.len() < 8 {
+        return false;
+    }
+    let block_size = u32::from_be_bytes(input[..4].try_into().unwrap()) as usize;
+    let chunk_size = u32::from_be_bytes(input[4..8].try_into().unwrap()) as usize;
+    block_size > 0 && block_size <= length && chunk_size > 0 && chunk_size <= input.len() - 8
+}
+
+/// Decompresses `input` into `output`, compressed either with the framing of the Hadoop
+/// `LzoCodec` (used by e.g. `parquet-mr`) or as a single LZO1X stream. The framing is chosen
+/// from the header of `input` (see [`is_hadoop`]); errors of the chosen framing are returned.
+pub fn decompress(input: &[u8], output: &mut [u8]) -> Result<()> {
+    if is_hadoop(input, output.len()) {
+        return decompress_hadoop(input, output);
+    }
+    if decompress_block(input, output)? != output.len() {
+        return Err(out_of_spec("it is smaller than its declared size"));
+    }
+    Ok(())
+}
+
+/// Compresses `input` to `output` with the framing of the Hadoop `LzoCodec`, so that it can be
+/// read by other implementations.
+pub fn compress(input: &[u8], output: &mut Vec<u8>) {
+    for block in input.chunks(HADOOP_BLOCK_SIZE) {
+        output.extend_from_slice(&(block.len() as u32).to_be_bytes());
+        let start = output.len();
+        output.extend_from_slice(&[0; 4]);
+        compress_block(block, output);
+        let chunk_size = (output.len() - start - 4) as u32;
+        output[start..start + 4].copy_from_slice(&chunk_size.to_be_bytes());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip(data: &[u8]) {
+        let mut compressed = vec![];
+        compress_block(data, &mut compressed);
+        let mut decompressed = vec![0; data.len()];
+        assert_eq!(
+            decompress_block(&compressed, &mut decompressed).unwrap(),
+            data.len()
+        );
+        assert_eq!(decompressed, data);
+    }
+
+    #[test]
+    fn block() {
+        roundtrip(&[]);
+        roundtrip(b"a");
+        roundtrip(b"abcd");
+        roundtrip(&[1; 1000]);
+        // literals of every length after matches of every kind
+        let data = (0..100_000u32)
+            .flat_map(|x| {
+                let run = (x % 23) as usize;
+                let value = (x.wrapping_mul(2654435761) >> 24) as u8;
+                std::iter::repeat_n(value, run).chain(x.to_le_bytes())
+            })
+            .collect::<Vec<_>>();
+        roundtrip(&data);
+    }
+
+    #[test]
+    fn decode() {
+        // 3 literals followed by an overlapping `M3` match of 9 bytes at distance 3
+        let stream = [20, b'a', b'b', b'c', 32 | 7, 2 << 2, 0, 17, 0, 0];
+        let mut decompressed = vec![0; 12];
+        decompress(&stream, &mut decompressed).unwrap();
+        assert_eq!(decompressed, b"abcabcabcabc");
+    }
+
+    #[test]
+    fn hadoop() {
+        let data = (0..1_000_000u32)
+            .map(|x| (x % 251) as u8)
+            .collect::<Vec<_>>();
+        let mut compressed = vec![];
+        compress(&data, &mut compressed);
+        assert!(compressed.len() < data.len() / 10);
+
+        let mut decompressed = vec![0; data.len()];
+        decompress(&compressed, &mut decompressed).unwrap();
+        assert_eq!(decompressed, data);
+
+        // errors of a Hadoop-framed input are not hidden by decoding it as a LZO1X stream
+        let error = decompress(&compressed[..compressed.len() - 1], &mut decompressed);
+        assert!(
+            matches!(&error, Err(Error::OutOfSpec(message)) if message.contains("Hadoop")),
+            "{error:?}"
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        let mut compressed = vec![];
+        compress_block(b"abcabcabcabcabcabc", &mut compressed);
+        let mut decompressed = vec![0; 18];
+        for length in 0..compressed.len() {
+            assert!(decompress(&compressed[..length], &mut decompressed).is_err());
+        }
+        // a match before the start of the stream
+        assert!(decompress(
+            &[20, b'a', b'b', b'c', 32 | 7, 9 << 2, 0, 17, 0, 0],
+            &mut decompressed
+        )
+        .is_err());
+        // a stream larger than the output
+        let mut decompressed = vec![0; 17];
+        assert!(decompress(&compressed, &mut decompressed).is_err());
+    }
+}
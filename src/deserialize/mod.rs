@@ -4,6 +4,7 @@ mod filtered_rle;
 mod fixed_len;
 mod hybrid_rle;
 mod native;
+mod nested;
 mod utils;
 
 pub use binary::*;
@@ -12,4 +13,5 @@ pub use filtered_rle::*;
 pub use fixed_len::*;
 pub use hybrid_rle::*;
 pub use native::*;
+pub use nested::{Nested, NestedDecoder};
 pub use utils::{DefLevelsDecoder, OptionalValues, SliceFilteredIter};
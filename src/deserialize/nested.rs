@@ -0,0 +1,382 @@
+use crate::{
+    encoding::hybrid_rle::HybridRleDecoder,
+    error::{Error, Result},
+    metadata::Descriptor,
+    page::{split_buffer, DataPage},
+    read::levels::get_bit_width,
+    schema::Repetition,
+};
+
+/// The items of a field of the path of a nested column, as assembled from the repetition and
+/// definition levels of the column.
+///
+/// Every field has one item per item of its parent field (or per row for the first field of
+/// the path), except for the fields within a repeated field, which have one item per element
+/// of the lists of the repeated field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Nested {
+    /// A field that is not repeated, e.g. a struct, the group of a list or map or the leaf.
+    /// An item is valid unless the field, or one of its parents, is null. `is_optional` is
+    /// whether the field itself may be null.
+    Field {
+        is_optional: bool,
+        validity: Vec<bool>,
+    },
+    /// A repeated field, e.g. the elements of a list or the key-values of a map. Item `i` is
+    /// the list of the items `offsets[i]..offsets[i + 1]` of the next field of the path or, when
+    /// this field is the leaf, of the values of the column. The lists of null parents are empty.
+    Repeated { offsets: Vec<usize> },
+}
+
+impl Nested {
+    /// The number of items of the field
+    pub fn len(&self) -> usize {
+        match self {
+            Nested::Field { validity, .. } => validity.len(),
+            Nested::Repeated { offsets } => offsets.len() - 1,
+        }
+    }
+
+    /// Whether the field has no items
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Field {
+    /// the definition level from which this field is defined
+    def_level: u32,
+    /// the number of repeated fields before this field
+    rep_level: u32,
+    /// the definition level from which the closest repeated field before this one has elements
+    parent_def_level: u32,
+}
+
+/// Assembles the repetition and definition levels of a nested column into the [`Nested`]
+/// items of each field of its path (Dremel's record assembly), so that the values of the
+/// column can be placed in their lists, maps and structs.
+///
+/// Levels are pushed in order, from one or more pages of a column chunk: rows may span pages.
+/// The values of the column are the levels whose definition level is the maximum definition
+/// level; they are valid items of the leaf or, when the leaf is repeated, its elements.
+#[derive(Debug)]
+pub struct NestedDecoder {
+    fields: Vec<Field>,
+    nested: Vec<Nested>,
+    /// the definition level from which the `i`th repeated field has elements
+    list_def_levels: Vec<u32>,
+    max_rep_level: u32,
+    max_def_level: u32,
+    previous_def_level: u32,
+    num_rows: usize,
+    num_values: usize,
+}
+
+impl NestedDecoder {
+    /// Returns a new [`NestedDecoder`] of a column described by `descriptor` whose path has
+    /// fields of `repetitions` (see [`ColumnDescriptor::repetitions`](crate::metadata::ColumnDescriptor::repetitions)).
+    /// # Errors
+    /// Errors if `repetitions` is empty or does not match the maximum levels of `descriptor`.
+    pub fn try_new(descriptor: &Descriptor, repetitions: &[Repetition]) -> Result<Self> {
+        let mut fields = Vec::with_capacity(repetitions.len());
+        let mut nested = Vec::with_capacity(repetitions.len());
+        let mut list_def_levels = vec![];
+        let mut def_level = 0;
+        let mut parent_def_level = 0;
+        for repetition in repetitions {
+            let rep_level = list_def_levels.len() as u32;
+            match repetition {
+                Repetition::Required => {}
+                Repetition::Optional => def_level += 1,
+                Repetition::Repeated => {
+                    def_level += 1;
+                    list_def_levels.push(def_level);
+                }
+            }
+            fields.push(Field {
+                def_level,
+                rep_level,
+                parent_def_level,
+            });
+            nested.push(match repetition {
+                Repetition::Repeated => {
+                    parent_def_level = def_level;
+                    Nested::Repeated { offsets: vec![0] }
+                }
+                _ => Nested::Field {
+                    is_optional: *repetition == Repetition::Optional,
+                    validity: vec![],
+                },
+            });
+        }
+
+        if fields.is_empty()
+            || def_level != descriptor.max_def_level as u32
+            || list_def_levels.len() != descriptor.max_rep_level as usize
+        {
+            return Err(Error::InvalidParameter(format!(
+                "The repetitions {:?} do not match the maximum levels ({}, {}) of the column",
+                repetitions, descriptor.max_rep_level, descriptor.max_def_level
+            )));
+        }
+
+        Ok(Self {
+            fields,
+            nested,
+            list_def_levels,
+            max_rep_level: descriptor.max_rep_level as u32,
+            max_def_level: descriptor.max_def_level as u32,
+            previous_def_level: 0,
+            num_rows: 0,
+            num_values: 0,
+        })
+    }
+
+    /// Pushes the repetition level `rep` and definition level `def` of the next item.
+    /// # Errors
+    /// Errors if the levels are larger than the maximum levels of the column or if they do not
+    /// follow the previous levels, e.g. when the first level does not start a row.
+    pub fn push(&mut self, rep: u32, def: u32) -> Result<()> {
+        if rep > self.max_rep_level || def > self.max_def_level {
+            return Err(Error::OutOfSpec(format!(
+                "The levels ({}, {}) are larger than the maximum levels of the column ({}, {})",
+                rep, def, self.max_rep_level, self.max_def_level
+            )));
+        }
+        if rep == 0 {
+            self.num_rows += 1;
+        } else if self.num_rows == 0 {
+            return Err(Error::OutOfSpec(
+                "The first level of a column chunk must start a row".to_string(),
+            ));
+        } else {
+            // SPEC: a repetition level `rep` adds an element to the `rep`th repeated field,
+            // which must thus have elements
+            let list_def_level = self.list_def_levels[rep as usize - 1];
+            if def < list_def_level || self.previous_def_level < list_def_level {
+                return Err(Error::OutOfSpec(format!(
+                    "The levels ({}, {}) repeat a list that is null or empty",
+                    rep, def
+                )));
+            }
+        }
+        self.previous_def_level = def;
+
+        for i in 0..self.fields.len() {
+            let field = self.fields[i];
+            // the field has a new item when the levels do not repeat one of its parents and
+            // the closest repeated parent has elements
+            if rep > field.rep_level || def < field.parent_def_level {
+                continue;
+            }
+            if i > 0 {
+                if let Nested::Repeated { offsets } = &mut self.nested[i - 1] {
+                    *offsets.last_mut().unwrap() += 1;
+                }
+            }
+            match &mut self.nested[i] {
+                Nested::Field { validity, .. } => validity.push(def >= field.def_level),
+                Nested::Repeated { offsets } => offsets.push(*offsets.last().unwrap()),
+            }
+        }
+
+        if def == self.max_def_level {
+            if let Some(Nested::Repeated { offsets }) = self.nested.last_mut() {
+                *offsets.last_mut().unwrap() += 1;
+            }
+            self.num_values += 1;
+        }
+        Ok(())
+    }
+
+    /// Pushes the levels of `page`.
+    /// # Errors
+    /// Errors if the levels of the page can't be decoded or are invalid (see [`Self::push`]).
+    pub fn push_page(&mut self, page: &DataPage) -> Result<()> {
+        let (rep_levels, def_levels, _) = split_buffer(page)?;
+        let length = page.num_values();
+
+        let max_rep_level = page.descriptor.max_rep_level;
+        let max_def_level = page.descriptor.max_def_level;
+        let mut rep_levels = (max_rep_level > 0)
+            .then(|| HybridRleDecoder::new(rep_levels, get_bit_width(max_rep_level), length));
+        let mut def_levels = (max_def_level > 0)
+            .then(|| HybridRleDecoder::new(def_levels, get_bit_width(max_def_level), length));
+
+        for _ in 0..length {
+            let rep = rep_levels.as_mut().map_or(Some(0), |x| x.next());
+            let def = def_levels.as_mut().map_or(Some(0), |x| x.next());
+            match (rep, def) {
+                (Some(rep), Some(def)) => self.push(rep, def)?,
+                _ => {
+                    return Err(Error::OutOfSpec(
+                        "The page has fewer levels than values".to_string(),
+                    ))
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The number of rows pushed
+    pub fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+
+    /// The number of values pushed, i.e. the number of levels with the maximum definition level
+    pub fn num_values(&self) -> usize {
+        self.num_values
+    }
+
+    /// Returns the [`Nested`] items of each field of the path of the column.
+    pub fn finish(self) -> Vec<Nested> {
+        self.nested
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::schema::types::{PhysicalType, PrimitiveType};
+
+    use Repetition::*;
+
+    fn decode(repetitions: &[Repetition], rep: &[u32], def: &[u32]) -> Result<Vec<Nested>> {
+        let descriptor = Descriptor {
+            primitive_type: PrimitiveType::from_physical("a".to_string(), PhysicalType::Int32),
+            max_def_level: repetitions.iter().filter(|x| **x != Required).count() as i16,
+            max_rep_level: repetitions.iter().filter(|x| **x == Repeated).count() as i16,
+        };
+        let mut decoder = NestedDecoder::try_new(&descriptor, repetitions)?;
+        for (rep, def) in rep.iter().zip(def.iter()) {
+            decoder.push(*rep, *def)?;
+        }
+        Ok(decoder.finish())
+    }
+
+    fn field(is_optional: bool, validity: &[bool]) -> Nested {
+        Nested::Field {
+            is_optional,
+            validity: validity.to_vec(),
+        }
+    }
+
+    fn repeated(offsets: &[usize]) -> Nested {
+        Nested::Repeated {
+            offsets: offsets.to_vec(),
+        }
+    }
+
+    #[test]
+    fn list() -> Result<()> {
+        // optional group a (LIST) { repeated group list { optional int32 element; } }
+        // [[1, null], null, [], [2]]
+        let nested = decode(
+            &[Optional, Repeated, Optional],
+            &[0, 1, 0, 0, 0],
+            &[3, 2, 0, 1, 3],
+        )?;
+        assert_eq!(
+            nested,
+            vec![
+                field(true, &[true, false, true, true]),
+                repeated(&[0, 2, 2, 2, 3]),
+                field(true, &[true, false, true]),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn list_of_lists() -> Result<()> {
+        // required group a (LIST) { repeated group list { optional group element (LIST) {
+        //   repeated group list { required int32 element; } } } }
+        // [[[1, 2], [3]], [null, []], []]
+        let nested = decode(
+            &[Required, Repeated, Optional, Repeated, Required],
+            &[0, 2, 1, 0, 1, 0],
+            &[3, 3, 3, 1, 2, 0],
+        )?;
+        assert_eq!(
+            nested,
+            vec![
+                field(false, &[true, true, true]),
+                repeated(&[0, 2, 4, 4]),
+                field(true, &[true, true, false, true]),
+                repeated(&[0, 2, 3, 3, 3]),
+                field(false, &[true, true, true]),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn map() -> Result<()> {
+        // optional group a (MAP) { repeated group key_value { required binary key;
+        //   optional int32 value; } }, the column of the values
+        // [{"a": 1, "b": null}, null, {"c": 2}]
+        let nested = decode(
+            &[Optional, Repeated, Optional],
+            &[0, 1, 0, 0],
+            &[3, 2, 0, 3],
+        )?;
+        assert_eq!(
+            nested,
+            vec![
+                field(true, &[true, false, true]),
+                repeated(&[0, 2, 2, 3]),
+                field(true, &[true, false, true]),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn struct_() -> Result<()> {
+        // optional group a { required int32 b; }
+        // [{b: 1}, null, {b: 2}]: the items of null structs are not valid
+        let nested = decode(&[Optional, Required], &[0, 0, 0], &[1, 0, 1])?;
+        assert_eq!(
+            nested,
+            vec![
+                field(true, &[true, false, true]),
+                field(false, &[true, false, true]),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn repeated_leaf() -> Result<()> {
+        // optional group a { repeated int32 b; }
+        // [{b: [1, 2]}, null, {b: []}]
+        let nested = decode(&[Optional, Repeated], &[0, 1, 0, 0], &[2, 2, 0, 1])?;
+        assert_eq!(
+            nested,
+            vec![field(true, &[true, false, true]), repeated(&[0, 2, 2, 2])]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn invalid() {
+        let repetitions = [Optional, Repeated, Optional];
+        // the first level must start a row
+        assert!(decode(&repetitions, &[1], &[3]).is_err());
+        // levels larger than the maximum levels
+        assert!(decode(&repetitions, &[0, 2], &[3, 3]).is_err());
+        assert!(decode(&repetitions, &[0], &[4]).is_err());
+        // repeating an empty list
+        assert!(decode(&repetitions, &[0, 1], &[1, 3]).is_err());
+        // repetitions that do not match the levels
+        let descriptor = Descriptor {
+            primitive_type: PrimitiveType::from_physical("a".to_string(), PhysicalType::Int32),
+            max_def_level: 3,
+            max_rep_level: 1,
+        };
+        assert!(NestedDecoder::try_new(&descriptor, &[Optional, Optional, Optional]).is_err());
+        assert!(NestedDecoder::try_new(&descriptor, &[]).is_err());
+    }
+}
@@ -18,8 +18,7 @@ pub fn encode(decompressed: &[u32], num_bits: u8, compressed: &mut [u8]) -> usiz
     let size = ceil8(BitPacker1x::BLOCK_LEN * num_bits as usize);
     if !remainder.is_empty() {
         let mut last_chunk = remainder.to_vec();
-        let trailing = BitPacker1x::BLOCK_LEN - remainder.len();
-        last_chunk.extend(std::iter::repeat(0).take(trailing));
+        last_chunk.resize(BitPacker1x::BLOCK_LEN, 0);
 
         let mut compressed_len = 0;
         chunks
@@ -116,6 +115,96 @@ impl<'a> Iterator for Decoder<'a> {
     }
 }
 
+/// Encodes `u64` values into a buffer using `num_bits`, between 0 and 64, returning the number
+/// of bytes written.
+///
+/// Values are packed from the least significant bit, as [`encode`] does, and `compressed`
+/// must hold at least `ceil8(decompressed.len() * num_bits)` bytes.
+pub fn encode_u64(decompressed: &[u64], num_bits: u8, compressed: &mut [u8]) -> usize {
+    let num_bits = num_bits as usize;
+    let compressed_len = ceil8(decompressed.len() * num_bits);
+    let compressed = &mut compressed[..compressed_len];
+    compressed.iter_mut().for_each(|x| *x = 0);
+    if num_bits == 0 {
+        return 0;
+    }
+
+    let mask = u64::MAX >> (64 - num_bits);
+    decompressed.iter().enumerate().for_each(|(i, value)| {
+        let offset = i * num_bits;
+        let mut value = ((value & mask) as u128) << (offset % 8);
+        compressed[offset / 8..]
+            .iter_mut()
+            .take(ceil8(offset % 8 + num_bits))
+            .for_each(|byte| {
+                *byte |= value as u8;
+                value >>= 8;
+            });
+    });
+    compressed_len
+}
+
+/// Decoder of `u64` values bitpacked with `num_bits`, between 0 and 64, see [`encode_u64`].
+#[derive(Debug, Clone)]
+pub struct Decoder64<'a> {
+    compressed: &'a [u8],
+    num_bits: usize,
+    mask: u64,
+    index: usize,
+    remaining: usize,
+}
+
+impl<'a> Decoder64<'a> {
+    /// Returns a new [`Decoder64`] of `length` values. Values not in `compressed` are zero.
+    /// # Panics
+    /// Panics if `num_bits` is larger than 64.
+    pub fn new(compressed: &'a [u8], num_bits: u8, length: usize) -> Self {
+        assert!(
+            num_bits <= 64,
+            "Values can't be bitpacked with more than 64 bits"
+        );
+        let num_bits = num_bits as usize;
+        Self {
+            compressed,
+            num_bits,
+            mask: if num_bits == 0 {
+                0
+            } else {
+                u64::MAX >> (64 - num_bits)
+            },
+            index: 0,
+            remaining: length,
+        }
+    }
+}
+
+impl<'a> Iterator for Decoder64<'a> {
+    type Item = u64;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let offset = self.index * self.num_bits;
+        let start = (offset / 8).min(self.compressed.len());
+        let end = (start + ceil8(offset % 8 + self.num_bits)).min(self.compressed.len());
+
+        let mut bytes = [0u8; 16];
+        bytes[..end - start].copy_from_slice(&self.compressed[start..end]);
+        let value = (u128::from_le_bytes(bytes) >> (offset % 8)) as u64 & self.mask;
+
+        self.index += 1;
+        self.remaining -= 1;
+        Some(value)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -254,4 +343,40 @@ mod tests {
         let decoded = Decoder::new(&data, num_bits, length).collect::<Vec<_>>();
         assert_eq!(decoded, expected);
     }
+
+    #[test]
+    fn u64_matches_u32() {
+        let data = (0..100u32).map(|x| x * 7 % 32).collect::<Vec<_>>();
+        let mut expected = vec![0u8; 4 * 4 * BitPacker1x::BLOCK_LEN];
+        encode(&data, 5, &mut expected);
+        expected.truncate(ceil8(100 * 5));
+
+        let data = data.into_iter().map(|x| x as u64).collect::<Vec<_>>();
+        let mut compressed = vec![0u8; ceil8(100 * 5)];
+        assert_eq!(encode_u64(&data, 5, &mut compressed), compressed.len());
+        assert_eq!(compressed, expected);
+        assert_eq!(
+            Decoder64::new(&compressed, 5, data.len()).collect::<Vec<_>>(),
+            data
+        );
+    }
+
+    #[test]
+    fn u64_all_widths() {
+        for num_bits in 0..=64u8 {
+            let mask = if num_bits == 0 {
+                0
+            } else {
+                u64::MAX >> (64 - num_bits)
+            };
+            let data = (0..67u64)
+                .map(|x| x.wrapping_mul(0x9E37_79B9_7F4A_7C15) & mask)
+                .collect::<Vec<_>>();
+
+            let mut compressed = vec![0u8; ceil8(data.len() * num_bits as usize)];
+            encode_u64(&data, num_bits, &mut compressed);
+            let decoded = Decoder64::new(&compressed, num_bits, data.len()).collect::<Vec<_>>();
+            assert_eq!(decoded, data, "{}", num_bits);
+        }
+    }
 }
@@ -0,0 +1,84 @@
+use std::marker::PhantomData;
+
+use crate::error::{Error, Result};
+use crate::types::{decode, NativeType};
+
+/// Decoder of parquets' `BYTE_STREAM_SPLIT` of [`NativeType`]. Implements `Iterator<Item = T>`.
+/// # Implementation
+/// This struct does not allocate on the heap.
+#[derive(Debug, Clone)]
+pub struct Decoder<'a, T: NativeType> {
+    values: &'a [u8],
+    length: usize,
+    index: usize,
+    phantom: PhantomData<T>,
+}
+
+impl<'a, T: NativeType> Decoder<'a, T> {
+    /// Returns a new [`Decoder`] over the `BYTE_STREAM_SPLIT`-encoded `values`.
+    /// # Error
+    /// Errors iff the length of `values` is not a multiple of the size of `T`.
+    pub fn try_new(values: &'a [u8]) -> Result<Self> {
+        let size = std::mem::size_of::<T>();
+        if !values.len().is_multiple_of(size) {
+            return Err(Error::OutOfSpec(
+                "A byte stream split page data's len must be a multiple of the type".to_string(),
+            ));
+        }
+        Ok(Self {
+            values,
+            length: values.len() / size,
+            index: 0,
+            phantom: PhantomData,
+        })
+    }
+}
+
+impl<'a, T: NativeType> Iterator for Decoder<'a, T> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index == self.length {
+            return None;
+        }
+        let size = std::mem::size_of::<T>();
+        // the largest native type (int96) has 12 bytes
+        let mut bytes = [0u8; 16];
+        bytes[..size]
+            .iter_mut()
+            .enumerate()
+            .for_each(|(stream, byte)| *byte = self.values[stream * self.length + self.index]);
+        self.index += 1;
+
+        Some(decode(&bytes[..size]))
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.index = std::cmp::min(self.index.saturating_add(n), self.length);
+        self.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.length - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+/// Decodes `BYTE_STREAM_SPLIT`-encoded `values` of `size` bytes each (e.g. `FIXED_LEN_BYTE_ARRAY`),
+/// extending `buffer` with the concatenated values.
+/// # Error
+/// Errors iff `size == 0` or the length of `values` is not a multiple of `size`.
+pub fn decode_fixed_len(values: &[u8], size: usize, buffer: &mut Vec<u8>) -> Result<()> {
+    if size == 0 || !values.len().is_multiple_of(size) {
+        return Err(Error::OutOfSpec(
+            "A byte stream split page data's len must be a multiple of the type".to_string(),
+        ));
+    }
+    let length = values.len() / size;
+    buffer.reserve(values.len());
+    (0..length).for_each(|i| buffer.extend((0..size).map(|stream| values[stream * length + i])));
+    Ok(())
+}
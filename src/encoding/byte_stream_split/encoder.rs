@@ -0,0 +1,48 @@
+use crate::error::{Error, Result};
+use crate::types::NativeType;
+
+/// Encodes a slice of [`NativeType`] according to parquet's `BYTE_STREAM_SPLIT` into `buffer`.
+/// # Implementation
+/// This function does not allocate besides extending `buffer`.
+pub fn encode<T: NativeType>(values: &[T], buffer: &mut Vec<u8>) {
+    let start = buffer.len();
+    buffer.resize(start + std::mem::size_of_val(values), 0);
+    let streams = &mut buffer[start..];
+
+    values.iter().enumerate().for_each(|(i, value)| {
+        let bytes = value.to_le_bytes();
+        bytes
+            .as_ref()
+            .iter()
+            .enumerate()
+            .for_each(|(stream, byte)| {
+                streams[stream * values.len() + i] = *byte;
+            });
+    });
+}
+
+/// Encodes a sequence of concatenated values of `size` bytes each (e.g. `FIXED_LEN_BYTE_ARRAY`)
+/// according to parquet's `BYTE_STREAM_SPLIT` into `buffer`.
+/// # Error
+/// Errors iff `size == 0` or the length of `values` is not a multiple of `size`.
+pub fn encode_fixed_len(values: &[u8], size: usize, buffer: &mut Vec<u8>) -> Result<()> {
+    if size == 0 || !values.len().is_multiple_of(size) {
+        return Err(Error::InvalidParameter(
+            "The length of the values to encode must be a multiple of their size".to_string(),
+        ));
+    }
+    let length = values.len() / size;
+    let start = buffer.len();
+    buffer.resize(start + values.len(), 0);
+    let streams = &mut buffer[start..];
+
+    values
+        .chunks_exact(size)
+        .enumerate()
+        .for_each(|(i, value)| {
+            value.iter().enumerate().for_each(|(stream, byte)| {
+                streams[stream * length + i] = *byte;
+            });
+        });
+    Ok(())
+}
@@ -0,0 +1,94 @@
+//! Implementation of parquet's `BYTE_STREAM_SPLIT` encoding, where the k-th byte of every value
+//! is placed on the k-th stream, and streams are concatenated.
+mod decoder;
+mod encoder;
+
+pub use decoder::{decode_fixed_len, Decoder};
+pub use encoder::{encode, encode_fixed_len};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn basic() {
+        let data = vec![1.0f32, 2.0, 3.0];
+
+        let mut buffer = vec![];
+        encode(&data, &mut buffer);
+
+        // 1.0 = [0, 0, 128, 63], 2.0 = [0, 0, 0, 64], 3.0 = [0, 0, 64, 64]
+        let expected = vec![0u8, 0, 0, 0, 0, 0, 128, 0, 64, 63, 64, 64];
+        assert_eq!(buffer, expected);
+
+        let result = Decoder::<f32>::try_new(&buffer)
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert_eq!(result, data);
+    }
+
+    #[test]
+    fn f64_round_trip() {
+        let data = (0..1000).map(|x| x as f64 * 0.1).collect::<Vec<_>>();
+
+        let mut buffer = vec![];
+        encode(&data, &mut buffer);
+
+        let result = Decoder::<f64>::try_new(&buffer)
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert_eq!(result, data);
+    }
+
+    #[test]
+    fn ints_round_trip() {
+        let data = vec![i32::MIN, -1, 0, 1, i32::MAX];
+        let mut buffer = vec![];
+        encode(&data, &mut buffer);
+        let result = Decoder::<i32>::try_new(&buffer)
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert_eq!(result, data);
+
+        let data = vec![i64::MIN, -1, 0, 1, i64::MAX];
+        let mut buffer = vec![];
+        encode(&data, &mut buffer);
+        let result = Decoder::<i64>::try_new(&buffer)
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert_eq!(result, data);
+    }
+
+    #[test]
+    fn nth() {
+        let data = vec![1i32, 2, 3, 4];
+        let mut buffer = vec![];
+        encode(&data, &mut buffer);
+
+        let mut decoder = Decoder::<i32>::try_new(&buffer).unwrap();
+        assert_eq!(decoder.nth(2), Some(3));
+        assert_eq!(decoder.size_hint(), (1, Some(1)));
+        assert_eq!(decoder.nth(1), None);
+    }
+
+    #[test]
+    fn fixed_len() {
+        let data = b"aabbccdd";
+
+        let mut buffer = vec![];
+        encode_fixed_len(data, 2, &mut buffer).unwrap();
+        assert_eq!(buffer, b"abcdabcd");
+
+        let mut result = vec![];
+        decode_fixed_len(&buffer, 2, &mut result).unwrap();
+        assert_eq!(result, data);
+    }
+
+    #[test]
+    fn invalid_length() {
+        assert!(Decoder::<f64>::try_new(&[0; 7]).is_err());
+        assert!(decode_fixed_len(&[0; 7], 2, &mut vec![]).is_err());
+        assert!(encode_fixed_len(&[0; 7], 2, &mut vec![]).is_err());
+        assert!(encode_fixed_len(&[0; 8], 0, &mut vec![]).is_err());
+    }
+}
@@ -4,6 +4,32 @@ use super::super::bitpacking;
 use super::super::uleb128;
 use super::super::zigzag_leb128;
 
+/// The relative deltas of a miniblock. Those of up to 32 bits are decoded with the faster
+/// `u32` decoder.
+#[derive(Debug)]
+enum MiniBlock<'a> {
+    Narrow(bitpacking::Decoder<'a>),
+    Wide(bitpacking::Decoder64<'a>),
+}
+
+impl<'a> MiniBlock<'a> {
+    fn new(values: &'a [u8], num_bits: u8, length: usize) -> Self {
+        if num_bits <= 32 {
+            Self::Narrow(bitpacking::Decoder::new(values, num_bits, length))
+        } else {
+            Self::Wide(bitpacking::Decoder64::new(values, num_bits, length))
+        }
+    }
+
+    #[inline]
+    fn next(&mut self) -> Option<u64> {
+        match self {
+            Self::Narrow(decoder) => decoder.next().map(|x| x as u64),
+            Self::Wide(decoder) => decoder.next(),
+        }
+    }
+}
+
 #[derive(Debug)]
 struct Block<'a> {
     // this is the minimum delta that must be added to every value.
@@ -15,7 +41,7 @@ struct Block<'a> {
     remaining: usize,     // number of elements
     current_index: usize, // invariant: < values_per_mini_block
     // None represents a relative delta of zero, in which case there is no miniblock.
-    current_miniblock: Option<bitpacking::Decoder<'a>>,
+    current_miniblock: Option<MiniBlock<'a>>,
     // number of bytes consumed.
     consumed_bytes: usize,
 }
@@ -69,7 +95,7 @@ impl<'a> Block<'a> {
             self.values = remainder;
             self.consumed_bytes += miniblock_length;
 
-            Some(bitpacking::Decoder::new(miniblock, num_bits, length))
+            Some(MiniBlock::new(miniblock, num_bits, length))
         } else {
             None
         };
@@ -84,12 +110,13 @@ impl<'a> Iterator for Block<'a> {
         if self.remaining == 0 {
             return None;
         }
-        let result = self.min_delta
-            + self
-                .current_miniblock
+        // SPEC: deltas use two's complement (wrapping) arithmetic
+        let result = self.min_delta.wrapping_add(
+            self.current_miniblock
                 .as_mut()
                 .map(|x| x.next().unwrap())
-                .unwrap_or(0) as i64;
+                .unwrap_or(0) as i64,
+        );
         self.current_index += 1;
         self.remaining -= 1;
 
@@ -204,7 +231,7 @@ impl<'a> Iterator for Decoder<'a> {
             delta
         };
 
-        self.next_value += delta;
+        self.next_value = self.next_value.wrapping_add(delta);
         result
     }
 
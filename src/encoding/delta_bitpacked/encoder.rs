@@ -1,73 +1,186 @@
 use crate::encoding::ceil8;
-use crate::read::levels::get_bit_width;
+use crate::error::{Error, Result};
 
 use super::super::bitpacking;
 use super::super::uleb128;
 use super::super::zigzag_leb128;
 
-/// Encodes an iterator of `i32` according to parquet's `DELTA_BINARY_PACKED`.
+/// The layout of the blocks of parquet's `DELTA_BINARY_PACKED`: the number of values per
+/// block and the number of mini-blocks per block, each with its own bit width.
+///
+/// The default, 128 values in 4 mini-blocks of 32 values, is the one of parquet-mr.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BlockLayout {
+    block_size: usize,
+    num_mini_blocks: usize,
+}
+
+impl BlockLayout {
+    /// Returns a new [`BlockLayout`] of blocks of `block_size` values in `num_mini_blocks`
+    /// mini-blocks.
+    /// # Errors
+    /// Errors if `block_size` is not a positive multiple of 128 or if the number of values
+    /// per mini-block is not a positive multiple of 32, as required by the specification.
+    pub fn try_new(block_size: usize, num_mini_blocks: usize) -> Result<Self> {
+        if block_size == 0 || !block_size.is_multiple_of(128) {
+            return Err(Error::InvalidParameter(format!(
+                "The block size of DELTA_BINARY_PACKED must be a positive multiple of 128, got {}",
+                block_size
+            )));
+        }
+        if num_mini_blocks == 0
+            || !block_size.is_multiple_of(num_mini_blocks)
+            || !(block_size / num_mini_blocks).is_multiple_of(32)
+        {
+            return Err(Error::InvalidParameter(format!(
+                "The {} values of a block of DELTA_BINARY_PACKED can't be split in {} mini-blocks of a multiple of 32 values",
+                block_size, num_mini_blocks
+            )));
+        }
+        Ok(Self {
+            block_size,
+            num_mini_blocks,
+        })
+    }
+
+    /// The number of values per block
+    pub fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    /// The number of mini-blocks per block
+    pub fn num_mini_blocks(&self) -> usize {
+        self.num_mini_blocks
+    }
+
+    /// The number of values per mini-block
+    pub fn values_per_mini_block(&self) -> usize {
+        self.block_size / self.num_mini_blocks
+    }
+}
+
+impl Default for BlockLayout {
+    fn default() -> Self {
+        Self {
+            block_size: 128,
+            num_mini_blocks: 4,
+        }
+    }
+}
+
+/// Encodes an iterator of `i64` according to parquet's `DELTA_BINARY_PACKED`, with the
+/// default [`BlockLayout`].
+pub fn encode<I: Iterator<Item = i64>>(iterator: I, buffer: &mut Vec<u8>) {
+    encode_with_layout(iterator, buffer, BlockLayout::default())
+}
+
+/// Encodes an iterator of `i64` according to parquet's `DELTA_BINARY_PACKED`, with blocks of
+/// `layout`.
 /// # Implementation
-/// * This function does not allocate on the heap.
-/// * The number of mini-blocks is always 1. This may change in the future.
-pub fn encode<I: Iterator<Item = i64>>(mut iterator: I, buffer: &mut Vec<u8>) {
-    let block_size = 128;
-    let mini_blocks = 1;
+/// * This function allocates two buffers of one block.
+/// * Each mini-block is bitpacked with the bit width of its own largest delta.
+pub fn encode_with_layout<I: Iterator<Item = i64>>(
+    iterator: I,
+    buffer: &mut Vec<u8>,
+    layout: BlockLayout,
+) {
+    encode_impl(iterator, buffer, layout, false)
+}
+
+/// Encodes an iterator of `i32` according to parquet's `DELTA_BINARY_PACKED`, with blocks of
+/// `layout`. Deltas are computed in 32 bits, so that mini-blocks are at most 32 bits wide,
+/// as required by readers of `INT32` columns.
+pub fn encode_i32_with_layout<I: Iterator<Item = i32>>(
+    iterator: I,
+    buffer: &mut Vec<u8>,
+    layout: BlockLayout,
+) {
+    encode_impl(iterator.map(|x| x as i64), buffer, layout, true)
+}
+
+fn encode_impl<I: Iterator<Item = i64>>(
+    mut iterator: I,
+    buffer: &mut Vec<u8>,
+    layout: BlockLayout,
+    is_i32: bool,
+) {
+    // SPEC: deltas are computed with two's complement (wrapping) arithmetic of the width of
+    // the physical type, so that they fit in its width for any two values
+    let sub = |lhs: i64, rhs: i64| {
+        if is_i32 {
+            (lhs as i32).wrapping_sub(rhs as i32) as i64
+        } else {
+            lhs.wrapping_sub(rhs)
+        }
+    };
+    let unsigned = |x: i64| {
+        if is_i32 {
+            x as u32 as u64
+        } else {
+            x as u64
+        }
+    };
 
     let mut container = [0u8; 10];
-    let encoded_len = uleb128::encode(block_size, &mut container);
+    let encoded_len = uleb128::encode(layout.block_size as u64, &mut container);
     buffer.extend_from_slice(&container[..encoded_len]);
 
-    let encoded_len = uleb128::encode(mini_blocks, &mut container);
+    let encoded_len = uleb128::encode(layout.num_mini_blocks as u64, &mut container);
     buffer.extend_from_slice(&container[..encoded_len]);
 
     let length = iterator.size_hint().1.unwrap();
     let encoded_len = uleb128::encode(length as u64, &mut container);
     buffer.extend_from_slice(&container[..encoded_len]);
 
-    let mut values = [0i64; 128];
-    let mut deltas = [0u32; 128];
+    let values_per_mini_block = layout.values_per_mini_block();
+    let mut values = Vec::with_capacity(layout.block_size);
+    let mut deltas = vec![0u64; values_per_mini_block];
 
-    let first_value = iterator.next().unwrap();
+    // SPEC: an empty sequence is represented by the header with a first value of 0
+    let first_value = iterator.next().unwrap_or_default();
     let (container, encoded_len) = zigzag_leb128::encode(first_value);
     buffer.extend_from_slice(&container[..encoded_len]);
 
     let mut prev = first_value;
-    let mut length = iterator.size_hint().1.unwrap();
-    while length != 0 {
-        for (i, v) in (0..128).zip(&mut iterator) {
-            values[i] = v - prev;
+    loop {
+        values.clear();
+        values.extend(iterator.by_ref().take(layout.block_size).map(|v| {
+            let delta = sub(v, prev);
             prev = v;
+            delta
+        }));
+        if values.is_empty() {
+            break;
         }
-        let consumed = std::cmp::min(length - iterator.size_hint().1.unwrap(), 128);
-        let values = &values[..consumed];
 
         let min_delta = *values.iter().min().unwrap();
-        let max_delta = *values.iter().max().unwrap();
-
-        values.iter().zip(deltas.iter_mut()).for_each(|(v, d)| {
-            *d = (v - min_delta) as u32;
-        });
 
         // <min delta> <list of bitwidths of miniblocks> <miniblocks>
         let (container, encoded_len) = zigzag_leb128::encode(min_delta);
         buffer.extend_from_slice(&container[..encoded_len]);
 
-        let num_bits = get_bit_width((max_delta - min_delta) as i16) as u8;
-        buffer.push(num_bits);
+        // SPEC: the bit widths of the mini-blocks without values are present (and zero)
+        let bitwidths_start = buffer.len();
+        buffer.resize(bitwidths_start + layout.num_mini_blocks, 0);
 
-        if num_bits > 0 {
-            let start = buffer.len();
+        for (i, mini_block) in values.chunks(values_per_mini_block).enumerate() {
+            // the remaining deltas of the last mini-block are padding
+            deltas.iter_mut().for_each(|d| *d = 0);
+            mini_block.iter().zip(deltas.iter_mut()).for_each(|(v, d)| {
+                *d = unsigned(sub(*v, min_delta));
+            });
 
-            // bitpack encode all (deltas.len = 128 which is a multiple of 32)
-            let bytes_needed = start + ceil8(deltas.len() * num_bits as usize);
-            buffer.resize(bytes_needed, 0);
-            bitpacking::encode(deltas.as_ref(), num_bits, &mut buffer[start..]);
+            let max = deltas.iter().max().copied().unwrap_or_default();
+            let num_bits = (64 - max.leading_zeros()) as u8;
+            buffer[bitwidths_start + i] = num_bits;
 
-            let bytes_needed = start + ceil8(deltas.len() * num_bits as usize);
-            buffer.truncate(bytes_needed);
+            if num_bits > 0 {
+                // bitpack encode all (deltas.len is a multiple of 32)
+                let start = buffer.len();
+                buffer.resize(start + ceil8(deltas.len() * num_bits as usize), 0);
+                bitpacking::encode_u64(deltas.as_ref(), num_bits, &mut buffer[start..]);
+            }
         }
-
-        length = iterator.size_hint().1.unwrap();
     }
 }
 
@@ -75,6 +188,10 @@ pub fn encode<I: Iterator<Item = i64>>(mut iterator: I, buffer: &mut Vec<u8>) {
 mod tests {
     use super::*;
 
+    fn encode_single_mini_block<I: Iterator<Item = i64>>(iterator: I, buffer: &mut Vec<u8>) {
+        encode_with_layout(iterator, buffer, BlockLayout::try_new(128, 1).unwrap())
+    }
+
     #[test]
     fn constant_delta() {
         // header: [128, 1, 1, 5, 2]:
@@ -89,7 +206,7 @@ mod tests {
         let expected = vec![128u8, 1, 1, 5, 2, 2, 0];
 
         let mut buffer = vec![];
-        encode(data, &mut buffer);
+        encode_single_mini_block(data, &mut buffer);
         assert_eq!(expected, buffer);
     }
 
@@ -110,10 +227,85 @@ mod tests {
         //      0b00001011
         // ]
         let mut expected = vec![128u8, 1, 1, 6, 2, 7, 3, 0b01101101, 0b00001011];
-        expected.extend(std::iter::repeat(0).take(128 * 3 / 8 - 2)); // 128 values, 3 bits, 2 already used
+        expected.resize(expected.len() + 128 * 3 / 8 - 2, 0); // 128 values, 3 bits, 2 already used
+
+        let mut buffer = vec![];
+        encode_single_mini_block(data.into_iter(), &mut buffer);
+        assert_eq!(expected, buffer);
+    }
+
+    #[test]
+    fn default_layout() {
+        // same as `negative_min_delta`, with 4 mini-blocks of 32 values, as written by spark
+        let data = vec![1, 2, 3, 4, 5, 1];
+        // header: [128, 1, 4, 6, 2]
+        // block1: [7, 3, 0, 0, 0]
+        //  min_delta: -4        <=z> 7
+        //  bitwidths: [3, 0, 0, 0] (the last 3 mini-blocks have no values)
+        let mut expected = vec![128u8, 1, 4, 6, 2, 7, 3, 0, 0, 0, 0b01101101, 0b00001011];
+        expected.resize(expected.len() + 32 * 3 / 8 - 2, 0); // 32 values, 3 bits, 2 already used
 
         let mut buffer = vec![];
         encode(data.into_iter(), &mut buffer);
         assert_eq!(expected, buffer);
     }
+
+    #[test]
+    fn mini_block_bit_widths() {
+        // a gap only widens the mini-block it is in
+        let mut data = (0..129).collect::<Vec<i64>>();
+        data[40..].iter_mut().for_each(|x| *x += 1000);
+
+        let mut buffer = vec![];
+        encode(data.into_iter(), &mut buffer);
+
+        // header: [128, 1, 4, 129, 1, 0]
+        assert_eq!(buffer[..6], [128u8, 1, 4, 129, 1, 0]);
+        // block1: min_delta: 1 <=z> 2, bitwidths: [0, 10, 0, 0]
+        assert_eq!(buffer[6..11], [2, 0, 10, 0, 0]);
+        assert_eq!(buffer.len(), 11 + 32 * 10 / 8);
+    }
+
+    #[test]
+    fn full_range() {
+        // deltas between these values overflow i64 and require 64 bits
+        let data = vec![i64::MIN, i64::MAX, 0, i64::MIN, -1];
+        let mut buffer = vec![];
+        encode(data.into_iter(), &mut buffer);
+
+        // header: block size (2 bytes), mini-blocks, elements, first value (10 bytes)
+        // block1: min_delta (10 bytes), 4 bitwidths, 32 values of 64 bits
+        assert_eq!(buffer[24..28], [64, 0, 0, 0]);
+        assert_eq!(buffer.len(), 28 + 32 * 8);
+    }
+
+    #[test]
+    fn full_range_i32() {
+        // deltas between these values overflow i32 but wrap in 32 bits
+        let data = vec![i32::MIN, i32::MAX, i32::MIN, 0, i32::MAX, -1];
+        let mut buffer = vec![];
+        encode_i32_with_layout(data.iter().copied(), &mut buffer, BlockLayout::default());
+
+        // header: block size (2 bytes), mini-blocks, elements, first value (5 bytes)
+        // block1: min_delta (5 bytes), 4 bitwidths
+        let widths = &buffer[14..18];
+        assert_eq!(widths, [32, 0, 0, 0]);
+        assert_eq!(buffer.len(), 18 + 32 * 32 / 8);
+
+        let result = super::super::Decoder::new(&buffer)
+            .map(|x| x as i32)
+            .collect::<Vec<_>>();
+        assert_eq!(result, data);
+    }
+
+    #[test]
+    fn invalid_layout() {
+        assert!(BlockLayout::try_new(0, 1).is_err());
+        assert!(BlockLayout::try_new(100, 1).is_err());
+        assert!(BlockLayout::try_new(128, 0).is_err());
+        assert!(BlockLayout::try_new(128, 3).is_err());
+        // 16 values per mini-block
+        assert!(BlockLayout::try_new(128, 8).is_err());
+        assert!(BlockLayout::try_new(256, 8).is_ok());
+    }
 }
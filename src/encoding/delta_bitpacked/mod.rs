@@ -2,7 +2,7 @@ mod decoder;
 mod encoder;
 
 pub use decoder::Decoder;
-pub use encoder::encode;
+pub use encoder::{encode, encode_i32_with_layout, encode_with_layout, BlockLayout};
 
 #[cfg(test)]
 mod tests {
@@ -61,4 +61,56 @@ mod tests {
 
         assert_eq!(iter.consumed_bytes(), len);
     }
+
+    #[test]
+    fn full_range() {
+        let mut data = vec![i64::MIN, i64::MAX, 0, i64::MIN, -1, 1, i64::MAX - 1];
+        // nanosecond timestamps with large gaps
+        data.extend((0..200).map(|x: i64| 1_700_000_000_000_000_000 + (x % 7) * (1 << 40) - x));
+
+        let mut buffer = vec![];
+        encode(data.clone().into_iter(), &mut buffer);
+        let mut iter = Decoder::new(&buffer);
+
+        let result = iter.by_ref().collect::<Vec<_>>();
+        assert_eq!(result, data);
+        assert_eq!(iter.consumed_bytes(), buffer.len());
+    }
+
+    #[test]
+    fn all_widths() {
+        for num_bits in 1..=64u32 {
+            // deltas alternating between 0 and 2^(num_bits - 1)
+            let delta = 1u64.wrapping_shl(num_bits - 1) as i64;
+            let data = (0..130)
+                .scan(0i64, |acc, i| {
+                    *acc = acc.wrapping_add(if i % 2 == 0 { delta } else { 0 });
+                    Some(*acc)
+                })
+                .collect::<Vec<_>>();
+
+            let mut buffer = vec![];
+            encode(data.clone().into_iter(), &mut buffer);
+            let result = Decoder::new(&buffer).collect::<Vec<_>>();
+            assert_eq!(result, data, "{}", num_bits);
+        }
+    }
+
+    #[test]
+    fn layouts() {
+        let data = (0..1000)
+            .map(|x: i64| x * x - if x % 97 == 0 { 1 << 40 } else { 0 })
+            .collect::<Vec<_>>();
+
+        for (block_size, num_mini_blocks) in [(128, 1), (128, 4), (256, 8), (512, 4), (1024, 1)] {
+            let layout = BlockLayout::try_new(block_size, num_mini_blocks).unwrap();
+            let mut buffer = vec![];
+            encode_with_layout(data.clone().into_iter(), &mut buffer, layout);
+            let mut iter = Decoder::new(&buffer);
+
+            let result = iter.by_ref().collect::<Vec<_>>();
+            assert_eq!(result, data);
+            assert_eq!(iter.consumed_bytes(), buffer.len());
+        }
+    }
 }
@@ -0,0 +1,47 @@
+use crate::encoding::{delta_bitpacked, delta_length_byte_array};
+
+/// Encodes a clonable iterator of `&[u8]` into `buffer` according to
+/// [Delta strings](https://github.com/apache/parquet-format/blob/master/Encodings.md#delta-strings-delta_byte_array--7).
+/// # Implementation
+/// The prefix lengths are encoded via [`delta_bitpacked::encode`], followed by the suffixes
+/// encoded via [`delta_length_byte_array::encode`].
+/// This function allocates one `usize` per item to hold its prefix length.
+pub fn encode<A: AsRef<[u8]>, I: Iterator<Item = A> + Clone>(iterator: I, buffer: &mut Vec<u8>) {
+    let mut previous = vec![];
+    let prefix_lengths = iterator
+        .clone()
+        .map(|x| {
+            let x = x.as_ref();
+            let prefix_length = previous
+                .iter()
+                .zip(x.iter())
+                .take_while(|(a, b)| a == b)
+                .count();
+            previous.clear();
+            previous.extend_from_slice(x);
+            prefix_length
+        })
+        .collect::<Vec<_>>();
+
+    delta_bitpacked::encode(prefix_lengths.iter().map(|x| *x as i64), buffer);
+
+    let suffixes = iterator
+        .zip(prefix_lengths.iter())
+        .map(|(value, prefix_length)| Suffix {
+            value,
+            prefix_length: *prefix_length,
+        });
+    delta_length_byte_array::encode(suffixes, buffer)
+}
+
+/// The bytes of `value` after its first `prefix_length` bytes
+struct Suffix<A: AsRef<[u8]>> {
+    value: A,
+    prefix_length: usize,
+}
+
+impl<A: AsRef<[u8]>> AsRef<[u8]> for Suffix<A> {
+    fn as_ref(&self) -> &[u8] {
+        &self.value.as_ref()[self.prefix_length..]
+    }
+}
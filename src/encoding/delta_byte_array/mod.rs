@@ -1,3 +1,109 @@
 mod decoder;
+mod encoder;
 
 pub use decoder::Decoder;
+pub use encoder::encode;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// decodes `data` into the original values
+    fn decode(data: &[u8]) -> Vec<Vec<u8>> {
+        let mut decoder = Decoder::new(data);
+        let prefixes = decoder.by_ref().collect::<Vec<_>>();
+
+        let mut decoder = decoder.into_lengths();
+        let lengths = decoder.by_ref().collect::<Vec<_>>();
+        let mut suffixes = decoder.values();
+
+        let mut previous: Vec<u8> = vec![];
+        prefixes
+            .into_iter()
+            .zip(lengths)
+            .map(|(prefix, length)| {
+                let (suffix, remaining) = suffixes.split_at(length as usize);
+                suffixes = remaining;
+                let mut value = previous[..prefix as usize].to_vec();
+                value.extend_from_slice(suffix);
+                previous = value.clone();
+                value
+            })
+            .collect()
+    }
+
+    #[test]
+    fn basic() {
+        let data = ["Hello", "World"];
+
+        let mut buffer = vec![];
+        encode(data.iter().map(|x| x.as_bytes()), &mut buffer);
+
+        // prefix lengths: [0, 0]
+        //  header: [128, 1, 4, 2, 0] (block size 128, 4 mini-blocks, 2 values, first value 0)
+        //  block: [0, 0, 0, 0, 0] (min delta 0, bitwidths 0)
+        // suffix lengths: [5, 5]
+        //  header: [128, 1, 4, 2, 10] (block size 128, 4 mini-blocks, 2 values, first value 5)
+        //  block: [0, 0, 0, 0, 0] (min delta 0, bitwidths 0)
+        // suffixes: "HelloWorld"
+        let expected = &[
+            128u8, 1, 4, 2, 0, 0, 0, 0, 0, 0, 128, 1, 4, 2, 10, 0, 0, 0, 0, 0, 72, 101, 108, 108,
+            111, 87, 111, 114, 108, 100,
+        ];
+        assert_eq!(buffer, expected);
+
+        let result = decode(&buffer);
+        assert_eq!(result, vec![b"Hello".to_vec(), b"World".to_vec()]);
+    }
+
+    #[test]
+    fn prefixes() {
+        let data = ["axis", "axle", "babble", "babyhood"];
+
+        let mut buffer = vec![];
+        encode(data.iter().map(|x| x.as_bytes()), &mut buffer);
+
+        let mut decoder = Decoder::new(&buffer);
+        let prefixes = decoder.by_ref().collect::<Vec<_>>();
+        assert_eq!(prefixes, vec![0, 2, 0, 3]);
+
+        let mut decoder = decoder.into_lengths();
+        let lengths = decoder.by_ref().collect::<Vec<_>>();
+        assert_eq!(lengths, vec![4, 2, 6, 5]);
+        assert_eq!(decoder.values(), b"axislebabbleyhood".as_ref());
+
+        let result = decode(&buffer);
+        let expected = data
+            .iter()
+            .map(|x| x.as_bytes().to_vec())
+            .collect::<Vec<_>>();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn many_values() {
+        let data = (0..300)
+            .map(|i| format!("https://example.com/path/{}", i / 3))
+            .collect::<Vec<_>>();
+
+        let mut buffer = vec![];
+        encode(data.iter(), &mut buffer);
+
+        let result = decode(&buffer);
+        let expected = data
+            .iter()
+            .map(|x| x.as_bytes().to_vec())
+            .collect::<Vec<_>>();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn empty() {
+        let data: Vec<&[u8]> = vec![];
+
+        let mut buffer = vec![];
+        encode(data.into_iter(), &mut buffer);
+
+        assert!(decode(&buffer).is_empty());
+    }
+}
@@ -3,28 +3,138 @@ use crate::encoding::{ceil8, uleb128};
 
 use std::io::Write;
 
-use super::bitpacked_encode;
+/// The number of values in a bitpacked group
+const GROUP_LEN: usize = 8;
+/// The maximum number of groups in a bitpacked run, so that its header fits in a single byte
+const MAX_GROUPS_PER_RUN: usize = 63;
+
+/// A stateful RLE-hybrid encoder that decides, per group of 8 values, whether to
+/// emit an RLE run or to append the group to a bitpacked run.
+///
+/// Like parquet-mr, a RLE run is emitted whenever a value is repeated at least 8 times starting
+/// at the beginning of a group; all other values are bitpacked.
+struct Encoder<'a, W: Write> {
+    writer: &'a mut W,
+    num_bits: u8,
+    // values of the current group, not yet assigned to a run
+    buffered: [u32; GROUP_LEN],
+    num_buffered: usize,
+    previous: u32,
+    repeat_count: usize,
+    // values of the current bitpacked run (a multiple of `GROUP_LEN`)
+    bitpacked: Vec<u32>,
+}
+
+impl<'a, W: Write> Encoder<'a, W> {
+    fn new(writer: &'a mut W, num_bits: u8) -> Self {
+        Self {
+            writer,
+            num_bits,
+            buffered: [0; GROUP_LEN],
+            num_buffered: 0,
+            previous: 0,
+            repeat_count: 0,
+            bitpacked: Vec::with_capacity(MAX_GROUPS_PER_RUN * GROUP_LEN),
+        }
+    }
+
+    fn push(&mut self, value: u32) -> std::io::Result<()> {
+        if value == self.previous && self.repeat_count > 0 {
+            self.repeat_count += 1;
+            if self.repeat_count >= GROUP_LEN {
+                // this is certainly a RLE run; keep counting repeats
+                return Ok(());
+            }
+        } else {
+            if self.repeat_count >= GROUP_LEN {
+                self.write_rle_run()?;
+            }
+            self.repeat_count = 1;
+            self.previous = value;
+        }
+
+        self.buffered[self.num_buffered] = value;
+        self.num_buffered += 1;
+        if self.num_buffered == GROUP_LEN {
+            // less than 8 repeated values => the group goes to a bitpacked run
+            self.bitpacked.extend_from_slice(&self.buffered);
+            self.num_buffered = 0;
+            self.repeat_count = 0;
+            if self.bitpacked.len() == MAX_GROUPS_PER_RUN * GROUP_LEN {
+                self.write_bitpacked_run(self.bitpacked.len())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_rle_run(&mut self) -> std::io::Result<()> {
+        self.write_bitpacked_run(self.bitpacked.len())?;
+
+        // write the length + indicator
+        let header = (self.repeat_count as u64) << 1;
+        let mut container = [0; 10];
+        let used = uleb128::encode(header, &mut container);
+        self.writer.write_all(&container[..used])?;
+
+        let value = self.previous.to_le_bytes();
+        self.writer
+            .write_all(&value[..ceil8(self.num_bits as usize)])?;
+
+        self.repeat_count = 0;
+        self.num_buffered = 0;
+        Ok(())
+    }
+
+    /// Writes the current bitpacked run, of which only the first `length` values are meaningful
+    fn write_bitpacked_run(&mut self, length: usize) -> std::io::Result<()> {
+        if self.bitpacked.is_empty() {
+            return Ok(());
+        }
+        // write the length + indicator
+        let mut header = ceil8(self.bitpacked.len()) as u64;
+        header <<= 1;
+        header |= 1; // it is bitpacked => first bit is set
+        let mut container = [0; 10];
+        let used = uleb128::encode(header, &mut container);
+        self.writer.write_all(&container[..used])?;
+
+        bitpacked_encode_u32(
+            self.writer,
+            self.bitpacked[..length].iter().copied(),
+            self.num_bits,
+        )?;
+        self.bitpacked.clear();
+        Ok(())
+    }
+
+    fn finish(mut self) -> std::io::Result<()> {
+        if self.repeat_count >= GROUP_LEN {
+            self.write_rle_run()
+        } else if self.num_buffered > 0 {
+            // the last group is padded, but only its values are written
+            let length = self.bitpacked.len() + self.num_buffered;
+            self.bitpacked.extend_from_slice(&self.buffered);
+            self.write_bitpacked_run(length)
+        } else {
+            self.write_bitpacked_run(self.bitpacked.len())
+        }
+    }
+}
 
-/// RLE-hybrid encoding of `u32`. This currently only yields bitpacked values.
+/// RLE-hybrid encoding of `u32`.
+///
+/// Sequences of at least 8 repeated values are encoded as RLE runs, all other values are
+/// bitpacked with `num_bits`.
 pub fn encode_u32<W: Write, I: Iterator<Item = u32>>(
     writer: &mut W,
     iterator: I,
     num_bits: u8,
 ) -> std::io::Result<()> {
-    // the length of the iterator.
-    let length = iterator.size_hint().1.unwrap();
-
-    // write the length + indicator
-    let mut header = ceil8(length as usize) as u64;
-    header <<= 1;
-    header |= 1; // it is bitpacked => first bit is set
-    let mut container = [0; 10];
-    let used = uleb128::encode(header, &mut container);
-    writer.write_all(&container[..used])?;
-
-    bitpacked_encode_u32(writer, iterator, num_bits)?;
-
-    Ok(())
+    let mut encoder = Encoder::new(writer, num_bits);
+    for value in iterator {
+        encoder.push(value)?;
+    }
+    encoder.finish()
 }
 
 fn bitpacked_encode_u32<W: Write, I: Iterator<Item = u32>>(
@@ -64,25 +174,15 @@ fn bitpacked_encode_u32<W: Write, I: Iterator<Item = u32>>(
     Ok(())
 }
 
-/// the bitpacked part of the encoder.
+/// RLE-hybrid encoding of `bool` (e.g. definition levels whose maximum is 1).
+///
+/// Sequences of at least 8 repeated values are encoded as RLE runs, all other values are
+/// bitpacked.
 pub fn encode_bool<W: Write, I: Iterator<Item = bool>>(
     writer: &mut W,
     iterator: I,
 ) -> std::io::Result<()> {
-    // the length of the iterator.
-    let length = iterator.size_hint().1.unwrap();
-
-    // write the length + indicator
-    let mut header = ceil8(length) as u64;
-    header <<= 1;
-    header |= 1; // it is bitpacked => first bit is set
-    let mut container = [0; 10];
-    let used = uleb128::encode(header, &mut container);
-
-    writer.write_all(&container[..used])?;
-
-    // encode the iterator
-    bitpacked_encode(writer, iterator)
+    encode_u32(writer, iterator.map(|x| x as u32), 1)
 }
 
 #[cfg(test)]
@@ -112,7 +212,8 @@ mod tests {
             vec![true, true, true, true, true, true, true, true].into_iter(),
         )?;
 
-        assert_eq!(vec, vec![(1 << 1 | 1), 0b11111111]);
+        // 8 repeated values are a RLE run
+        assert_eq!(vec, vec![(8 << 1), 1]);
         Ok(())
     }
 
@@ -158,4 +259,67 @@ mod tests {
         assert_eq!(expected, vec);
         Ok(())
     }
+
+    #[test]
+    fn test_u32_rle() -> std::io::Result<()> {
+        let mut vec = vec![];
+
+        let values = std::iter::repeat_n(3, 100);
+        encode_u32(&mut vec, values, 2)?;
+
+        // header: 100 << 1, value: 3 in 1 byte
+        assert_eq!(vec, vec![200, 1, 3]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_u32_bitpacked_and_rle() -> std::io::Result<()> {
+        let mut vec = vec![];
+
+        // a bitpacked group, followed by a run of 10 and a (padded) bitpacked group of 2
+        let values = (0..8).chain(std::iter::repeat_n(5, 10)).chain([1, 2]);
+        encode_u32(&mut vec, values, 3)?;
+
+        // 0..8 in 3 bits, least significant bits first
+        let expected = vec![
+            (1 << 1 | 1),
+            0b1000_1000,
+            0b1100_0110,
+            0b1111_1010,
+            (10 << 1),
+            5,
+            (1 << 1 | 1),
+            0b00_010_001,
+        ];
+        assert_eq!(vec, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_u32_unaligned_run() -> std::io::Result<()> {
+        let mut vec = vec![];
+
+        // the run only starts to be RLE-encoded at the beginning of the next group
+        let values = [1, 2, 3].into_iter().chain(std::iter::repeat_n(0, 21));
+        encode_u32(&mut vec, values, 2)?;
+
+        let expected = vec![(1 << 1 | 1), 0b00_11_10_01, 0b00_00_00_00, (16 << 1), 0];
+        assert_eq!(vec, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_u32_max_groups() -> std::io::Result<()> {
+        let mut vec = vec![];
+
+        // 64 groups of bitpacked values are split in two runs
+        let values = (0..64 * 8).map(|x| x % 2);
+        encode_u32(&mut vec, values, 1)?;
+
+        let mut expected = vec![(63 << 1 | 1)];
+        expected.extend(std::iter::repeat_n(0b10101010, 63));
+        expected.extend([(1 << 1 | 1), 0b10101010]);
+        assert_eq!(vec, expected);
+        Ok(())
+    }
 }
@@ -119,6 +119,31 @@ mod tests {
         assert_eq!(result, data);
     }
 
+    #[test]
+    fn roundtrip_runs() {
+        let mut buffer = vec![];
+        let num_bits = 3;
+
+        // mix of runs and non-repeated values, not aligned to groups
+        let data = (0..1000u32)
+            .map(|x| {
+                if (x / 37) % 2 == 0 {
+                    x % 7
+                } else {
+                    (x / 37) % 8
+                }
+            })
+            .collect::<Vec<_>>();
+
+        encode_u32(&mut buffer, data.iter().cloned(), num_bits).unwrap();
+
+        let decoder = HybridRleDecoder::new(&buffer, num_bits as u32, data.len());
+
+        let result = decoder.collect::<Vec<_>>();
+
+        assert_eq!(result, data);
+    }
+
     #[test]
     fn pyarrow_integration() {
         // data encoded from pyarrow representing (0..1000)
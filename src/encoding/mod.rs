@@ -1,6 +1,7 @@
 use std::convert::TryInto;
 
 pub mod bitpacking;
+pub mod byte_stream_split;
 pub mod delta_bitpacked;
 pub mod delta_byte_array;
 pub mod delta_length_byte_array;
@@ -0,0 +1,257 @@
+use aes_gcm::aead::consts::U12;
+use aes_gcm::aead::generic_array::GenericArray;
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::OsRng;
+use aes_gcm::aes::{Aes128, Aes192, Aes256};
+use aes_gcm::{AeadInPlace, AesGcm, KeyInit};
+use ctr::cipher::{KeyIvInit, StreamCipher};
+use ctr::Ctr128BE;
+
+use crate::error::{Error, Result};
+
+/// The number of bytes of the length that prefixes every module
+pub(super) const LENGTH_LEN: usize = 4;
+pub(super) const NONCE_LEN: usize = 12;
+pub(super) const TAG_LEN: usize = 16;
+
+fn invalid_key(key: &[u8]) -> Error {
+    Error::InvalidParameter(format!(
+        "AES keys must have 16, 24 or 32 bytes but a key has {} bytes",
+        key.len()
+    ))
+}
+
+/// Errors if `key` is not an AES key.
+pub(super) fn check_key(key: &[u8]) -> Result<()> {
+    match key.len() {
+        16 | 24 | 32 => Ok(()),
+        _ => Err(invalid_key(key)),
+    }
+}
+
+fn decryption_failed() -> Error {
+    Error::General(
+        "Failed to decrypt a module: the key is wrong or the file is corrupted".to_string(),
+    )
+}
+
+// calls `$op` on the AES-GCM cipher of the size of `$key`
+macro_rules! with_gcm {
+    ($key:expr, $cipher:ident => $op:expr) => {
+        match $key.len() {
+            16 => {
+                let $cipher = AesGcm::<Aes128, U12>::new_from_slice($key).unwrap();
+                $op
+            }
+            24 => {
+                let $cipher = AesGcm::<Aes192, U12>::new_from_slice($key).unwrap();
+                $op
+            }
+            32 => {
+                let $cipher = AesGcm::<Aes256, U12>::new_from_slice($key).unwrap();
+                $op
+            }
+            _ => return Err(invalid_key($key)),
+        }
+    };
+}
+
+// calls `$op` on the AES-CTR cipher of the size of `$key`
+macro_rules! with_ctr {
+    ($key:expr, $iv:expr, $cipher:ident => $op:expr) => {
+        match $key.len() {
+            16 => {
+                let mut $cipher = Ctr128BE::<Aes128>::new_from_slices($key, $iv).unwrap();
+                $op
+            }
+            24 => {
+                let mut $cipher = Ctr128BE::<Aes192>::new_from_slices($key, $iv).unwrap();
+                $op
+            }
+            32 => {
+                let mut $cipher = Ctr128BE::<Aes256>::new_from_slices($key, $iv).unwrap();
+                $op
+            }
+            _ => return Err(invalid_key($key)),
+        }
+    };
+}
+
+/// Splits a module, `[length][nonce][data]`, into its nonce and its data
+fn split_module(module: &[u8], min_len: usize) -> Result<(&[u8], &[u8])> {
+    if module.len() < LENGTH_LEN + min_len {
+        return Err(Error::OutOfSpec(format!(
+            "An encrypted module must have at least {} bytes but it has {}",
+            LENGTH_LEN + min_len,
+            module.len()
+        )));
+    }
+    let length = u32::from_le_bytes(module[..LENGTH_LEN].try_into().unwrap()) as usize;
+    if length != module.len() - LENGTH_LEN {
+        return Err(Error::OutOfSpec(format!(
+            "The length of an encrypted module ({}) must be equal to its size ({})",
+            length,
+            module.len() - LENGTH_LEN
+        )));
+    }
+    Ok(module[LENGTH_LEN..].split_at(NONCE_LEN))
+}
+
+/// Returns the IV of AES-CTR: the nonce followed by a (big-endian) counter starting at 1
+fn ctr_iv(nonce: &[u8]) -> [u8; 16] {
+    let mut iv = [0; 16];
+    iv[..NONCE_LEN].copy_from_slice(nonce);
+    iv[15] = 1;
+    iv
+}
+
+/// Decrypts a module encrypted with AES-GCM, `[length][nonce][ciphertext][tag]`.
+pub(super) fn decrypt_gcm(key: &[u8], aad: &[u8], module: &[u8]) -> Result<Vec<u8>> {
+    let (nonce, data) = split_module(module, NONCE_LEN + TAG_LEN)?;
+    let (ciphertext, tag) = data.split_at(data.len() - TAG_LEN);
+
+    let mut buffer = ciphertext.to_vec();
+    with_gcm!(key, cipher => cipher.decrypt_in_place_detached(
+        GenericArray::from_slice(nonce),
+        aad,
+        &mut buffer,
+        GenericArray::from_slice(tag),
+    ))
+    .map_err(|_| decryption_failed())?;
+    Ok(buffer)
+}
+
+/// Decrypts a module encrypted with AES-CTR, `[length][nonce][ciphertext]`.
+pub(super) fn decrypt_ctr(key: &[u8], module: &[u8]) -> Result<Vec<u8>> {
+    let (nonce, ciphertext) = split_module(module, NONCE_LEN)?;
+
+    let mut buffer = ciphertext.to_vec();
+    with_ctr!(key, &ctr_iv(nonce), cipher => cipher.apply_keystream(&mut buffer));
+    Ok(buffer)
+}
+
+/// Returns `N` random bytes, used as nonces and as the unique part of the AAD of files.
+pub(super) fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0; N];
+    OsRng.fill_bytes(&mut bytes);
+    bytes
+}
+
+/// Encrypts `buffer` in place with AES-GCM, returning its tag.
+fn gcm_encrypt_in_place(
+    key: &[u8],
+    nonce: &[u8],
+    aad: &[u8],
+    buffer: &mut [u8],
+) -> Result<Vec<u8>> {
+    let tag = with_gcm!(key, cipher => cipher.encrypt_in_place_detached(
+        GenericArray::from_slice(nonce),
+        aad,
+        buffer,
+    ))
+    .map_err(|_| Error::General("Failed to encrypt a module".to_string()))?;
+    Ok(tag.to_vec())
+}
+
+/// Returns the AES-GCM tag of `plaintext`, used to sign plaintext footers.
+pub(super) fn gcm_tag(key: &[u8], nonce: &[u8], aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>> {
+    gcm_encrypt_in_place(key, nonce, aad, &mut plaintext.to_vec())
+}
+
+/// Returns whether `tag` is the AES-GCM tag of `plaintext`. The tags are compared in
+/// constant time.
+pub(super) fn verify_gcm_tag(
+    key: &[u8],
+    nonce: &[u8],
+    aad: &[u8],
+    plaintext: &[u8],
+    tag: &[u8],
+) -> Result<bool> {
+    let mut buffer = plaintext.to_vec();
+    gcm_encrypt_in_place(key, nonce, aad, &mut buffer)?;
+    // decrypting the ciphertext verifies its tag
+    Ok(with_gcm!(key, cipher => cipher.decrypt_in_place_detached(
+        GenericArray::from_slice(nonce),
+        aad,
+        &mut buffer,
+        GenericArray::from_slice(tag),
+    ))
+    .is_ok())
+}
+
+/// Encrypts `plaintext` with AES-GCM into a module, `[length][nonce][ciphertext][tag]`.
+pub(super) fn encrypt_gcm(
+    key: &[u8],
+    nonce: &[u8],
+    aad: &[u8],
+    plaintext: &[u8],
+) -> Result<Vec<u8>> {
+    let mut buffer = plaintext.to_vec();
+    let tag = gcm_encrypt_in_place(key, nonce, aad, &mut buffer)?;
+    let length = module_length(NONCE_LEN + buffer.len() + TAG_LEN)?;
+    Ok([&length.to_le_bytes(), nonce, &buffer, &tag].concat())
+}
+
+/// Encrypts `plaintext` with AES-CTR into a module, `[length][nonce][ciphertext]`.
+pub(super) fn encrypt_ctr(key: &[u8], nonce: &[u8], plaintext: &[u8]) -> Result<Vec<u8>> {
+    let mut buffer = plaintext.to_vec();
+    with_ctr!(key, &ctr_iv(nonce), cipher => cipher.apply_keystream(&mut buffer));
+    let length = module_length(NONCE_LEN + buffer.len())?;
+    Ok([&length.to_le_bytes(), nonce, &buffer].concat())
+}
+
+fn module_length(length: usize) -> Result<u32> {
+    length.try_into().map_err(|_| {
+        Error::OutOfSpec(format!(
+            "An encrypted module can only contain u32::MAX bytes. This one contains {}",
+            length
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gcm() -> Result<()> {
+        for key in [[1u8; 16].as_ref(), &[2; 24], &[3; 32]] {
+            let module = encrypt_gcm(key, &[4; 12], b"aad", b"hello")?;
+            assert_eq!(module.len(), LENGTH_LEN + NONCE_LEN + 5 + TAG_LEN);
+            assert_eq!(decrypt_gcm(key, b"aad", &module)?, b"hello");
+
+            // the AAD is authenticated
+            assert!(decrypt_gcm(key, b"other", &module).is_err());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn tag() -> Result<()> {
+        let key = [1u8; 16];
+        let tag = gcm_tag(&key, &[4; 12], b"aad", b"hello")?;
+        assert!(verify_gcm_tag(&key, &[4; 12], b"aad", b"hello", &tag)?);
+        assert!(!verify_gcm_tag(&key, &[4; 12], b"aad", b"hellO", &tag)?);
+        assert!(!verify_gcm_tag(&[2; 16], &[4; 12], b"aad", b"hello", &tag)?);
+        Ok(())
+    }
+
+    #[test]
+    fn ctr() -> Result<()> {
+        let key = [1u8; 16];
+        let module = encrypt_ctr(&key, &[4; 12], b"hello")?;
+        assert_eq!(module.len(), LENGTH_LEN + NONCE_LEN + 5);
+        assert_eq!(decrypt_ctr(&key, &module)?, b"hello");
+        Ok(())
+    }
+
+    #[test]
+    fn invalid() -> Result<()> {
+        let mut module = encrypt_gcm(&[1; 16], &[0; 12], b"", b"a")?;
+        assert!(decrypt_gcm(&[1; 15], b"", &module).is_err());
+        // the length does not match the module
+        module.pop();
+        assert!(decrypt_gcm(&[1; 16], b"", &module).is_err());
+        Ok(())
+    }
+}
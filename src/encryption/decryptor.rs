@@ -0,0 +1,688 @@
+use std::io::Read;
+use std::sync::Arc;
+
+use parquet_format_safe::thrift::protocol::TCompactInputProtocol;
+use parquet_format_safe::{
+    ColumnChunk, ColumnCryptoMetaData, ColumnMetaData, CompressionCodec, EncryptionAlgorithm,
+    FileCryptoMetaData, FileMetaData as TFileMetaData,
+};
+
+use crate::error::{Error, Result};
+use crate::metadata::{ColumnDescriptor, FileMetaData, SchemaDescriptor};
+use crate::page::ParquetPageHeader;
+
+use super::cipher::{decrypt_ctr, decrypt_gcm, verify_gcm_tag, LENGTH_LEN, NONCE_LEN, TAG_LEN};
+use super::{module_aad, FileDecryptionProperties, ModuleType};
+
+/// Decrypts the footer and the column metadata of a file
+struct FileDecryptor<'a> {
+    properties: &'a FileDecryptionProperties,
+    footer_key: Option<Vec<u8>>,
+    aad_file: Vec<u8>,
+    // whether pages are encrypted with AES-CTR (`AES_GCM_CTR_V1`)
+    ctr: bool,
+}
+
+impl<'a> FileDecryptor<'a> {
+    fn try_new(
+        algorithm: &EncryptionAlgorithm,
+        properties: &'a FileDecryptionProperties,
+        footer_key_metadata: Option<&[u8]>,
+    ) -> Result<Self> {
+        let (ctr, aad_prefix, aad_file_unique, supply_aad_prefix) = match algorithm {
+            EncryptionAlgorithm::AESGCMV1(x) => (
+                false,
+                &x.aad_prefix,
+                &x.aad_file_unique,
+                x.supply_aad_prefix,
+            ),
+            EncryptionAlgorithm::AESGCMCTRV1(x) => {
+                (true, &x.aad_prefix, &x.aad_file_unique, x.supply_aad_prefix)
+            }
+        };
+        let aad_prefix = match (&properties.aad_prefix, aad_prefix) {
+            (Some(prefix), _) | (None, Some(prefix)) => prefix.as_slice(),
+            (None, None) if supply_aad_prefix.unwrap_or(false) => {
+                return Err(Error::InvalidParameter(
+                    "The file was encrypted with an AAD prefix that is not stored in it and must be passed to decrypt it".to_string(),
+                ))
+            }
+            (None, None) => &[],
+        };
+        let aad_file = [aad_prefix, aad_file_unique.as_deref().unwrap_or_default()].concat();
+
+        Ok(Self {
+            properties,
+            footer_key: properties.footer_key(footer_key_metadata)?,
+            aad_file,
+            ctr,
+        })
+    }
+
+    fn footer_key(&self) -> Result<&[u8]> {
+        self.footer_key.as_deref().ok_or_else(|| {
+            Error::InvalidParameter("The footer key is required to decrypt the file".to_string())
+        })
+    }
+
+    fn decrypt_footer(&self, module: &[u8]) -> Result<Vec<u8>> {
+        let aad = module_aad(&self.aad_file, ModuleType::Footer, 0, 0, None);
+        decrypt_gcm(self.footer_key()?, &aad, module)
+    }
+
+    /// Verifies the signature, `[nonce][tag]`, of a plaintext footer
+    fn verify_footer(&self, footer: &[u8], signature: &[u8]) -> Result<()> {
+        if signature.len() != NONCE_LEN + TAG_LEN {
+            return Err(Error::OutOfSpec(format!(
+                "The signature of a plaintext footer must have {} bytes but it has {}",
+                NONCE_LEN + TAG_LEN,
+                signature.len()
+            )));
+        }
+        let (nonce, tag) = signature.split_at(NONCE_LEN);
+        let aad = module_aad(&self.aad_file, ModuleType::Footer, 0, 0, None);
+        if !verify_gcm_tag(self.footer_key()?, nonce, &aad, footer, tag)? {
+            return Err(Error::General(
+                "The signature of the plaintext footer does not match: the footer key is wrong or the footer was modified".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns the decryptor of a column chunk, decrypting its metadata when it is encrypted.
+    ///
+    /// When the key of the column is not available, the column can't be decrypted: its
+    /// decryptor errors when it is used and, if its metadata is only stored encrypted, a
+    /// placeholder without values is used.
+    fn column(
+        &self,
+        column_chunk: &mut ColumnChunk,
+        descriptor: &ColumnDescriptor,
+        row_group: i16,
+        column: i16,
+    ) -> Result<Option<Arc<ColumnDecryptor>>> {
+        let key = match &column_chunk.crypto_metadata {
+            None => return Ok(None),
+            Some(ColumnCryptoMetaData::ENCRYPTIONWITHFOOTERKEY(_)) => {
+                self.footer_key().map(|key| key.to_vec())
+            }
+            Some(ColumnCryptoMetaData::ENCRYPTIONWITHCOLUMNKEY(crypto)) => self
+                .properties
+                .column_key(&crypto.path_in_schema, crypto.key_metadata.as_deref()),
+        };
+        let mut decryptor = ColumnDecryptor {
+            key,
+            aad_file: self.aad_file.clone(),
+            ctr: self.ctr,
+            row_group,
+            column,
+            has_dictionary: false,
+        };
+
+        match &column_chunk.encrypted_column_metadata {
+            Some(encrypted) if decryptor.key.is_ok() => {
+                let metadata = decryptor.decrypt(ModuleType::ColumnMetaData, None, encrypted)?;
+                let mut prot =
+                    TCompactInputProtocol::new(metadata.as_slice(), metadata.len() * 2 + 1024);
+                column_chunk.meta_data = Some(ColumnMetaData::read_from_in_protocol(&mut prot)?);
+            }
+            Some(_) if column_chunk.meta_data.is_none() => {
+                column_chunk.meta_data = Some(placeholder_metadata(descriptor));
+            }
+            _ => {}
+        }
+        decryptor.has_dictionary = column_chunk
+            .meta_data
+            .as_ref()
+            .is_some_and(|x| x.dictionary_page_offset.is_some());
+        Ok(Some(Arc::new(decryptor)))
+    }
+}
+
+/// The metadata of a column chunk whose metadata can't be decrypted: it has no values, as
+/// documented on [`ColumnChunkMetaData`](crate::metadata::ColumnChunkMetaData).
+fn placeholder_metadata(descriptor: &ColumnDescriptor) -> ColumnMetaData {
+    let (type_, _) = descriptor.descriptor.primitive_type.physical_type.into();
+    ColumnMetaData {
+        type_,
+        encodings: vec![],
+        path_in_schema: descriptor.path_in_schema.clone(),
+        codec: CompressionCodec::UNCOMPRESSED,
+        num_values: 0,
+        total_uncompressed_size: 0,
+        total_compressed_size: 0,
+        key_value_metadata: None,
+        data_page_offset: 0,
+        index_page_offset: None,
+        dictionary_page_offset: None,
+        statistics: None,
+        encoding_stats: None,
+        bloom_filter_offset: None,
+    }
+}
+
+/// Deserializes the metadata of an encrypted file from its `footer`, i.e. the bytes between
+/// the column chunks and the footer's length.
+pub(crate) fn decrypt_metadata(
+    footer: &[u8],
+    encrypted_footer: bool,
+    properties: &FileDecryptionProperties,
+) -> Result<FileMetaData> {
+    let max_size = footer.len() * 2 + 1024;
+    let mut reader = footer;
+
+    let (mut metadata, decryptor) = if encrypted_footer {
+        // [FileCryptoMetaData][encrypted FileMetaData]
+        let mut prot = TCompactInputProtocol::new(&mut reader, max_size);
+        let crypto = FileCryptoMetaData::read_from_in_protocol(&mut prot)?;
+        let decryptor = FileDecryptor::try_new(
+            &crypto.encryption_algorithm,
+            properties,
+            crypto.key_metadata.as_deref(),
+        )?;
+
+        let footer = decryptor.decrypt_footer(reader)?;
+        let mut prot = TCompactInputProtocol::new(footer.as_slice(), footer.len() * 2 + 1024);
+        (TFileMetaData::read_from_in_protocol(&mut prot)?, decryptor)
+    } else {
+        // [FileMetaData][signature]
+        let mut prot = TCompactInputProtocol::new(&mut reader, max_size);
+        let metadata = TFileMetaData::read_from_in_protocol(&mut prot)?;
+        let algorithm = if let Some(algorithm) = metadata.encryption_algorithm.clone() {
+            algorithm
+        } else {
+            // the file is not encrypted
+            return FileMetaData::try_from_thrift(metadata);
+        };
+        let decryptor = FileDecryptor::try_new(
+            &algorithm,
+            properties,
+            metadata.footer_signing_key_metadata.as_deref(),
+        )?;
+        if properties.verify_plaintext_footer {
+            decryptor.verify_footer(&footer[..footer.len() - reader.len()], reader)?;
+        }
+        (metadata, decryptor)
+    };
+
+    let schema = SchemaDescriptor::try_from_thrift(&metadata.schema)?;
+    let decryptors = metadata
+        .row_groups
+        .iter_mut()
+        .enumerate()
+        .map(|(i, row_group)| {
+            let row_group_ordinal = row_group.ordinal.map_or_else(|| i.try_into(), Ok)?;
+            row_group
+                .columns
+                .iter_mut()
+                .zip(schema.columns())
+                .enumerate()
+                .map(|(j, (column, descriptor))| {
+                    decryptor.column(column, descriptor, row_group_ordinal, j.try_into()?)
+                })
+                .collect::<Result<Vec<_>>>()
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut metadata = FileMetaData::try_from_thrift(metadata)?;
+    for (row_group, decryptors) in metadata.row_groups.iter_mut().zip(decryptors) {
+        for (column, decryptor) in row_group.columns_mut().iter_mut().zip(decryptors) {
+            column.decryptor = decryptor;
+        }
+    }
+    Ok(metadata)
+}
+
+/// Decrypts the modules (pages, indexes, bloom filters) of an encrypted column chunk.
+#[derive(Clone)]
+pub struct ColumnDecryptor {
+    // the error of retrieving the key when it is not available
+    key: Result<Vec<u8>>,
+    aad_file: Vec<u8>,
+    // whether pages are encrypted with AES-CTR instead of AES-GCM
+    ctr: bool,
+    row_group: i16,
+    column: i16,
+    // whether the column chunk starts with a dictionary page
+    has_dictionary: bool,
+}
+
+// errors are compared by whether the key is available
+impl PartialEq for ColumnDecryptor {
+    fn eq(&self, other: &Self) -> bool {
+        self.key.as_ref().ok() == other.key.as_ref().ok()
+            && self.aad_file == other.aad_file
+            && self.ctr == other.ctr
+            && self.row_group == other.row_group
+            && self.column == other.column
+            && self.has_dictionary == other.has_dictionary
+    }
+}
+
+impl Eq for ColumnDecryptor {}
+
+// the key is not printed
+impl std::fmt::Debug for ColumnDecryptor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ColumnDecryptor")
+            .field("has_key", &self.key.is_ok())
+            .field("ctr", &self.ctr)
+            .field("row_group", &self.row_group)
+            .field("column", &self.column)
+            .field("has_dictionary", &self.has_dictionary)
+            .finish()
+    }
+}
+
+impl ColumnDecryptor {
+    /// Returns the key of the column chunk, or the error of retrieving it when reading the
+    /// metadata of the file.
+    pub(crate) fn key(&self) -> Result<&[u8]> {
+        self.key.as_deref().map_err(Clone::clone)
+    }
+
+    /// Decrypts `module`, whose type is `module_type` and that belongs to the data page `page`.
+    pub(crate) fn decrypt(
+        &self,
+        module_type: ModuleType,
+        page: Option<i16>,
+        module: &[u8],
+    ) -> Result<Vec<u8>> {
+        if self.ctr
+            && matches!(
+                module_type,
+                ModuleType::DataPage | ModuleType::DictionaryPage
+            )
+        {
+            decrypt_ctr(self.key()?, module)
+        } else {
+            let aad = module_aad(
+                &self.aad_file,
+                module_type,
+                self.row_group,
+                self.column,
+                page,
+            );
+            decrypt_gcm(self.key()?, &aad, module)
+        }
+    }
+}
+
+/// Reads a module, `[length][data]`, of at most `max_size` bytes from `reader`.
+pub(crate) fn read_module<R: Read>(reader: &mut R, max_size: usize) -> Result<Vec<u8>> {
+    let mut length = [0; LENGTH_LEN];
+    reader.read_exact(&mut length)?;
+    let size = u32::from_le_bytes(length) as usize;
+    if size > max_size {
+        return Err(Error::OutOfSpec(format!(
+            "An encrypted module has {} bytes, more than the maximum of {}",
+            size, max_size
+        )));
+    }
+
+    let mut module = vec![];
+    module.try_reserve(LENGTH_LEN + size)?;
+    module.extend_from_slice(&length);
+    reader.take(size as u64).read_to_end(&mut module)?;
+    Ok(module)
+}
+
+/// Asynchronously reads a module, `[length][data]`, of at most `max_size` bytes from `reader`.
+#[cfg(feature = "async")]
+pub(crate) async fn read_module_async<R: futures::AsyncRead + Unpin + Send>(
+    reader: &mut R,
+    max_size: usize,
+) -> Result<Vec<u8>> {
+    use futures::AsyncReadExt;
+
+    let mut length = [0; LENGTH_LEN];
+    reader.read_exact(&mut length).await?;
+    let size = u32::from_le_bytes(length) as usize;
+    if size > max_size {
+        return Err(Error::OutOfSpec(format!(
+            "An encrypted module has {} bytes, more than the maximum of {}",
+            size, max_size
+        )));
+    }
+
+    let mut module = vec![];
+    module.try_reserve(LENGTH_LEN + size)?;
+    module.extend_from_slice(&length);
+    reader.take(size as u64).read_to_end(&mut module).await?;
+    Ok(module)
+}
+
+/// Decrypts the pages of a column chunk in the order they are stored in it.
+#[derive(Debug)]
+pub(crate) struct PageDecryptor {
+    column: Arc<ColumnDecryptor>,
+    // whether the next page is the dictionary page
+    dictionary: bool,
+    // the ordinal of the next data page
+    page_ordinal: usize,
+}
+
+impl PageDecryptor {
+    pub fn new(column: Arc<ColumnDecryptor>) -> Self {
+        Self {
+            dictionary: column.has_dictionary,
+            column,
+            page_ordinal: 0,
+        }
+    }
+
+    /// Sets the next page to the data page `page_ordinal`.
+    pub fn seek(&mut self, page_ordinal: usize) {
+        self.dictionary = false;
+        self.page_ordinal = page_ordinal;
+    }
+
+    /// Returns the module types of the header and of the data of the next page, and its ordinal.
+    fn next_page(&self) -> Result<(ModuleType, ModuleType, Option<i16>)> {
+        Ok(if self.dictionary {
+            (
+                ModuleType::DictionaryPageHeader,
+                ModuleType::DictionaryPage,
+                None,
+            )
+        } else {
+            (
+                ModuleType::DataPageHeader,
+                ModuleType::DataPage,
+                Some(self.page_ordinal.try_into()?),
+            )
+        })
+    }
+
+    /// Decrypts the header of the next page from its `module`.
+    pub fn decrypt_header(&self, module: &[u8]) -> Result<ParquetPageHeader> {
+        let (module_type, _, page) = self.next_page()?;
+        let header = self.column.decrypt(module_type, page, module)?;
+        let mut prot = TCompactInputProtocol::new(header.as_slice(), header.len() * 2 + 1024);
+        Ok(ParquetPageHeader::read_from_in_protocol(&mut prot)?)
+    }
+
+    /// Errors if the pages can't be decrypted because the key of the column is not available.
+    pub fn check_key(&self) -> Result<()> {
+        self.column.key().map(|_| ())
+    }
+
+    /// Reads and decrypts the header of the next page, whose module has at most `max_size` bytes.
+    pub fn read_header<R: Read>(
+        &self,
+        reader: &mut R,
+        max_size: usize,
+    ) -> Result<ParquetPageHeader> {
+        self.check_key()?;
+        let module = read_module(reader, max_size)?;
+        self.decrypt_header(&module)
+    }
+
+    /// Decrypts the data of the next page and moves to the page after it.
+    pub fn decrypt_page(&mut self, data: &mut Vec<u8>) -> Result<()> {
+        let (_, module_type, page) = self.next_page()?;
+        *data = self.column.decrypt(module_type, page, data)?;
+        self.skip_page();
+        Ok(())
+    }
+
+    /// Moves to the page after the next page.
+    pub fn skip_page(&mut self) {
+        if self.dictionary {
+            self.dictionary = false;
+        } else {
+            self.page_ordinal += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use parquet_format_safe::thrift::protocol::TCompactOutputProtocol;
+    use parquet_format_safe::{AesGcmV1, EncryptionWithColumnKey, PageType, RowGroup};
+
+    use super::super::cipher::{encrypt_ctr, encrypt_gcm};
+    use super::*;
+    use crate::metadata::SchemaDescriptor;
+    use crate::schema::types::{ParquetType, PhysicalType};
+
+    fn serialize<
+        F: FnOnce(
+            &mut TCompactOutputProtocol<&mut Vec<u8>>,
+        ) -> parquet_format_safe::thrift::Result<usize>,
+    >(
+        f: F,
+    ) -> Vec<u8> {
+        let mut buffer = vec![];
+        f(&mut TCompactOutputProtocol::new(&mut buffer)).unwrap();
+        buffer
+    }
+
+    fn column_metadata() -> ColumnMetaData {
+        ColumnMetaData {
+            type_: parquet_format_safe::Type::INT32,
+            encodings: vec![],
+            path_in_schema: vec!["a".to_string()],
+            codec: parquet_format_safe::CompressionCodec::UNCOMPRESSED,
+            num_values: 10,
+            total_uncompressed_size: 40,
+            total_compressed_size: 40,
+            key_value_metadata: None,
+            data_page_offset: 4,
+            index_page_offset: None,
+            dictionary_page_offset: None,
+            statistics: None,
+            encoding_stats: None,
+            bloom_filter_offset: None,
+        }
+    }
+
+    /// Returns the metadata of a file with one column encrypted with `column_key`
+    fn file_metadata(algorithm: EncryptionAlgorithm, column_key: &[u8]) -> TFileMetaData {
+        let schema = SchemaDescriptor::new(
+            "schema".to_string(),
+            vec![ParquetType::from_physical(
+                "a".to_string(),
+                PhysicalType::Int32,
+            )],
+        );
+        let aad = module_aad(b"unique", ModuleType::ColumnMetaData, 0, 0, None);
+        let metadata = serialize(|prot| column_metadata().write_to_out_protocol(prot));
+        let column = ColumnChunk {
+            file_path: None,
+            file_offset: 44,
+            meta_data: None,
+            offset_index_offset: None,
+            offset_index_length: None,
+            column_index_offset: None,
+            column_index_length: None,
+            crypto_metadata: Some(ColumnCryptoMetaData::ENCRYPTIONWITHCOLUMNKEY(
+                EncryptionWithColumnKey {
+                    path_in_schema: vec!["a".to_string()],
+                    key_metadata: Some(b"column".to_vec()),
+                },
+            )),
+            encrypted_column_metadata: Some(
+                encrypt_gcm(column_key, &[1; 12], &aad, &metadata).unwrap(),
+            ),
+        };
+
+        TFileMetaData {
+            version: 1,
+            schema: schema.into_thrift(),
+            num_rows: 10,
+            row_groups: vec![RowGroup {
+                columns: vec![column],
+                total_byte_size: 40,
+                num_rows: 10,
+                sorting_columns: None,
+                file_offset: None,
+                total_compressed_size: None,
+                ordinal: None,
+            }],
+            key_value_metadata: None,
+            created_by: None,
+            column_orders: None,
+            encryption_algorithm: Some(algorithm),
+            footer_signing_key_metadata: None,
+        }
+    }
+
+    fn algorithm() -> EncryptionAlgorithm {
+        EncryptionAlgorithm::AESGCMV1(AesGcmV1 {
+            aad_prefix: None,
+            aad_file_unique: Some(b"unique".to_vec()),
+            supply_aad_prefix: None,
+        })
+    }
+
+    #[test]
+    fn encrypted_footer() -> Result<()> {
+        let footer_key = [1u8; 16];
+        let column_key = [2u8; 16];
+
+        let mut metadata = file_metadata(algorithm(), &column_key);
+        metadata.encryption_algorithm = None;
+        let crypto = FileCryptoMetaData {
+            encryption_algorithm: algorithm(),
+            key_metadata: None,
+        };
+        let aad = module_aad(b"unique", ModuleType::Footer, 0, 0, None);
+        let footer = [
+            serialize(|prot| crypto.write_to_out_protocol(prot)),
+            encrypt_gcm(
+                &footer_key,
+                &[3; 12],
+                &aad,
+                &serialize(|prot| metadata.write_to_out_protocol(prot)),
+            )?,
+        ]
+        .concat();
+
+        let mut properties = FileDecryptionProperties::new(footer_key.to_vec());
+        properties
+            .column_keys
+            .insert(vec!["a".to_string()], column_key.to_vec());
+        let metadata = decrypt_metadata(&footer, true, &properties)?;
+
+        let column = &metadata.row_groups[0].columns()[0];
+        assert_eq!(column.num_values(), 10);
+        assert!(column.decryptor.is_some());
+
+        // without its key, the column can't be decrypted but the metadata can be read
+        let column_keys = std::mem::take(&mut properties.column_keys);
+        let metadata = decrypt_metadata(&footer, true, &properties)?;
+        let column = &metadata.row_groups[0].columns()[0];
+        assert_eq!(column.num_values(), 0);
+        assert!(column.decryptor.as_ref().unwrap().key().is_err());
+        properties.column_keys = column_keys;
+
+        // a wrong footer key fails to decrypt the footer
+        properties.footer_key = Some(vec![0; 16]);
+        assert!(decrypt_metadata(&footer, true, &properties).is_err());
+        Ok(())
+    }
+
+    struct Retriever;
+
+    impl super::super::KeyRetriever for Retriever {
+        fn retrieve_key(&self, key_metadata: &[u8]) -> Result<Vec<u8>> {
+            match key_metadata {
+                b"footer" => Ok(vec![1; 32]),
+                b"column" => Ok(vec![2; 32]),
+                _ => Err(Error::General("unknown key".to_string())),
+            }
+        }
+    }
+
+    #[test]
+    fn plaintext_footer() -> Result<()> {
+        let footer_key = [1u8; 32];
+        let mut metadata = file_metadata(algorithm(), &[2; 32]);
+        metadata.footer_signing_key_metadata = Some(b"footer".to_vec());
+        let plaintext = serialize(|prot| metadata.write_to_out_protocol(prot));
+
+        let aad = module_aad(b"unique", ModuleType::Footer, 0, 0, None);
+        let module = encrypt_gcm(&footer_key, &[3; 12], &aad, &plaintext)?;
+        let signature = [&[3; 12], &module[module.len() - TAG_LEN..]].concat();
+        let footer = [plaintext.as_slice(), &signature].concat();
+
+        let properties = FileDecryptionProperties::with_key_retriever(Arc::new(Retriever));
+        let metadata = decrypt_metadata(&footer, false, &properties)?;
+        assert_eq!(metadata.row_groups[0].columns()[0].num_values(), 10);
+
+        // a modified footer does not match its signature
+        let mut footer = footer;
+        let last = footer.len() - 1;
+        footer[last] ^= 1;
+        assert!(decrypt_metadata(&footer, false, &properties).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn pages() -> Result<()> {
+        let key = vec![1u8; 16];
+        let column = ColumnDecryptor {
+            key: Ok(key.clone()),
+            aad_file: b"unique".to_vec(),
+            ctr: true,
+            row_group: 1,
+            column: 2,
+            has_dictionary: true,
+        };
+        let aad = |module_type, page| module_aad(b"unique", module_type, 1, 2, page);
+        let header = |type_: PageType| {
+            let header = ParquetPageHeader {
+                type_,
+                uncompressed_page_size: 1,
+                compressed_page_size: 1,
+                crc: None,
+                data_page_header: None,
+                index_page_header: None,
+                dictionary_page_header: None,
+                data_page_header_v2: None,
+            };
+            serialize(|prot| header.write_to_out_protocol(prot))
+        };
+
+        let mut decryptor = PageDecryptor::new(Arc::new(column));
+
+        let module = encrypt_gcm(
+            &key,
+            &[0; 12],
+            &aad(ModuleType::DictionaryPageHeader, None),
+            &header(PageType::DICTIONARY_PAGE),
+        )?;
+        let header_ = decryptor.read_header(&mut module.as_slice(), usize::MAX)?;
+        assert_eq!(header_.type_, PageType::DICTIONARY_PAGE);
+        let mut data = encrypt_ctr(&key, &[0; 12], b"dict")?;
+        decryptor.decrypt_page(&mut data)?;
+        assert_eq!(data, b"dict");
+
+        for page in 0..2 {
+            let module = encrypt_gcm(
+                &key,
+                &[0; 12],
+                &aad(ModuleType::DataPageHeader, Some(page)),
+                &header(PageType::DATA_PAGE),
+            )?;
+            let header_ = decryptor.decrypt_header(&module)?;
+            assert_eq!(header_.type_, PageType::DATA_PAGE);
+            let mut data = encrypt_ctr(&key, &[0; 12], b"data")?;
+            decryptor.decrypt_page(&mut data)?;
+            assert_eq!(data, b"data");
+        }
+
+        // the header of a data page is authenticated with its ordinal
+        let module = encrypt_gcm(
+            &key,
+            &[0; 12],
+            &aad(ModuleType::DataPageHeader, Some(0)),
+            &header(PageType::DATA_PAGE),
+        )?;
+        assert!(decryptor.decrypt_header(&module).is_err());
+        decryptor.seek(0);
+        assert!(decryptor.decrypt_header(&module).is_ok());
+        Ok(())
+    }
+}
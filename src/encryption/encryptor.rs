@@ -0,0 +1,442 @@
+use parquet_format_safe::thrift::protocol::TCompactOutputProtocol;
+use parquet_format_safe::{
+    AesGcmCtrV1, AesGcmV1, ColumnChunk, ColumnCryptoMetaData, EncryptionAlgorithm,
+    EncryptionWithColumnKey, EncryptionWithFooterKey, FileCryptoMetaData, PageType,
+};
+
+use crate::error::Result;
+use crate::metadata::{SchemaDescriptor, ThriftFileMetaData};
+use crate::page::ParquetPageHeader;
+use crate::{PARQUET_ENCRYPTED_MAGIC, PARQUET_MAGIC};
+
+use super::cipher::{check_key, encrypt_ctr, encrypt_gcm, gcm_tag, random_bytes, NONCE_LEN};
+use super::{
+    module_aad, Algorithm, ColumnEncryption, EncryptionKey, FileEncryptionProperties, ModuleType,
+};
+
+/// The number of random bytes of the AAD that makes it unique to every file
+const AAD_FILE_UNIQUE_LEN: usize = 8;
+
+/// Encrypts the modules of a file, as declared by its [`FileEncryptionProperties`]
+pub(crate) struct FileEncryptor {
+    algorithm: EncryptionAlgorithm,
+    encrypted_footer: bool,
+    footer_key: EncryptionKey,
+    aad_file: Vec<u8>,
+    // the path and encryption of every column of the schema
+    columns: Vec<(Vec<String>, ColumnEncryption)>,
+}
+
+impl FileEncryptor {
+    /// Returns a new [`FileEncryptor`], retrieving the keys of the columns of `schema`.
+    pub fn try_new(
+        properties: &FileEncryptionProperties,
+        schema: &SchemaDescriptor,
+    ) -> Result<Self> {
+        let footer_key = properties.key_provider.footer_key()?;
+        check_key(&footer_key.key)?;
+        let columns = schema
+            .columns()
+            .iter()
+            .map(|column| {
+                let path = column.path_in_schema.clone();
+                let encryption = properties.key_provider.column_encryption(&path)?;
+                if let ColumnEncryption::ColumnKey(key) = &encryption {
+                    check_key(&key.key)?;
+                }
+                Ok((path, encryption))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let aad_file_unique = random_bytes::<AAD_FILE_UNIQUE_LEN>().to_vec();
+        let aad_prefix = properties.aad_prefix.as_deref().unwrap_or_default();
+        let aad_file = [aad_prefix, &aad_file_unique].concat();
+
+        let (aad_prefix, supply_aad_prefix) = match &properties.aad_prefix {
+            Some(prefix) if properties.store_aad_prefix => (Some(prefix.clone()), None),
+            Some(_) => (None, Some(true)),
+            None => (None, None),
+        };
+        let algorithm = match properties.algorithm {
+            Algorithm::AesGcm => EncryptionAlgorithm::AESGCMV1(AesGcmV1::new(
+                aad_prefix,
+                aad_file_unique,
+                supply_aad_prefix,
+            )),
+            Algorithm::AesGcmCtr => EncryptionAlgorithm::AESGCMCTRV1(AesGcmCtrV1::new(
+                aad_prefix,
+                aad_file_unique,
+                supply_aad_prefix,
+            )),
+        };
+
+        Ok(Self {
+            algorithm,
+            encrypted_footer: properties.encrypted_footer,
+            footer_key,
+            aad_file,
+            columns,
+        })
+    }
+
+    /// Returns the key of `column`, or `None` if it is not encrypted.
+    fn column_key(&self, column: usize) -> Option<&[u8]> {
+        match &self.columns[column].1 {
+            ColumnEncryption::Plaintext => None,
+            ColumnEncryption::FooterKey => Some(&self.footer_key.key),
+            ColumnEncryption::ColumnKey(key) => Some(&key.key),
+        }
+    }
+
+    /// Returns the encryptor of the column chunk `column` of the row group `row_group`, or
+    /// `None` if the column is not encrypted.
+    pub fn column(&self, row_group: usize, column: usize) -> Result<Option<ColumnEncryptor>> {
+        self.column_key(column)
+            .map(|key| {
+                Ok(ColumnEncryptor {
+                    key: key.to_vec(),
+                    aad_file: self.aad_file.clone(),
+                    ctr: matches!(self.algorithm, EncryptionAlgorithm::AESGCMCTRV1(_)),
+                    row_group: row_group.try_into()?,
+                    column: column.try_into()?,
+                })
+            })
+            .transpose()
+    }
+
+    /// Sets the crypto metadata of `column_chunk` and encrypts its metadata when required:
+    /// metadata of columns encrypted with their own key and, when the footer is in plaintext,
+    /// of all encrypted columns.
+    fn encrypt_column_chunk(
+        &self,
+        column_chunk: &mut ColumnChunk,
+        row_group: usize,
+        column: usize,
+    ) -> Result<()> {
+        let (path, encryption) = &self.columns[column];
+        column_chunk.crypto_metadata = match encryption {
+            ColumnEncryption::Plaintext => return Ok(()),
+            ColumnEncryption::FooterKey => Some(ColumnCryptoMetaData::ENCRYPTIONWITHFOOTERKEY(
+                EncryptionWithFooterKey {},
+            )),
+            ColumnEncryption::ColumnKey(key) => Some(
+                ColumnCryptoMetaData::ENCRYPTIONWITHCOLUMNKEY(EncryptionWithColumnKey {
+                    path_in_schema: path.clone(),
+                    key_metadata: key.key_metadata.clone(),
+                }),
+            ),
+        };
+        if self.encrypted_footer && *encryption == ColumnEncryption::FooterKey {
+            // the metadata is encrypted with the footer
+            return Ok(());
+        }
+
+        let mut metadata = vec![];
+        if let Some(meta_data) = &column_chunk.meta_data {
+            meta_data.write_to_out_protocol(&mut TCompactOutputProtocol::new(&mut metadata))?;
+        }
+        let encryptor = self.column(row_group, column)?.unwrap();
+        column_chunk.encrypted_column_metadata =
+            Some(encryptor.encrypt(ModuleType::ColumnMetaData, None, &metadata)?);
+
+        if self.encrypted_footer {
+            column_chunk.meta_data = None;
+        } else if let Some(meta_data) = column_chunk.meta_data.as_mut() {
+            // readers without the key can read the plaintext footer: only keep the metadata
+            // that does not leak the column's values
+            meta_data.statistics = None;
+            meta_data.encoding_stats = None;
+        }
+        Ok(())
+    }
+
+    /// Returns the magic that starts and ends the file: `PARE` when the footer is encrypted
+    /// and `PAR1` otherwise.
+    pub fn magic(&self) -> [u8; 4] {
+        if self.encrypted_footer {
+            PARQUET_ENCRYPTED_MAGIC
+        } else {
+            PARQUET_MAGIC
+        }
+    }
+
+    /// Returns the footer of the file with `metadata`, i.e. the bytes before the footer's
+    /// length, and the magic that ends the file.
+    pub fn footer(&self, metadata: &ThriftFileMetaData) -> Result<(Vec<u8>, [u8; 4])> {
+        let mut metadata = metadata.clone();
+        for (i, row_group) in metadata.row_groups.iter_mut().enumerate() {
+            for (j, column_chunk) in row_group.columns.iter_mut().enumerate() {
+                self.encrypt_column_chunk(column_chunk, i, j)?;
+            }
+        }
+        let aad = module_aad(&self.aad_file, ModuleType::Footer, 0, 0, None);
+        let nonce = random_bytes::<NONCE_LEN>();
+
+        let mut footer = vec![];
+        if self.encrypted_footer {
+            // [FileCryptoMetaData][encrypted FileMetaData]
+            let crypto = FileCryptoMetaData::new(
+                self.algorithm.clone(),
+                self.footer_key.key_metadata.clone(),
+            );
+            crypto.write_to_out_protocol(&mut TCompactOutputProtocol::new(&mut footer))?;
+
+            let mut plaintext = vec![];
+            metadata.write_to_out_protocol(&mut TCompactOutputProtocol::new(&mut plaintext))?;
+            footer.extend(encrypt_gcm(&self.footer_key.key, &nonce, &aad, &plaintext)?);
+            Ok((footer, self.magic()))
+        } else {
+            // [FileMetaData][nonce][tag]
+            metadata.encryption_algorithm = Some(self.algorithm.clone());
+            metadata.footer_signing_key_metadata = self.footer_key.key_metadata.clone();
+            metadata.write_to_out_protocol(&mut TCompactOutputProtocol::new(&mut footer))?;
+
+            let tag = gcm_tag(&self.footer_key.key, &nonce, &aad, &footer)?;
+            footer.extend_from_slice(&nonce);
+            footer.extend(tag);
+            Ok((footer, self.magic()))
+        }
+    }
+}
+
+/// Encrypts the modules (pages, indexes, bloom filters) of an encrypted column chunk.
+#[derive(Clone)]
+pub(crate) struct ColumnEncryptor {
+    key: Vec<u8>,
+    aad_file: Vec<u8>,
+    // whether pages are encrypted with AES-CTR instead of AES-GCM
+    ctr: bool,
+    row_group: i16,
+    column: i16,
+}
+
+impl ColumnEncryptor {
+    /// Encrypts `data` into a module whose type is `module_type` and that belongs to the data
+    /// page `page`.
+    fn encrypt(&self, module_type: ModuleType, page: Option<i16>, data: &[u8]) -> Result<Vec<u8>> {
+        let nonce = random_bytes::<NONCE_LEN>();
+        if self.ctr
+            && matches!(
+                module_type,
+                ModuleType::DataPage | ModuleType::DictionaryPage
+            )
+        {
+            encrypt_ctr(&self.key, &nonce, data)
+        } else {
+            let aad = module_aad(
+                &self.aad_file,
+                module_type,
+                self.row_group,
+                self.column,
+                page,
+            );
+            encrypt_gcm(&self.key, &nonce, &aad, data)
+        }
+    }
+
+    /// Encrypts a serialized column index.
+    pub fn encrypt_column_index(&self, index: &[u8]) -> Result<Vec<u8>> {
+        self.encrypt(ModuleType::ColumnIndex, None, index)
+    }
+
+    /// Encrypts a serialized offset index.
+    pub fn encrypt_offset_index(&self, index: &[u8]) -> Result<Vec<u8>> {
+        self.encrypt(ModuleType::OffsetIndex, None, index)
+    }
+
+    /// Encrypts the serialized header and the bitset of a bloom filter.
+    pub fn encrypt_bloom_filter(&self, header: &[u8], bitset: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+        Ok((
+            self.encrypt(ModuleType::BloomFilterHeader, None, header)?,
+            self.encrypt(ModuleType::BloomFilterBitset, None, bitset)?,
+        ))
+    }
+}
+
+/// Encrypts the pages of a column chunk in the order they are written.
+pub(crate) struct PageEncryptor {
+    column: ColumnEncryptor,
+    // the ordinal of the next data page
+    page_ordinal: usize,
+}
+
+impl PageEncryptor {
+    pub fn new(column: ColumnEncryptor) -> Self {
+        Self {
+            column,
+            page_ordinal: 0,
+        }
+    }
+
+    /// Returns the module types of the header and of the data of the page with `header`, and
+    /// its ordinal.
+    fn next_page(
+        &self,
+        header: &ParquetPageHeader,
+    ) -> Result<(ModuleType, ModuleType, Option<i16>)> {
+        Ok(if header.type_ == PageType::DICTIONARY_PAGE {
+            (
+                ModuleType::DictionaryPageHeader,
+                ModuleType::DictionaryPage,
+                None,
+            )
+        } else {
+            (
+                ModuleType::DataPageHeader,
+                ModuleType::DataPage,
+                Some(self.page_ordinal.try_into()?),
+            )
+        })
+    }
+
+    /// Encrypts the data of the next page into a module. The compressed size of its `header` is
+    /// set to the size of the module.
+    pub fn encrypt_page(&self, header: &mut ParquetPageHeader, data: &[u8]) -> Result<Vec<u8>> {
+        let (_, module_type, page) = self.next_page(header)?;
+        let module = self.column.encrypt(module_type, page, data)?;
+        header.compressed_page_size = module.len().try_into()?;
+        Ok(module)
+    }
+
+    /// Encrypts the `header` of the next page into a module and moves to the page after it.
+    pub fn encrypt_header(&mut self, header: &ParquetPageHeader) -> Result<Vec<u8>> {
+        let (module_type, _, page) = self.next_page(header)?;
+        let mut buffer = vec![];
+        header.write_to_out_protocol(&mut TCompactOutputProtocol::new(&mut buffer))?;
+        let module = self.column.encrypt(module_type, page, &buffer)?;
+        if page.is_some() {
+            self.page_ordinal += 1;
+        }
+        Ok(module)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::encryption::{decrypt_metadata, FileDecryptionProperties, KeyProvider};
+    use crate::schema::types::{ParquetType, PhysicalType};
+
+    struct Provider;
+
+    impl KeyProvider for Provider {
+        fn footer_key(&self) -> Result<EncryptionKey> {
+            Ok(EncryptionKey::with_metadata(
+                vec![1; 16],
+                b"footer".to_vec(),
+            ))
+        }
+
+        fn column_encryption(&self, path: &[String]) -> Result<ColumnEncryption> {
+            Ok(match path[0].as_str() {
+                "a" => ColumnEncryption::ColumnKey(EncryptionKey::new(vec![2; 16])),
+                "b" => ColumnEncryption::FooterKey,
+                _ => ColumnEncryption::Plaintext,
+            })
+        }
+    }
+
+    fn metadata(schema: &SchemaDescriptor) -> ThriftFileMetaData {
+        let columns = schema
+            .columns()
+            .iter()
+            .map(|column| ColumnChunk {
+                file_path: None,
+                file_offset: 4,
+                meta_data: Some(parquet_format_safe::ColumnMetaData {
+                    type_: parquet_format_safe::Type::INT32,
+                    encodings: vec![],
+                    path_in_schema: column.path_in_schema.clone(),
+                    codec: parquet_format_safe::CompressionCodec::UNCOMPRESSED,
+                    num_values: 0,
+                    total_uncompressed_size: 0,
+                    total_compressed_size: 0,
+                    key_value_metadata: None,
+                    data_page_offset: 4,
+                    index_page_offset: None,
+                    dictionary_page_offset: None,
+                    statistics: Some(parquet_format_safe::Statistics::new(
+                        None,
+                        None,
+                        Some(0),
+                        None,
+                        None,
+                        None,
+                    )),
+                    encoding_stats: None,
+                    bloom_filter_offset: None,
+                }),
+                offset_index_offset: None,
+                offset_index_length: None,
+                column_index_offset: None,
+                column_index_length: None,
+                crypto_metadata: None,
+                encrypted_column_metadata: None,
+            })
+            .collect();
+        ThriftFileMetaData::new(
+            1,
+            schema.clone().into_thrift(),
+            0,
+            vec![parquet_format_safe::RowGroup::new(
+                columns,
+                0,
+                0,
+                None,
+                None,
+                None,
+                Some(0),
+            )],
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    fn footer() -> Result<()> {
+        let schema = SchemaDescriptor::new(
+            "schema".to_string(),
+            ["a", "b", "c"]
+                .iter()
+                .map(|name| ParquetType::from_physical(name.to_string(), PhysicalType::Int32))
+                .collect(),
+        );
+        let metadata = metadata(&schema);
+        let decryption = FileDecryptionProperties {
+            footer_key: Some(vec![1; 16]),
+            column_keys: HashMap::from([(vec!["a".to_string()], vec![2; 16])]),
+            ..Default::default()
+        };
+
+        for encrypted_footer in [true, false] {
+            let mut properties = FileEncryptionProperties::new(Arc::new(Provider));
+            properties.encrypted_footer = encrypted_footer;
+            let encryptor = FileEncryptor::try_new(&properties, &schema)?;
+            let (footer, magic) = encryptor.footer(&metadata)?;
+            assert_eq!(magic == PARQUET_ENCRYPTED_MAGIC, encrypted_footer);
+
+            let result = decrypt_metadata(&footer, encrypted_footer, &decryption)?;
+            let columns = result.row_groups[0].columns();
+            assert!(columns[0].decryptor().is_some());
+            assert!(columns[1].decryptor().is_some());
+            assert!(columns[2].decryptor().is_none());
+            for column in columns {
+                assert_eq!(column.data_page_offset(), 4);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_key() {
+        let schema = SchemaDescriptor::new("schema".to_string(), vec![]);
+        let properties = FileEncryptionProperties::new(Arc::new(EncryptionKey::new(vec![1; 10])));
+        assert!(FileEncryptor::try_new(&properties, &schema).is_err());
+    }
+}
@@ -0,0 +1,80 @@
+//! API to read and write files encrypted with
+//! [parquet modular encryption](https://github.com/apache/parquet-format/blob/master/Encryption.md).
+//!
+//! Encrypted files are read by passing [`FileDecryptionProperties`] to
+//! [`read_metadata_with_decryption`](crate::read::read_metadata_with_decryption). The pages,
+//! indexes and bloom filters of the encrypted columns are then decrypted by the readers of
+//! this crate.
+//!
+//! Encrypted files are written by passing [`FileEncryptionProperties`] to
+//! [`FileWriter::with_encryption`](crate::write::FileWriter::with_encryption), whose keys are
+//! provided by a [`KeyProvider`].
+mod cipher;
+mod decryptor;
+mod encryptor;
+mod properties;
+
+#[cfg(feature = "async")]
+pub(crate) use decryptor::read_module_async;
+pub use decryptor::ColumnDecryptor;
+pub(crate) use decryptor::{decrypt_metadata, read_module, PageDecryptor};
+pub(crate) use encryptor::{ColumnEncryptor, FileEncryptor, PageEncryptor};
+pub use properties::{
+    Algorithm, ColumnEncryption, EncryptionKey, FileDecryptionProperties, FileEncryptionProperties,
+    KeyProvider, KeyRetriever,
+};
+
+/// The type of an encrypted module, used to build its additional authenticated data (AAD)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ModuleType {
+    Footer = 0,
+    ColumnMetaData = 1,
+    DataPage = 2,
+    DictionaryPage = 3,
+    DataPageHeader = 4,
+    DictionaryPageHeader = 5,
+    ColumnIndex = 6,
+    OffsetIndex = 7,
+    BloomFilterHeader = 8,
+    BloomFilterBitset = 9,
+}
+
+/// Returns the AAD of a module: the file's AAD followed by the module type and, except for the
+/// footer, the (little-endian) ordinals of the row group, of the column and of the (data) page.
+pub(crate) fn module_aad(
+    aad_file: &[u8],
+    module_type: ModuleType,
+    row_group: i16,
+    column: i16,
+    page: Option<i16>,
+) -> Vec<u8> {
+    let mut aad = Vec::with_capacity(aad_file.len() + 7);
+    aad.extend_from_slice(aad_file);
+    aad.push(module_type as u8);
+    if module_type == ModuleType::Footer {
+        return aad;
+    }
+    aad.extend_from_slice(&row_group.to_le_bytes());
+    aad.extend_from_slice(&column.to_le_bytes());
+    if let Some(page) = page {
+        aad.extend_from_slice(&page.to_le_bytes());
+    }
+    aad
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aad() {
+        let aad = module_aad(b"file", ModuleType::Footer, 1, 2, None);
+        assert_eq!(aad, b"file\x00");
+
+        let aad = module_aad(b"file", ModuleType::ColumnIndex, 1, 2, None);
+        assert_eq!(aad, b"file\x06\x01\x00\x02\x00");
+
+        let aad = module_aad(b"file", ModuleType::DataPageHeader, 1, 2, Some(258));
+        assert_eq!(aad, b"file\x04\x01\x00\x02\x00\x02\x01");
+    }
+}
@@ -0,0 +1,226 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::error::{Error, Result};
+
+/// Retrieves the keys of an encrypted file from the key metadata stored in it, e.g. by
+/// unwrapping them with a key management service.
+pub trait KeyRetriever: Send + Sync {
+    /// Returns the key identified by `key_metadata`.
+    fn retrieve_key(&self, key_metadata: &[u8]) -> Result<Vec<u8>>;
+}
+
+/// The keys and options used to decrypt an encrypted file.
+///
+/// Keys are taken from `footer_key` and `column_keys` when set and retrieved via
+/// `key_retriever` otherwise.
+#[derive(Clone)]
+pub struct FileDecryptionProperties {
+    /// The key of the footer and of the columns encrypted with it
+    pub footer_key: Option<Vec<u8>>,
+    /// The keys of the columns encrypted with their own key, by their path in the schema
+    pub column_keys: HashMap<Vec<String>, Vec<u8>>,
+    /// Retrieves the keys that are not set
+    pub key_retriever: Option<Arc<dyn KeyRetriever>>,
+    /// The AAD prefix, required when the file was written without storing it
+    pub aad_prefix: Option<Vec<u8>>,
+    /// Whether the signature of plaintext footers is verified. Defaults to `true`.
+    pub verify_plaintext_footer: bool,
+}
+
+impl Default for FileDecryptionProperties {
+    fn default() -> Self {
+        Self {
+            footer_key: None,
+            column_keys: HashMap::new(),
+            key_retriever: None,
+            aad_prefix: None,
+            verify_plaintext_footer: true,
+        }
+    }
+}
+
+// keys are not printed
+impl std::fmt::Debug for FileDecryptionProperties {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FileDecryptionProperties")
+            .field("footer_key", &self.footer_key.as_ref().map(|_| "..."))
+            .field("column_keys", &self.column_keys.keys().collect::<Vec<_>>())
+            .field("key_retriever", &self.key_retriever.as_ref().map(|_| "..."))
+            .field("aad_prefix", &self.aad_prefix)
+            .field("verify_plaintext_footer", &self.verify_plaintext_footer)
+            .finish()
+    }
+}
+
+impl FileDecryptionProperties {
+    /// Returns [`FileDecryptionProperties`] of a file whose footer and columns are all
+    /// encrypted with `footer_key`.
+    pub fn new(footer_key: Vec<u8>) -> Self {
+        Self {
+            footer_key: Some(footer_key),
+            ..Default::default()
+        }
+    }
+
+    /// Returns [`FileDecryptionProperties`] that retrieve all keys with `key_retriever`.
+    pub fn with_key_retriever(key_retriever: Arc<dyn KeyRetriever>) -> Self {
+        Self {
+            key_retriever: Some(key_retriever),
+            ..Default::default()
+        }
+    }
+
+    fn retrieve(&self, key_metadata: Option<&[u8]>, name: &str) -> Result<Option<Vec<u8>>> {
+        self.key_retriever
+            .as_ref()
+            .map(|retriever| retriever.retrieve_key(key_metadata.unwrap_or_default()))
+            .transpose()
+            .map_err(|e| Error::General(format!("Failed to retrieve the key of {}: {}", name, e)))
+    }
+
+    /// Returns the key of the footer, if available.
+    pub(super) fn footer_key(&self, key_metadata: Option<&[u8]>) -> Result<Option<Vec<u8>>> {
+        match &self.footer_key {
+            Some(key) => Ok(Some(key.clone())),
+            None => self.retrieve(key_metadata, "the footer"),
+        }
+    }
+
+    /// Returns the key of the column at `path`.
+    pub(super) fn column_key(
+        &self,
+        path: &[String],
+        key_metadata: Option<&[u8]>,
+    ) -> Result<Vec<u8>> {
+        if let Some(key) = self.column_keys.get(path) {
+            return Ok(key.clone());
+        }
+        let name = format!("the column \"{}\"", path.join("."));
+        self.retrieve(key_metadata, &name)?.ok_or_else(|| {
+            Error::InvalidParameter(format!("The key of {} is required to decrypt it", name))
+        })
+    }
+}
+
+/// A key used to encrypt a file and the metadata stored in the file to retrieve it when
+/// decrypting it, e.g. its identifier in a key management service.
+#[derive(Clone, PartialEq, Eq)]
+pub struct EncryptionKey {
+    /// The AES key, of 16, 24 or 32 bytes
+    pub key: Vec<u8>,
+    /// The metadata stored in the file, passed to [`KeyRetriever::retrieve_key`]
+    pub key_metadata: Option<Vec<u8>>,
+}
+
+// the key is not printed
+impl std::fmt::Debug for EncryptionKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EncryptionKey")
+            .field("key_metadata", &self.key_metadata)
+            .finish()
+    }
+}
+
+impl EncryptionKey {
+    /// Returns an [`EncryptionKey`] without key metadata.
+    pub fn new(key: Vec<u8>) -> Self {
+        Self {
+            key,
+            key_metadata: None,
+        }
+    }
+
+    /// Returns an [`EncryptionKey`] with `key_metadata`.
+    pub fn with_metadata(key: Vec<u8>, key_metadata: Vec<u8>) -> Self {
+        Self {
+            key,
+            key_metadata: Some(key_metadata),
+        }
+    }
+}
+
+/// How a column is encrypted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ColumnEncryption {
+    /// The column is not encrypted
+    Plaintext,
+    /// The column is encrypted with the footer key
+    FooterKey,
+    /// The column is encrypted with its own key
+    ColumnKey(EncryptionKey),
+}
+
+/// Provides the keys used to encrypt a file, e.g. by generating and wrapping them with a key
+/// management service.
+pub trait KeyProvider: Send + Sync {
+    /// Returns the key of the footer, also used to encrypt the columns encrypted with it.
+    fn footer_key(&self) -> Result<EncryptionKey>;
+
+    /// Returns how the column at `path` is encrypted. Defaults to the footer key (uniform
+    /// encryption).
+    fn column_encryption(&self, _path: &[String]) -> Result<ColumnEncryption> {
+        Ok(ColumnEncryption::FooterKey)
+    }
+}
+
+/// Encrypts the footer and all columns with the same key.
+impl KeyProvider for EncryptionKey {
+    fn footer_key(&self) -> Result<EncryptionKey> {
+        Ok(self.clone())
+    }
+}
+
+/// The algorithm used to encrypt a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Algorithm {
+    /// All modules are encrypted with AES-GCM (`AES_GCM_V1`)
+    AesGcm,
+    /// Pages are encrypted with AES-CTR and the other modules with AES-GCM
+    /// (`AES_GCM_CTR_V1`)
+    AesGcmCtr,
+}
+
+/// The keys and options used to encrypt a file.
+#[derive(Clone)]
+pub struct FileEncryptionProperties {
+    /// Provides the keys of the footer and of the columns
+    pub key_provider: Arc<dyn KeyProvider>,
+    /// The algorithm used to encrypt the file. Defaults to [`Algorithm::AesGcm`].
+    pub algorithm: Algorithm,
+    /// Whether the footer is encrypted or written in plaintext (and signed with the footer key),
+    /// so that readers without the keys can read the schema and the plaintext columns.
+    /// Defaults to `true`.
+    pub encrypted_footer: bool,
+    /// A prefix of the AAD of every module, e.g. the name of the file, that binds the file
+    /// to it.
+    pub aad_prefix: Option<Vec<u8>>,
+    /// Whether `aad_prefix` is stored in the file. Defaults to `true`; when `false`, readers
+    /// must pass it to decrypt the file.
+    pub store_aad_prefix: bool,
+}
+
+// keys are not printed
+impl std::fmt::Debug for FileEncryptionProperties {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FileEncryptionProperties")
+            .field("algorithm", &self.algorithm)
+            .field("encrypted_footer", &self.encrypted_footer)
+            .field("aad_prefix", &self.aad_prefix)
+            .field("store_aad_prefix", &self.store_aad_prefix)
+            .finish()
+    }
+}
+
+impl FileEncryptionProperties {
+    /// Returns [`FileEncryptionProperties`] whose keys are provided by `key_provider`.
+    pub fn new(key_provider: Arc<dyn KeyProvider>) -> Self {
+        Self {
+            key_provider,
+            algorithm: Algorithm::AesGcm,
+            encrypted_footer: true,
+            aad_prefix: None,
+            store_aad_prefix: true,
+        }
+    }
+}
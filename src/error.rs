@@ -16,6 +16,10 @@ pub enum Feature {
     Lz4,
     /// Zstd compression and decompression
     Zstd,
+    /// Lzo compression and decompression
+    Lzo,
+    /// Parquet modular encryption
+    Encryption,
 }
 
 /// Errors generated by this crate
@@ -28,6 +32,15 @@ pub enum Error {
     FeatureNotActive(Feature, String),
     /// When the parquet file is known to be out of spec.
     OutOfSpec(String),
+    /// When a parameter passed to this crate is invalid.
+    InvalidParameter(String),
+    /// When the CRC32 checksum of a page does not match its data, i.e. the page is corrupted.
+    ChecksumMismatch {
+        /// The checksum declared in the page's header
+        expected: u32,
+        /// The checksum of the page's data
+        actual: u32,
+    },
     /// An error originating from a consumer or dependency
     External(String, Arc<dyn std::error::Error + Send + Sync>),
 }
@@ -50,6 +63,16 @@ impl std::fmt::Display for Error {
             Error::OutOfSpec(message) => {
                 write!(fmt, "{}", message)
             }
+            Error::InvalidParameter(message) => {
+                write!(fmt, "Invalid parameter: {}", message)
+            }
+            Error::ChecksumMismatch { expected, actual } => {
+                write!(
+                    fmt,
+                    "The checksum of a page ({:#010x}) does not match the checksum of its data ({:#010x})",
+                    expected, actual
+                )
+            }
             Error::External(message, err) => {
                 write!(fmt, "{}: {}", message, err)
             }
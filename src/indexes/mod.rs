@@ -5,6 +5,7 @@ pub use crate::parquet_bridge::BoundaryOrder;
 pub use crate::thrift_format::PageLocation;
 
 pub use self::index::{BooleanIndex, ByteIndex, FixedLenByteIndex, Index, NativeIndex, PageIndex};
+pub(crate) use intervals::compute_page_row_intervals;
 pub use intervals::{compute_rows, select_pages, FilteredPage, Interval};
 
 #[cfg(test)]
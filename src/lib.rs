@@ -8,6 +8,8 @@ pub mod bloom_filter;
 pub mod compression;
 pub mod deserialize;
 pub mod encoding;
+#[cfg(feature = "encryption")]
+pub mod encryption;
 pub mod indexes;
 pub mod metadata;
 pub mod page;
@@ -26,6 +28,8 @@ pub use streaming_decompression::FallibleStreamingIterator;
 const HEADER_SIZE: u64 = PARQUET_MAGIC.len() as u64;
 const FOOTER_SIZE: u64 = 8;
 const PARQUET_MAGIC: [u8; 4] = [b'P', b'A', b'R', b'1'];
+/// The magic of files whose footer is encrypted
+const PARQUET_ENCRYPTED_MAGIC: [u8; 4] = [b'P', b'A', b'R', b'E'];
 
 /// The number of bytes read at the end of the parquet file on first read
 const DEFAULT_FOOTER_READ_SIZE: u64 = 64 * 1024;
@@ -4,17 +4,28 @@ use parquet_format_safe::{ColumnChunk, ColumnMetaData, Encoding};
 
 use super::column_descriptor::ColumnDescriptor;
 use crate::compression::Compression;
+#[cfg(feature = "encryption")]
+use crate::encryption::ColumnDecryptor;
 use crate::error::{Error, Result};
+use crate::read::encrypted_column_error;
 use crate::schema::types::PhysicalType;
 use crate::statistics::{deserialize_statistics, Statistics};
 
 /// Metadata for a column chunk.
+///
+/// The metadata of an encrypted column chunk that can't be decrypted, because its key is not
+/// available, is a placeholder: it has no values, no statistics and no pages, i.e.
+/// [`Self::num_values`] is 0 and [`Self::byte_range`] is `(0, 0)`. Use
+/// [`Self::check_decryption`] to tell them apart from the metadata of an empty column chunk.
 // This contains the `ColumnDescriptor` associated with the chunk so that deserializers have
 // access to the descriptor (e.g. physical, converted, logical).
 #[derive(Debug, Clone)]
 pub struct ColumnChunkMetaData {
     column_chunk: ColumnChunk,
     column_descr: ColumnDescriptor,
+    // decrypts the modules of the column chunk when it is encrypted
+    #[cfg(feature = "encryption")]
+    pub(crate) decryptor: Option<Arc<ColumnDecryptor>>,
 }
 
 // Represents common operations for a column chunk.
@@ -24,6 +35,8 @@ impl ColumnChunkMetaData {
         Self {
             column_chunk,
             column_descr,
+            #[cfg(feature = "encryption")]
+            decryptor: None,
         }
     }
 
@@ -127,6 +140,35 @@ impl ColumnChunkMetaData {
         (start, length)
     }
 
+    /// Returns the [`ColumnDecryptor`] of this column chunk, if it is encrypted.
+    #[cfg(feature = "encryption")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "encryption")))]
+    pub fn decryptor(&self) -> Option<&Arc<ColumnDecryptor>> {
+        self.decryptor.as_ref()
+    }
+
+    /// Whether this column chunk is encrypted, i.e. its pages, indexes and bloom filter can
+    /// only be read when the metadata of its file is read with decryption.
+    pub fn is_encrypted(&self) -> bool {
+        self.column_chunk.crypto_metadata.is_some()
+    }
+
+    /// Checks that this column chunk can be read, i.e. that it is not encrypted or that it can be
+    /// decrypted.
+    /// # Error
+    /// Errors if this column chunk is encrypted but can't be decrypted, because it has no
+    /// decryptor or its key is not available. Its metadata is then a placeholder.
+    pub fn check_decryption(&self) -> Result<()> {
+        #[cfg(feature = "encryption")]
+        if let Some(decryptor) = self.decryptor() {
+            return decryptor.key().map(|_| ());
+        }
+        if self.is_encrypted() {
+            return Err(encrypted_column_error());
+        }
+        Ok(())
+    }
+
     /// Method to convert from Thrift.
     pub(crate) fn try_from_thrift(
         column_descr: ColumnDescriptor,
@@ -148,10 +190,7 @@ impl ColumnChunkMetaData {
             ));
         }
 
-        Ok(Self {
-            column_chunk,
-            column_descr,
-        })
+        Ok(Self::new(column_chunk, column_descr))
     }
 
     /// Method to convert to Thrift.
@@ -1,4 +1,5 @@
 use crate::schema::types::{ParquetType, PrimitiveType};
+use crate::schema::Repetition;
 
 /// A descriptor of a parquet column. It contains the necessary information to deserialize
 /// a parquet column.
@@ -42,4 +43,26 @@ impl ColumnDescriptor {
             base_type,
         }
     }
+
+    /// Returns the [`Repetition`] of each field of the path of this column, from the field of
+    /// the schema to the leaf, e.g. to decode its levels with
+    /// [`NestedDecoder`](crate::deserialize::NestedDecoder).
+    pub fn repetitions(&self) -> Vec<Repetition> {
+        let mut repetitions = vec![self.base_type.get_field_info().repetition];
+        let mut current = &self.base_type;
+        for name in self.path_in_schema.iter().skip(1) {
+            let field = match current {
+                ParquetType::GroupType { fields, .. } => fields.iter().find(|f| f.name() == name),
+                ParquetType::PrimitiveType(_) => None,
+            };
+            match field {
+                Some(field) => {
+                    repetitions.push(field.get_field_info().repetition);
+                    current = field;
+                }
+                None => break,
+            }
+        }
+        repetitions
+    }
 }
@@ -1,6 +1,9 @@
 use crate::{error::Error, metadata::get_sort_order};
 
-use super::{column_order::ColumnOrder, schema_descriptor::SchemaDescriptor, RowGroupMetaData};
+use super::{
+    column_order::ColumnOrder, schema_descriptor::SchemaDescriptor, ColumnChunkMetaData,
+    RowGroupMetaData,
+};
 use parquet_format_safe::ColumnOrder as TColumnOrder;
 
 pub use crate::thrift_format::KeyValue;
@@ -59,6 +62,27 @@ impl FileMetaData {
             .unwrap_or(ColumnOrder::Undefined)
     }
 
+    /// Resolves the columns of the schema `requested` to the columns of this file by their
+    /// field id (see [`SchemaDescriptor::project_by_id`]), returning, for every row group, the
+    /// [`ColumnChunkMetaData`] of every column of `requested`, or `None` if this file does not
+    /// have it.
+    pub fn project_by_id(
+        &self,
+        requested: &SchemaDescriptor,
+    ) -> Result<Vec<Vec<Option<&ColumnChunkMetaData>>>, Error> {
+        let columns = self.schema_descr.project_by_id(requested)?;
+        Ok(self
+            .row_groups
+            .iter()
+            .map(|row_group| {
+                columns
+                    .iter()
+                    .map(|column| column.map(|column| &row_group.columns()[column]))
+                    .collect()
+            })
+            .collect())
+    }
+
     /// Deserializes [`crate::thrift_format::FileMetaData`] into this struct
     pub fn try_from_thrift(metadata: parquet_format_safe::FileMetaData) -> Result<Self, Error> {
         let schema_descr = SchemaDescriptor::try_from_thrift(&metadata.schema)?;
@@ -33,6 +33,11 @@ impl RowGroupMetaData {
         &self.columns
     }
 
+    #[cfg(feature = "encryption")]
+    pub(crate) fn columns_mut(&mut self) -> &mut [ColumnChunkMetaData] {
+        &mut self.columns
+    }
+
     /// Number of rows in this row group.
     pub fn num_rows(&self) -> usize {
         self.num_rows
@@ -2,7 +2,11 @@ use parquet_format_safe::SchemaElement;
 
 use crate::{
     error::Error,
-    schema::{io_message::from_message, types::ParquetType, Repetition},
+    schema::{
+        io_message::{from_message, write_message},
+        types::ParquetType,
+        Repetition,
+    },
 };
 use crate::{error::Result, schema::types::FieldInfo};
 
@@ -90,6 +94,126 @@ impl SchemaDescriptor {
         let schema = from_message(message)?;
         Self::try_from_type(schema)
     }
+
+    /// Returns the path of the field with id `id`, searching nested fields, if any.
+    pub fn path_by_id(&self, id: i32) -> Option<Vec<String>> {
+        let mut path = vec![];
+        find_path_by_id(&self.fields, id, &mut path).then_some(path)
+    }
+
+    /// Returns the indices in [`SchemaDescriptor::columns`] of the columns of the field with
+    /// id `id`, searching nested fields. For primitive fields, this has exactly one item, and
+    /// it is empty when no field has id `id`.
+    pub fn columns_by_id(&self, id: i32) -> Vec<usize> {
+        self.path_by_id(id)
+            .map(|path| {
+                self.leaves
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, column)| column.path_in_schema.starts_with(&path))
+                    .map(|(index, _)| index)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Resolves the columns of the schema `requested` to the columns of this schema, returning,
+    /// for every column of `requested`, the index of the column in [`SchemaDescriptor::columns`]
+    /// or `None` if this schema does not have it.
+    ///
+    /// Fields are matched by their id, independently of their names, as e.g. Iceberg does;
+    /// fields of `requested` without id (e.g. the repeated group of a list) are matched by name.
+    /// # Error
+    /// Errors iff a field of `requested` is a group in this schema and vice-versa.
+    pub fn project_by_id(&self, requested: &SchemaDescriptor) -> Result<Vec<Option<usize>>> {
+        let mut paths = vec![];
+        project_by_id(requested.fields(), &self.fields, &mut vec![], &mut paths)?;
+        Ok(paths
+            .into_iter()
+            .map(|path| {
+                path.and_then(|path| {
+                    self.leaves
+                        .iter()
+                        .position(|column| column.path_in_schema == path)
+                })
+            })
+            .collect())
+    }
+
+    /// Serializes this schema into a message type, the inverse of
+    /// [`SchemaDescriptor::try_from_message`].
+    /// # Error
+    /// Errors iff the name of a field can't be written, e.g. because it contains whitespace.
+    pub fn to_message(&self) -> Result<String> {
+        write_message(&self.name, &self.fields)
+    }
+}
+
+fn find_path_by_id(fields: &[ParquetType], id: i32, path: &mut Vec<String>) -> bool {
+    for field in fields {
+        path.push(field.name().to_string());
+        if field.get_field_info().id == Some(id) {
+            return true;
+        }
+        if let ParquetType::GroupType { fields, .. } = field {
+            if find_path_by_id(fields, id, path) {
+                return true;
+            }
+        }
+        path.pop();
+    }
+    false
+}
+
+fn num_leaves(field: &ParquetType) -> usize {
+    match field {
+        ParquetType::PrimitiveType(_) => 1,
+        ParquetType::GroupType { fields, .. } => fields.iter().map(num_leaves).sum(),
+    }
+}
+
+/// Pushes to `paths` the path of the field of `fields` of every leaf of `requested`
+fn project_by_id(
+    requested: &[ParquetType],
+    fields: &[ParquetType],
+    path: &mut Vec<String>,
+    paths: &mut Vec<Option<Vec<String>>>,
+) -> Result<()> {
+    for requested in requested {
+        let field = fields
+            .iter()
+            .find(|field| match requested.get_field_info().id {
+                Some(id) => field.get_field_info().id == Some(id),
+                None => field.name() == requested.name(),
+            });
+        let field = if let Some(field) = field {
+            field
+        } else {
+            paths.resize(paths.len() + num_leaves(requested), None);
+            continue;
+        };
+
+        path.push(field.name().to_string());
+        match (requested, field) {
+            (ParquetType::PrimitiveType(_), ParquetType::PrimitiveType(_)) => {
+                paths.push(Some(path.clone()))
+            }
+            (
+                ParquetType::GroupType {
+                    fields: requested, ..
+                },
+                ParquetType::GroupType { fields, .. },
+            ) => project_by_id(requested, fields, path, paths)?,
+            _ => {
+                return Err(Error::InvalidParameter(format!(
+                    "The field \"{}\" is a group in one schema and a primitive in the other",
+                    path.join(".")
+                )))
+            }
+        }
+        path.pop();
+    }
+    Ok(())
 }
 
 fn build_tree<'a>(
@@ -140,3 +264,77 @@ fn build_tree<'a>(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema() -> SchemaDescriptor {
+        SchemaDescriptor::try_from_message(
+            "message schema {
+                required int32 a = 1;
+                optional group b (LIST) = 2 {
+                    repeated group list {
+                        optional group element = 3 {
+                            required int64 c = 4;
+                            optional binary d = 5;
+                        }
+                    }
+                }
+                required double e = 6;
+            }",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn by_id() {
+        let schema = schema();
+        assert_eq!(schema.path_by_id(1), Some(vec!["a".to_string()]));
+        assert_eq!(
+            schema.path_by_id(5),
+            Some(vec![
+                "b".to_string(),
+                "list".to_string(),
+                "element".to_string(),
+                "d".to_string()
+            ])
+        );
+        assert_eq!(schema.path_by_id(7), None);
+
+        assert_eq!(schema.columns_by_id(1), vec![0]);
+        assert_eq!(schema.columns_by_id(2), vec![1, 2]);
+        assert_eq!(schema.columns_by_id(3), vec![1, 2]);
+        assert_eq!(schema.columns_by_id(5), vec![2]);
+        assert_eq!(schema.columns_by_id(6), vec![3]);
+        assert_eq!(schema.columns_by_id(7), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn project() -> Result<()> {
+        // renamed and reordered fields, a removed field (`c`) and an added field (`f`)
+        let requested = SchemaDescriptor::try_from_message(
+            "message schema {
+                required double x = 6;
+                optional group y (LIST) = 2 {
+                    repeated group list {
+                        optional group element = 3 {
+                            optional binary z = 5;
+                            optional float f = 7;
+                        }
+                    }
+                }
+                required int32 a = 1;
+            }",
+        )?;
+        assert_eq!(
+            schema().project_by_id(&requested)?,
+            vec![Some(3), Some(2), None, Some(0)]
+        );
+
+        let requested =
+            SchemaDescriptor::try_from_message("message schema { required int32 b = 2; }")?;
+        assert!(schema().project_by_id(&requested).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,140 @@
+use std::io::{Read, Seek};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use crate::error::Result;
+
+use super::lru::Lru;
+use super::{read_metadata_with_options, FileFooter, MetadataOptions};
+
+#[derive(Debug)]
+struct Entry {
+    size: u64,
+    modified: Option<SystemTime>,
+    footer: Arc<FileFooter>,
+}
+
+/// A cache of the footers of files, so that opening the same file again does not read nor
+/// deserialize its metadata.
+///
+/// Entries are keyed by the path of the file, its size and its modification time: a file
+/// whose size or modification time changed is read again. Up to `capacity` files are cached,
+/// evicting the least recently used one. The cache can be shared between threads.
+///
+/// Files are read with the [`MetadataOptions`] of the cache; concurrent misses of the same
+/// file may read it more than once.
+#[derive(Debug)]
+pub struct MetadataCache {
+    capacity: usize,
+    options: MetadataOptions,
+    entries: Mutex<Lru<PathBuf, Entry>>,
+}
+
+impl MetadataCache {
+    /// Returns a new [`MetadataCache`] of up to `capacity` files, read with `options`.
+    pub fn new(capacity: usize, options: MetadataOptions) -> Self {
+        Self {
+            capacity,
+            options,
+            entries: Default::default(),
+        }
+    }
+
+    /// Returns the options the files are read with
+    pub fn options(&self) -> &MetadataOptions {
+        &self.options
+    }
+
+    /// Returns the cached footer of the file at `path`, if it was cached with the same `size`
+    /// and `modified` time.
+    pub fn get(
+        &self,
+        path: &Path,
+        size: u64,
+        modified: Option<SystemTime>,
+    ) -> Option<Arc<FileFooter>> {
+        let mut entries = self.entries.lock().unwrap();
+        let entry = entries.get(path)?;
+        if entry.size != size || entry.modified != modified {
+            return None;
+        }
+        Some(entry.footer.clone())
+    }
+
+    /// Caches `footer` as the footer of the file at `path` of `size` bytes and `modified` time,
+    /// replacing any previous footer of the file.
+    pub fn insert(
+        &self,
+        path: PathBuf,
+        size: u64,
+        modified: Option<SystemTime>,
+        footer: Arc<FileFooter>,
+    ) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap();
+        if !entries.contains_key(&path) && entries.len() >= self.capacity {
+            entries.pop_oldest();
+        }
+        entries.insert(
+            path,
+            Entry {
+                size,
+                modified,
+                footer,
+            },
+        );
+    }
+
+    /// Returns the footer of the file at `path`, reading it from `reader` unless it is cached
+    /// with the same `size` and `modified` time. Use this for files that are not local, e.g.
+    /// with a [`super::RangeCursor`] of an object in object storage.
+    /// # Error
+    /// Errors if the footer is not cached and can't be read.
+    pub fn get_or_read<R: Read + Seek>(
+        &self,
+        reader: &mut R,
+        path: &Path,
+        size: u64,
+        modified: Option<SystemTime>,
+    ) -> Result<Arc<FileFooter>> {
+        if let Some(footer) = self.get(path, size, modified) {
+            return Ok(footer);
+        }
+        let footer = Arc::new(read_metadata_with_options(reader, &self.options)?);
+        self.insert(path.to_path_buf(), size, modified, footer.clone());
+        Ok(footer)
+    }
+
+    /// Returns the footer of the local file at `path`, reading it unless it is cached with the
+    /// current size and modification time of the file.
+    /// # Error
+    /// Errors if the file can't be opened or its footer can't be read.
+    pub fn read(&self, path: &Path) -> Result<Arc<FileFooter>> {
+        let mut file = std::fs::File::open(path)?;
+        let metadata = file.metadata()?;
+        self.get_or_read(&mut file, path, metadata.len(), metadata.modified().ok())
+    }
+
+    /// Removes the footer of the file at `path` from the cache
+    pub fn remove(&self, path: &Path) {
+        self.entries.lock().unwrap().remove(path);
+    }
+
+    /// Removes all footers from the cache
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+
+    /// Returns the number of cached footers
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    /// Returns whether the cache is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
@@ -0,0 +1,368 @@
+use std::io::{Read, Seek, SeekFrom};
+use std::ops::Range;
+use std::sync::Arc;
+
+#[cfg(feature = "async")]
+use futures::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt};
+
+use crate::error::{Error, Result};
+use crate::indexes::FilteredPage;
+use crate::metadata::{ColumnChunkMetaData, FileMetaData};
+use crate::page::CompressedPage;
+
+use super::page::{IndexedPageReader, PageFilter, PageReader};
+use super::RangeReader;
+
+/// Options to coalesce the byte ranges of a [`ReadPlan`] into fewer, larger, reads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CoalesceOptions {
+    /// Ranges separated by at most this number of bytes are read together, reading the gap
+    /// between them.
+    pub max_gap: u64,
+    /// Ranges are not merged into a read larger than this number of bytes. A single range
+    /// larger than this is still read at once.
+    pub max_size: u64,
+}
+
+impl Default for CoalesceOptions {
+    fn default() -> Self {
+        Self {
+            max_gap: 1024 * 1024,
+            max_size: 64 * 1024 * 1024,
+        }
+    }
+}
+
+/// Merges `ranges` according to `options`, returning sorted, non-overlapping, ranges that
+/// cover all of them.
+pub fn coalesce_ranges(ranges: &[Range<u64>], options: CoalesceOptions) -> Vec<Range<u64>> {
+    let mut ranges = ranges
+        .iter()
+        .filter(|range| !range.is_empty())
+        .cloned()
+        .collect::<Vec<_>>();
+    ranges.sort_unstable_by_key(|range| range.start);
+
+    let mut merged: Vec<Range<u64>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last)
+                if range.start <= last.end
+                    || (range.start - last.end <= options.max_gap
+                        && range.end - last.start <= options.max_size) =>
+            {
+                last.end = last.end.max(range.end)
+            }
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+/// A column chunk to read: either all its pages or only the pages of `pages` (see
+/// [`crate::indexes::select_pages`]).
+#[derive(Debug, Clone)]
+pub struct ChunkRead {
+    /// The column chunk
+    pub column: ColumnChunkMetaData,
+    /// The pages to read, or `None` to read all pages
+    pub pages: Option<Vec<FilteredPage>>,
+}
+
+impl ChunkRead {
+    /// The byte ranges of the file that must be read to read this chunk
+    fn ranges(&self) -> Vec<Range<u64>> {
+        let (start, length) = self.column.byte_range();
+        let pages = if let Some(pages) = &self.pages {
+            pages
+        } else {
+            return std::iter::once(start..start + length).collect();
+        };
+
+        // the dictionary page, if any, is before the first data page (see `IndexedPageReader`)
+        let dictionary = pages.first().map(|page| start..page.start);
+        dictionary
+            .into_iter()
+            .chain(
+                pages
+                    .iter()
+                    .filter(|page| !page.selected_rows.is_empty())
+                    .map(|page| page.start..page.start + page.length as u64),
+            )
+            .collect()
+    }
+}
+
+/// A plan to read a set of column chunks with few, large, reads, e.g. from object storage.
+///
+/// The byte ranges of its chunks are coalesced according to [`CoalesceOptions`]. Read them
+/// with [`ReadPlan::read`], [`ReadPlan::read_async`] or [`ReadPlan::read_ranges`], or fetch
+/// [`ReadPlan::ranges`] with a custom client and create [`FetchedRanges`] from them. The pages of each chunk are
+/// then read from memory with [`FetchedRanges::pages`].
+#[derive(Debug, Clone)]
+pub struct ReadPlan {
+    chunks: Vec<ChunkRead>,
+    ranges: Vec<Range<u64>>,
+}
+
+impl ReadPlan {
+    /// Returns a new [`ReadPlan`] of `chunks`
+    pub fn new(chunks: Vec<ChunkRead>, options: CoalesceOptions) -> Self {
+        let ranges = chunks
+            .iter()
+            .flat_map(|chunk| chunk.ranges())
+            .collect::<Vec<_>>();
+        let ranges = coalesce_ranges(&ranges, options);
+        Self { chunks, ranges }
+    }
+
+    /// Returns a new [`ReadPlan`] of all pages of the columns `columns` of the row groups
+    /// `row_groups` of `metadata`, ordered by row group and then by column.
+    pub fn from_metadata(
+        metadata: &FileMetaData,
+        row_groups: &[usize],
+        columns: &[usize],
+        options: CoalesceOptions,
+    ) -> Self {
+        let chunks = row_groups
+            .iter()
+            .flat_map(|row_group| {
+                columns.iter().map(move |column| ChunkRead {
+                    column: metadata.row_groups[*row_group].columns()[*column].clone(),
+                    pages: None,
+                })
+            })
+            .collect();
+        Self::new(chunks, options)
+    }
+
+    /// The chunks of this plan
+    pub fn chunks(&self) -> &[ChunkRead] {
+        &self.chunks
+    }
+
+    /// The (coalesced) byte ranges to read
+    pub fn ranges(&self) -> &[Range<u64>] {
+        &self.ranges
+    }
+
+    /// Reads the ranges of this plan from `reader`, one read per range.
+    pub fn read<R: Read + Seek>(&self, reader: &mut R) -> Result<FetchedRanges> {
+        let mut fetched = Vec::with_capacity(self.ranges.len());
+        for range in &self.ranges {
+            reader.seek(SeekFrom::Start(range.start))?;
+            let length = range.end - range.start;
+
+            let mut data = vec![];
+            data.try_reserve(length as usize)?;
+            reader.by_ref().take(length).read_to_end(&mut data)?;
+            fetched.push((range.start, data));
+        }
+        FetchedRanges::try_new(fetched)
+    }
+
+    /// Reads the ranges of this plan from `reader`, one request per range.
+    pub fn read_ranges<R: RangeReader + ?Sized>(&self, reader: &R) -> Result<FetchedRanges> {
+        let fetched = self
+            .ranges
+            .iter()
+            .map(|range| {
+                let data = reader.get_range(range.start, (range.end - range.start).try_into()?)?;
+                Ok((range.start, data))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        FetchedRanges::try_new(fetched)
+    }
+
+    /// Asynchronously reads the ranges of this plan from `reader`, one read per range.
+    #[cfg(feature = "async")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+    pub async fn read_async<R: AsyncRead + AsyncSeek + Send + Unpin>(
+        &self,
+        reader: &mut R,
+    ) -> Result<FetchedRanges> {
+        let mut fetched = Vec::with_capacity(self.ranges.len());
+        for range in &self.ranges {
+            reader.seek(SeekFrom::Start(range.start)).await?;
+            let length = range.end - range.start;
+
+            let mut data = vec![];
+            data.try_reserve(length as usize)?;
+            (&mut *reader).take(length).read_to_end(&mut data).await?;
+            fetched.push((range.start, data));
+        }
+        FetchedRanges::try_new(fetched)
+    }
+}
+
+/// Byte ranges of a file in memory, e.g. read by a [`ReadPlan`].
+///
+/// It implements [`Read`] and [`Seek`] with the offsets of the file, so that column chunks
+/// can be read from it as from the file. Reading bytes that were not fetched errors.
+/// Cloning it is cheap and the clones read independently.
+#[derive(Debug, Clone)]
+pub struct FetchedRanges {
+    ranges: Arc<[(u64, Vec<u8>)]>,
+    position: u64,
+}
+
+impl FetchedRanges {
+    /// Returns a new [`FetchedRanges`] from `(start, data)` pairs.
+    /// # Error
+    /// Errors if the ranges overlap.
+    pub fn try_new(mut ranges: Vec<(u64, Vec<u8>)>) -> Result<Self> {
+        ranges.sort_unstable_by_key(|(start, _)| *start);
+        if ranges
+            .windows(2)
+            .any(|x| x[0].0 + x[0].1.len() as u64 > x[1].0)
+        {
+            return Err(Error::InvalidParameter(
+                "Fetched byte ranges must not overlap".to_string(),
+            ));
+        }
+        Ok(Self {
+            ranges: ranges.into(),
+            position: 0,
+        })
+    }
+
+    /// Returns an iterator over the pages of `chunk`, read from these ranges.
+    /// `max_header_size` is the maximum number of bytes thrift is allowed to allocate
+    /// to read a page header.
+    pub fn pages(
+        &self,
+        chunk: &ChunkRead,
+        page_filter: Option<PageFilter>,
+        scratch: Vec<u8>,
+        max_header_size: usize,
+    ) -> Result<ChunkPageReader> {
+        Ok(match &chunk.pages {
+            Some(pages) => ChunkPageReader::Indexed(IndexedPageReader::new(
+                self.clone(),
+                &chunk.column,
+                pages.clone(),
+                scratch,
+                vec![],
+            )),
+            None => ChunkPageReader::All(super::get_page_iterator(
+                &chunk.column,
+                self.clone(),
+                page_filter,
+                scratch,
+                max_header_size,
+            )?),
+        })
+    }
+}
+
+impl Read for FetchedRanges {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let index = self
+            .ranges
+            .partition_point(|(start, _)| *start <= self.position);
+        let data = index
+            .checked_sub(1)
+            .map(|index| &self.ranges[index])
+            .and_then(|(start, data)| data.get((self.position - start) as usize..))
+            .filter(|data| !data.is_empty())
+            .ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    format!("The byte at offset {} was not fetched", self.position),
+                )
+            })?;
+
+        let length = data.len().min(buf.len());
+        buf[..length].copy_from_slice(&data[..length]);
+        self.position += length as u64;
+        Ok(length)
+    }
+}
+
+impl Seek for FetchedRanges {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        let position = match pos {
+            SeekFrom::Start(position) => Some(position),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+            SeekFrom::End(_) => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::Unsupported,
+                    "Fetched byte ranges can't be seeked from their end",
+                ))
+            }
+        };
+        self.position = position.ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "Invalid seek to a negative or overflowing position",
+            )
+        })?;
+        Ok(self.position)
+    }
+}
+
+/// An [`Iterator`] of the [`CompressedPage`]s of a [`ChunkRead`], returned by
+/// [`FetchedRanges::pages`].
+pub enum ChunkPageReader {
+    /// Reads all pages of the chunk
+    All(PageReader<FetchedRanges>),
+    /// Reads the selected pages of the chunk
+    Indexed(IndexedPageReader<FetchedRanges>),
+}
+
+impl Iterator for ChunkPageReader {
+    type Item = Result<CompressedPage>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            ChunkPageReader::All(reader) => reader.next(),
+            ChunkPageReader::Indexed(reader) => reader.next(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coalesce() {
+        let options = CoalesceOptions {
+            max_gap: 10,
+            max_size: 100,
+        };
+        // unsorted, overlapping, empty and distant ranges
+        let ranges = [50..60, 0..10, 15..20, 5..12, 30..30, 200..300, 65..160];
+        assert_eq!(
+            coalesce_ranges(&ranges, options),
+            vec![0..20, 50..60, 65..160, 200..300]
+        );
+
+        let options = CoalesceOptions {
+            max_gap: 100,
+            max_size: 1000,
+        };
+        assert_eq!(coalesce_ranges(&ranges, options), vec![0..300]);
+        assert!(coalesce_ranges(&[], options).is_empty());
+    }
+
+    #[test]
+    fn fetched() -> Result<()> {
+        let mut ranges = FetchedRanges::try_new(vec![(10, vec![3, 4]), (0, vec![0, 1, 2])])?;
+        let mut buf = [0; 3];
+        ranges.read_exact(&mut buf)?;
+        assert_eq!(buf, [0, 1, 2]);
+        assert!(ranges.read_exact(&mut buf[..1]).is_err());
+
+        ranges.seek(SeekFrom::Start(10))?;
+        ranges.read_exact(&mut buf[..2])?;
+        assert_eq!(buf[..2], [3, 4]);
+        assert!(ranges.seek(SeekFrom::End(0)).is_err());
+
+        assert!(FetchedRanges::try_new(vec![(0, vec![0; 3]), (2, vec![0])]).is_err());
+        Ok(())
+    }
+}
@@ -1,9 +1,15 @@
+use std::borrow::Cow;
 use std::convert::TryInto;
-use std::io::{Cursor, Read, Seek, SeekFrom};
+use std::io::{Read, Seek, SeekFrom};
+
+#[cfg(feature = "async")]
+use futures::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt};
 
 use parquet_format_safe::ColumnChunk;
 use parquet_format_safe::{thrift::protocol::TCompactInputProtocol, OffsetIndex, PageLocation};
 
+#[cfg(feature = "encryption")]
+use crate::encryption::ModuleType;
 use crate::error::Error;
 use crate::indexes::Index;
 use crate::metadata::ColumnChunkMetaData;
@@ -55,24 +61,50 @@ fn prepare_offset_index_read(chunks: &[ColumnChunkMetaData]) -> Result<(u64, Vec
     prepare_read(chunks, |x| x.offset_index_offset, |x| x.offset_index_length)
 }
 
+/// Splits `data` into the indexes of each column chunk, decrypting those of encrypted chunks.
+/// Errors if an encrypted chunk can't be decrypted.
+fn split_indexes<'a>(
+    chunks: &'a [ColumnChunkMetaData],
+    data: &'a [u8],
+    lengths: Vec<usize>,
+    is_offset_index: bool,
+) -> impl Iterator<Item = Result<(&'a ColumnChunkMetaData, Cow<'a, [u8]>), Error>> + 'a {
+    let mut start = 0;
+    chunks.iter().zip(lengths).map(move |(chunk, length)| {
+        chunk.check_decryption()?;
+        let data = data.get(start..start + length).ok_or_else(|| {
+            Error::OutOfSpec("The page indexes are larger than the bytes read".to_string())
+        })?;
+        start += length;
+
+        #[cfg(feature = "encryption")]
+        if let Some(decryptor) = chunk.decryptor() {
+            let module_type = if is_offset_index {
+                ModuleType::OffsetIndex
+            } else {
+                ModuleType::ColumnIndex
+            };
+            return Ok((
+                chunk,
+                Cow::Owned(decryptor.decrypt(module_type, None, data)?),
+            ));
+        }
+        #[cfg(not(feature = "encryption"))]
+        let _ = is_offset_index;
+        Ok((chunk, Cow::Borrowed(data)))
+    })
+}
+
 fn deserialize_column_indexes(
     chunks: &[ColumnChunkMetaData],
     data: &[u8],
     lengths: Vec<usize>,
 ) -> Result<Vec<Box<dyn Index>>, Error> {
-    let mut start = 0;
-    let data = lengths.into_iter().map(|length| {
-        let r = &data[start..start + length];
-        start += length;
-        r
-    });
-
-    chunks
-        .iter()
-        .zip(data)
-        .map(|(chunk, data)| {
+    split_indexes(chunks, data, lengths, false)
+        .map(|maybe_index| {
+            let (chunk, data) = maybe_index?;
             let primitive_type = chunk.descriptor().descriptor.primitive_type.clone();
-            deserialize(data, primitive_type)
+            deserialize(&data, primitive_type)
         })
         .collect()
 }
@@ -97,15 +129,15 @@ pub fn read_columns_indexes<R: Read + Seek>(
 }
 
 fn deserialize_page_locations(
+    chunks: &[ColumnChunkMetaData],
     data: &[u8],
-    column_number: usize,
+    lengths: Vec<usize>,
 ) -> Result<Vec<Vec<PageLocation>>, Error> {
-    let len = data.len() * 2 + 1024;
-    let mut reader = Cursor::new(data);
-
-    (0..column_number)
-        .map(|_| {
-            let mut prot = TCompactInputProtocol::new(&mut reader, len);
+    split_indexes(chunks, data, lengths, true)
+        .map(|maybe_index| {
+            let (_, data) = maybe_index?;
+            let len = data.len() * 2 + 1024;
+            let mut prot = TCompactInputProtocol::new(data.as_ref(), len);
             let offset = OffsetIndex::read_from_in_protocol(&mut prot)?;
             Ok(offset.page_locations)
         })
@@ -128,5 +160,53 @@ pub fn read_pages_locations<R: Read + Seek>(
     data.try_reserve(length)?;
     reader.by_ref().take(length as u64).read_to_end(&mut data)?;
 
-    deserialize_page_locations(&data, chunks.len())
+    deserialize_page_locations(chunks, &data, lengths)
+}
+
+/// Asynchronously reads the `length` bytes starting at `offset` with a single request.
+#[cfg(feature = "async")]
+async fn read_range_async<R: AsyncRead + AsyncSeek + Send + Unpin>(
+    reader: &mut R,
+    offset: u64,
+    length: usize,
+) -> Result<Vec<u8>, Error> {
+    reader.seek(SeekFrom::Start(offset)).await?;
+
+    let mut data = vec![];
+    data.try_reserve(length)?;
+    reader.take(length as u64).read_to_end(&mut data).await?;
+    Ok(data)
+}
+
+/// Asynchronously reads the column indexes of all [`ColumnChunkMetaData`] and deserializes
+/// them into [`Index`].
+/// Returns an empty vector if indexes are not available
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub async fn read_columns_indexes_async<R: AsyncRead + AsyncSeek + Send + Unpin>(
+    reader: &mut R,
+    chunks: &[ColumnChunkMetaData],
+) -> Result<Vec<Box<dyn Index>>, Error> {
+    let (offset, lengths) = prepare_column_index_read(chunks)?;
+
+    let length = lengths.iter().sum::<usize>();
+    let data = read_range_async(reader, offset, length).await?;
+
+    deserialize_column_indexes(chunks, &data, lengths)
+}
+
+/// Asynchronously reads [`PageLocation`]s from the [`ColumnChunkMetaData`]s.
+/// Returns an empty vector if indexes are not available
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub async fn read_pages_locations_async<R: AsyncRead + AsyncSeek + Send + Unpin>(
+    reader: &mut R,
+    chunks: &[ColumnChunkMetaData],
+) -> Result<Vec<Vec<PageLocation>>, Error> {
+    let (offset, lengths) = prepare_offset_index_read(chunks)?;
+
+    let length = lengths.iter().sum::<usize>();
+    let data = read_range_async(reader, offset, length).await?;
+
+    deserialize_page_locations(chunks, &data, lengths)
 }
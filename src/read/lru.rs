@@ -0,0 +1,127 @@
+use std::borrow::Borrow;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+
+/// A map that tracks the order in which its entries were last used, so that the least recently
+/// used one is found in logarithmic time.
+#[derive(Debug)]
+pub(super) struct Lru<K, V> {
+    // key -> (value, time it was last used)
+    entries: HashMap<K, (V, u64)>,
+    // time an entry was last used -> its key
+    order: BTreeMap<u64, K>,
+    // incremented on every use
+    clock: u64,
+}
+
+impl<K, V> Default for Lru<K, V> {
+    fn default() -> Self {
+        Self {
+            entries: HashMap::new(),
+            order: BTreeMap::new(),
+            clock: 0,
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> Lru<K, V> {
+    /// Returns the value of `key`, marking it as the most recently used entry.
+    pub fn get<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.clock += 1;
+        let (value, last_used) = self.entries.get_mut(key)?;
+        let key = self.order.remove(last_used).unwrap();
+        *last_used = self.clock;
+        self.order.insert(self.clock, key);
+        Some(value)
+    }
+
+    /// Returns whether there is a value for `key`, without marking it as used.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.entries.contains_key(key)
+    }
+
+    /// Inserts `value` as the most recently used entry, returning the previous value of `key`.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.clock += 1;
+        self.order.insert(self.clock, key.clone());
+        let previous = self.entries.insert(key, (value, self.clock));
+        previous.map(|(value, last_used)| {
+            self.order.remove(&last_used);
+            value
+        })
+    }
+
+    /// Removes the entry of `key`, returning its value.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let (value, last_used) = self.entries.remove(key)?;
+        self.order.remove(&last_used);
+        Some(value)
+    }
+
+    /// Removes all entries
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+    }
+
+    /// Returns the number of entries
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Removes the least recently used entry
+    pub fn pop_oldest(&mut self) -> Option<(K, V)> {
+        let (_, key) = self.order.pop_first()?;
+        let (value, _) = self.entries.remove(&key).unwrap();
+        Some((key, value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn order() {
+        let mut lru = Lru::default();
+        lru.insert(1, "a");
+        lru.insert(2, "b");
+        lru.insert(3, "c");
+        assert_eq!(lru.get(&1), Some(&mut "a"));
+        assert_eq!(lru.insert(2, "d"), Some("b"));
+
+        assert_eq!(lru.pop_oldest(), Some((3, "c")));
+        assert_eq!(lru.pop_oldest(), Some((1, "a")));
+        assert!(!lru.contains_key(&1));
+        assert_eq!(lru.pop_oldest(), Some((2, "d")));
+        assert_eq!(lru.pop_oldest(), None);
+    }
+
+    #[test]
+    fn remove() {
+        let mut lru = Lru::default();
+        lru.insert(1, "a");
+        lru.insert(2, "b");
+        assert_eq!(lru.remove(&1), Some("a"));
+        assert_eq!(lru.remove(&1), None);
+        assert_eq!(lru.len(), 1);
+        assert_eq!(lru.pop_oldest(), Some((2, "b")));
+
+        lru.insert(3, "c");
+        lru.clear();
+        assert_eq!(lru.len(), 0);
+        assert_eq!(lru.pop_oldest(), None);
+    }
+}
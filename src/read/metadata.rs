@@ -2,24 +2,30 @@ use std::convert::TryInto;
 use std::{
     cmp::min,
     io::{Read, Seek, SeekFrom},
+    ops::Range,
 };
 
 use parquet_format_safe::thrift::protocol::TCompactInputProtocol;
 use parquet_format_safe::FileMetaData as TFileMetaData;
 
 use super::super::{
-    metadata::FileMetaData, DEFAULT_FOOTER_READ_SIZE, FOOTER_SIZE, HEADER_SIZE, PARQUET_MAGIC,
+    metadata::FileMetaData, DEFAULT_FOOTER_READ_SIZE, FOOTER_SIZE, HEADER_SIZE,
+    PARQUET_ENCRYPTED_MAGIC, PARQUET_MAGIC,
 };
 
+#[cfg(feature = "encryption")]
+use crate::encryption::{decrypt_metadata, FileDecryptionProperties};
 use crate::error::{Error, Result};
 
+use super::FetchedRanges;
+
 pub(super) fn metadata_len(buffer: &[u8], len: usize) -> i32 {
     i32::from_le_bytes(buffer[len - 8..len - 4].try_into().unwrap())
 }
 
 // see (unstable) Seek::stream_len
 fn stream_len(seek: &mut impl Seek) -> std::result::Result<u64, std::io::Error> {
-    let old_pos = seek.seek(SeekFrom::Current(0))?;
+    let old_pos = seek.stream_position()?;
     let len = seek.seek(SeekFrom::End(0))?;
 
     // Avoid seeking a third time when we were already at the end of the
@@ -31,8 +37,30 @@ fn stream_len(seek: &mut impl Seek) -> std::result::Result<u64, std::io::Error>
     Ok(len)
 }
 
-/// Reads a [`FileMetaData`] from the reader, located at the end of the file.
-pub fn read_metadata<R: Read + Seek>(reader: &mut R) -> Result<FileMetaData> {
+/// The last bytes of a file, containing at least its whole footer
+struct Tail {
+    file_size: u64,
+    data: Vec<u8>,
+    metadata_len: usize,
+    encrypted: bool,
+}
+
+impl Tail {
+    /// The offset in the file of the first byte of `data`
+    fn start(&self) -> u64 {
+        self.file_size - self.data.len() as u64
+    }
+
+    /// The bytes of the (possibly encrypted) metadata
+    fn metadata(&self) -> &[u8] {
+        let end = self.data.len() - FOOTER_SIZE as usize;
+        &self.data[end - self.metadata_len..end]
+    }
+}
+
+/// Reads the last `read_size` bytes of the file, and again more bytes if they do not contain
+/// the whole footer.
+fn read_tail<R: Read + Seek>(reader: &mut R, read_size: u64) -> Result<Tail> {
     // check file is large enough to hold footer
     let file_size = stream_len(reader)?;
     if file_size < HEADER_SIZE + FOOTER_SIZE {
@@ -41,20 +69,19 @@ pub fn read_metadata<R: Read + Seek>(reader: &mut R) -> Result<FileMetaData> {
         ));
     }
 
-    // read and cache up to DEFAULT_FOOTER_READ_SIZE bytes from the end and process the footer
-    let default_end_len = min(DEFAULT_FOOTER_READ_SIZE, file_size) as usize;
+    // read and cache up to `read_size` bytes from the end and process the footer
+    let default_end_len = min(read_size.max(FOOTER_SIZE), file_size) as usize;
     reader.seek(SeekFrom::End(-(default_end_len as i64)))?;
 
-    let mut buffer = Vec::with_capacity(default_end_len);
+    let mut buffer = vec![];
+    buffer.try_reserve(default_end_len)?;
     reader
         .by_ref()
         .take(default_end_len as u64)
         .read_to_end(&mut buffer)?;
 
     // check this is indeed a parquet file
-    if buffer[default_end_len - 4..] != PARQUET_MAGIC {
-        return Err(Error::OutOfSpec("The file must end with PAR1".to_string()));
-    }
+    let encrypted = footer_magic(&buffer[default_end_len - 4..])?;
 
     let metadata_len = metadata_len(&buffer, default_end_len);
 
@@ -67,28 +94,248 @@ pub fn read_metadata<R: Read + Seek>(reader: &mut R) -> Result<FileMetaData> {
         ));
     }
 
-    let reader: &[u8] = if (footer_len as usize) < buffer.len() {
-        // the whole metadata is in the bytes we already read
-        let remaining = buffer.len() - footer_len as usize;
-        &buffer[remaining..]
-    } else {
+    if footer_len > buffer.len() as u64 {
         // the end of file read by default is not long enough, read again including the metadata.
         reader.seek(SeekFrom::End(-(footer_len as i64)))?;
 
         buffer.clear();
         buffer.try_reserve(footer_len as usize)?;
-        reader.take(footer_len as u64).read_to_end(&mut buffer)?;
+        reader.take(footer_len).read_to_end(&mut buffer)?;
+    }
 
-        &buffer
-    };
+    Ok(Tail {
+        file_size,
+        data: buffer,
+        metadata_len: metadata_len as usize,
+        encrypted,
+    })
+}
+
+/// Reads the footer of the file, returning the bytes of its metadata and whether the
+/// footer is encrypted (i.e. the file ends with `PARE`).
+#[cfg(feature = "encryption")]
+fn read_footer<R: Read + Seek>(reader: &mut R) -> Result<(Vec<u8>, bool)> {
+    let tail = read_tail(reader, DEFAULT_FOOTER_READ_SIZE)?;
+    Ok((tail.metadata().to_vec(), tail.encrypted))
+}
+
+/// Returns the range of the file spanning the column and offset indexes of all its column
+/// chunks, or `None` if the file has none.
+fn page_index_range(metadata: &FileMetaData) -> Result<Option<Range<u64>>> {
+    let mut range: Option<Range<u64>> = None;
+    for column in metadata.row_groups.iter().flat_map(|x| x.columns()) {
+        let chunk = column.column_chunk();
+        let indexes = [
+            (chunk.column_index_offset, chunk.column_index_length),
+            (chunk.offset_index_offset, chunk.offset_index_length),
+        ];
+        for (offset, length) in indexes {
+            if let (Some(offset), Some(length)) = (offset, length) {
+                let start: u64 = offset.try_into()?;
+                let length: u64 = length.try_into()?;
+                let end = start.checked_add(length).ok_or(Error::OutOfSpec(
+                    "The page index of a column chunk is too large".to_string(),
+                ))?;
+                range = Some(match range {
+                    Some(range) => range.start.min(start)..range.end.max(end),
+                    None => start..end,
+                });
+            }
+        }
+    }
+    Ok(range)
+}
+
+/// Returns the page index of the file in `range`, reading from `reader` the bytes that are not
+/// in `tail`.
+fn read_page_index<R: Read + Seek>(
+    reader: &mut R,
+    tail: &Tail,
+    range: Range<u64>,
+) -> Result<FetchedRanges> {
+    if range.end > tail.file_size {
+        return Err(Error::OutOfSpec(
+            "The page index must be within the file".to_string(),
+        ));
+    }
+    let length = (range.end - range.start) as usize;
+    let mut data = vec![];
+    data.try_reserve(length)?;
+
+    let tail_start = tail.start();
+    if range.start < tail_start {
+        let missing = range.end.min(tail_start) - range.start;
+        reader.seek(SeekFrom::Start(range.start))?;
+        reader.by_ref().take(missing).read_to_end(&mut data)?;
+        if data.len() as u64 != missing {
+            return Err(Error::OutOfSpec(
+                "The page index must be within the file".to_string(),
+            ));
+        }
+    }
+    if range.end > tail_start {
+        let from = range.start.max(tail_start) - tail_start;
+        let to = range.end - tail_start;
+        data.extend_from_slice(&tail.data[from as usize..to as usize]);
+    }
+    FetchedRanges::try_new(vec![(range.start, data)])
+}
+
+/// Returns whether the file's `magic` is the one of files with an encrypted footer, `PARE`.
+pub(super) fn footer_magic(magic: &[u8]) -> Result<bool> {
+    if magic == PARQUET_MAGIC {
+        Ok(false)
+    } else if magic == PARQUET_ENCRYPTED_MAGIC {
+        Ok(true)
+    } else {
+        Err(Error::OutOfSpec("The file must end with PAR1".to_string()))
+    }
+}
+
+/// Returns the error of reading the metadata of a file with an encrypted footer without keys
+#[cfg(feature = "encryption")]
+pub(super) fn encrypted_footer_error() -> Error {
+    Error::InvalidParameter(
+        "The footer of the file is encrypted: read its metadata with `read_metadata_with_decryption`"
+            .to_string(),
+    )
+}
+
+/// Returns the error of reading the metadata of a file with an encrypted footer without keys
+#[cfg(not(feature = "encryption"))]
+pub(super) fn encrypted_footer_error() -> Error {
+    Error::FeatureNotActive(
+        crate::error::Feature::Encryption,
+        "read files with an encrypted footer".to_string(),
+    )
+}
+
+/// Returns the error of reading the pages, indexes or bloom filter of an encrypted column
+/// chunk without a decryptor
+#[cfg(feature = "encryption")]
+pub(crate) fn encrypted_column_error() -> Error {
+    Error::InvalidParameter(
+        "The column is encrypted: read the metadata of its file with `read_metadata_with_decryption`"
+            .to_string(),
+    )
+}
+
+/// Returns the error of reading the pages, indexes or bloom filter of an encrypted column
+/// chunk without a decryptor
+#[cfg(not(feature = "encryption"))]
+pub(crate) fn encrypted_column_error() -> Error {
+    Error::FeatureNotActive(
+        crate::error::Feature::Encryption,
+        "read encrypted columns".to_string(),
+    )
+}
+
+/// Options to read the metadata of a file with [`read_metadata_with_options`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MetadataOptions {
+    /// The number of bytes read from the end of the file in a first read, expecting them to
+    /// contain the whole footer. If they don't, the footer is read again with a second read.
+    /// Defaults to 64 KiB.
+    pub footer_read_size: u64,
+    /// Whether to also read the column and offset indexes of the file (its page index),
+    /// usually written right before the footer. No further read is made when they are within
+    /// the bytes read for the footer. Defaults to `false`.
+    pub prefetch_page_index: bool,
+}
+
+impl Default for MetadataOptions {
+    fn default() -> Self {
+        Self {
+            footer_read_size: DEFAULT_FOOTER_READ_SIZE,
+            prefetch_page_index: false,
+        }
+    }
+}
+
+/// The metadata of a file read with [`read_metadata_with_options`], and its prefetched page
+/// index.
+#[derive(Debug, Clone)]
+pub struct FileFooter {
+    /// The metadata of the file
+    pub metadata: FileMetaData,
+    /// The bytes of the column and offset indexes of the file, when prefetched and the file has
+    /// them. Use it as the reader of [`super::read_columns_indexes`] and
+    /// [`super::read_pages_locations`].
+    pub page_index: Option<FetchedRanges>,
+}
+
+/// Reads a [`FileMetaData`] from the reader, located at the end of the file.
+/// # Errors
+/// Errors if the footer of the file is encrypted, see [`read_metadata_with_decryption`]. The
+/// encrypted columns of a file with a plaintext footer can only be read with the metadata
+/// returned by [`read_metadata_with_decryption`].
+pub fn read_metadata<R: Read + Seek>(reader: &mut R) -> Result<FileMetaData> {
+    read_metadata_with_options(reader, &Default::default()).map(|footer| footer.metadata)
+}
+
+/// Reads a [`FileMetaData`] from the reader, located at the end of the file, and, if
+/// requested by `options`, the page index of the file.
+///
+/// With the default options, this reads the last 64 KiB of the file, and the footer again when
+/// it is larger than that. Set [`MetadataOptions::footer_read_size`] to the expected size of
+/// the footers to read them with a single request.
+/// # Errors
+/// Errors if the footer of the file is encrypted, see [`read_metadata_with_decryption`]. The
+/// encrypted columns of a file with a plaintext footer can only be read with the metadata
+/// returned by [`read_metadata_with_decryption`].
+pub fn read_metadata_with_options<R: Read + Seek>(
+    reader: &mut R,
+    options: &MetadataOptions,
+) -> Result<FileFooter> {
+    let tail = read_tail(reader, options.footer_read_size)?;
+    if tail.encrypted {
+        return Err(encrypted_footer_error());
+    }
+    let metadata = tail.metadata();
 
     // a highly nested but sparse struct could result in many allocations
-    let max_size = reader.len() * 2 + 1024;
+    let max_size = metadata.len() * 2 + 1024;
+
+    let metadata = deserialize_metadata(metadata, max_size)?;
+
+    let page_index = if options.prefetch_page_index {
+        page_index_range(&metadata)?
+            .map(|range| read_page_index(reader, &tail, range))
+            .transpose()?
+    } else {
+        None
+    };
+
+    Ok(FileFooter {
+        metadata,
+        page_index,
+    })
+}
 
-    deserialize_metadata(reader, max_size)
+/// Reads a [`FileMetaData`] of an encrypted file from the reader, decrypting its footer
+/// and the metadata of its encrypted columns with the keys of `properties`.
+///
+/// Pages, indexes and bloom filters of encrypted columns are decrypted when read with the
+/// returned [`FileMetaData`]. Files that are not encrypted are also supported.
+///
+/// Columns whose key is not available can't be read: reading their pages, indexes or bloom
+/// filters errors, and, when the footer is encrypted, their metadata is a placeholder without
+/// values.
+#[cfg(feature = "encryption")]
+#[cfg_attr(docsrs, doc(cfg(feature = "encryption")))]
+pub fn read_metadata_with_decryption<R: Read + Seek>(
+    reader: &mut R,
+    properties: &FileDecryptionProperties,
+) -> Result<FileMetaData> {
+    let (metadata, encrypted) = read_footer(reader)?;
+    decrypt_metadata(&metadata, encrypted, properties)
 }
 
 /// Parse loaded metadata bytes
+///
+/// The encrypted columns of a file with a plaintext footer can't be read with the returned
+/// [`FileMetaData`]: reading their pages, indexes or bloom filters errors, see
+/// [`read_metadata_with_decryption`].
 pub fn deserialize_metadata<R: Read>(reader: R, max_size: usize) -> Result<FileMetaData> {
     let mut prot = TCompactInputProtocol::new(reader, max_size);
     let metadata = TFileMetaData::read_from_in_protocol(&mut prot)?;
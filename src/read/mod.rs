@@ -1,8 +1,13 @@
+mod cache;
+mod coalesce;
 mod compression;
 mod indexes;
 pub mod levels;
+mod lru;
 mod metadata;
 mod page;
+pub(crate) mod predicate;
+mod range;
 #[cfg(feature = "async")]
 mod stream;
 
@@ -10,16 +15,33 @@ use std::io::{Read, Seek, SeekFrom};
 use std::sync::Arc;
 use std::vec::IntoIter;
 
+pub use cache::MetadataCache;
+pub use coalesce::{
+    coalesce_ranges, ChunkPageReader, ChunkRead, CoalesceOptions, FetchedRanges, ReadPlan,
+};
 pub use compression::{decompress, BasicDecompressor, Decompressor};
-pub use metadata::{deserialize_metadata, read_metadata};
+pub(crate) use metadata::encrypted_column_error;
+#[cfg(feature = "encryption")]
+#[cfg_attr(docsrs, doc(cfg(feature = "encryption")))]
+pub use metadata::read_metadata_with_decryption;
+pub use metadata::{
+    deserialize_metadata, read_metadata, read_metadata_with_options, FileFooter, MetadataOptions,
+};
 #[cfg(feature = "async")]
 #[cfg_attr(docsrs, doc(cfg(feature = "async")))]
-pub use page::{get_page_stream, get_page_stream_from_column_start};
+pub use page::{
+    get_page_stream, get_page_stream_from_column_start, get_page_stream_with_page_meta,
+};
 pub use page::{IndexedPageReader, PageFilter, PageIterator, PageMetaData, PageReader};
+pub use predicate::{prune_pages, prune_row_groups, read_filtered_pages, Predicate, Value};
+pub use range::{CachingRangeReader, RangeCursor, RangeReader};
 
 #[cfg(feature = "async")]
 #[cfg_attr(docsrs, doc(cfg(feature = "async")))]
 pub use stream::read_metadata as read_metadata_async;
+#[cfg(all(feature = "async", feature = "encryption"))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "async", feature = "encryption"))))]
+pub use stream::read_metadata_with_decryption as read_metadata_with_decryption_async;
 
 use crate::error::Error;
 use crate::metadata::{ColumnChunkMetaData, RowGroupMetaData};
@@ -28,6 +50,9 @@ use crate::schema::types::ParquetType;
 use crate::{error::Result, metadata::FileMetaData};
 
 pub use indexes::{read_columns_indexes, read_pages_locations};
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub use indexes::{read_columns_indexes_async, read_pages_locations_async};
 
 /// Filters row group metadata to only those row groups,
 /// for which the predicate function returns true
@@ -84,6 +109,22 @@ pub fn get_field_columns<'a>(
         .map(move |x| &metadata.row_groups[row_group].columns()[x.0])
 }
 
+/// Returns an [`Iterator`] of [`ColumnChunkMetaData`] corresponding to the columns
+/// of the field with id `id` at `row_group`, searching nested fields.
+/// For primitive fields (e.g. `i64`), the iterator has exactly one item; it has none if no field
+/// has id `id`.
+pub fn get_field_columns_by_id(
+    metadata: &FileMetaData,
+    row_group: usize,
+    id: i32,
+) -> impl Iterator<Item = &ColumnChunkMetaData> {
+    metadata
+        .schema()
+        .columns_by_id(id)
+        .into_iter()
+        .map(move |column| &metadata.row_groups[row_group].columns()[column])
+}
+
 /// Returns a [`ColumnIterator`] of column chunks corresponding to `field`.
 ///
 /// Contrarily to [`get_page_iterator`] that returns a single iterator of pages, this iterator
@@ -3,15 +3,18 @@ use std::{
     io::{Cursor, Read, Seek, SeekFrom},
 };
 
+#[cfg(feature = "encryption")]
+use crate::encryption::PageDecryptor;
 use crate::{
     error::Error,
     indexes::{FilteredPage, Interval},
     metadata::{ColumnChunkMetaData, Descriptor},
-    page::{CompressedDictPage, CompressedPage, ParquetPageHeader},
+    page::{CompressedPage, ParquetPageHeader},
     parquet_bridge::Compression,
+    read::encrypted_column_error,
 };
 
-use super::reader::{finish_page, read_page_header, PageMetaData};
+use super::reader::{finish_page, read_page_header, verify_crc, PageMetaData};
 
 #[derive(Debug, Clone, Copy)]
 enum State {
@@ -41,54 +44,14 @@ pub struct IndexedPageReader<R: Read + Seek> {
     pages: VecDeque<FilteredPage>,
 
     state: State,
-}
 
-fn read_page<R: Read + Seek>(
-    reader: &mut R,
-    start: u64,
-    length: usize,
-    buffer: &mut Vec<u8>,
-    data: &mut Vec<u8>,
-) -> Result<ParquetPageHeader, Error> {
-    // seek to the page
-    reader.seek(SeekFrom::Start(start))?;
-
-    // read [header][data] to buffer
-    buffer.clear();
-    buffer.try_reserve(length)?;
-    reader.by_ref().take(length as u64).read_to_end(buffer)?;
-
-    // deserialize [header]
-    let mut reader = Cursor::new(buffer);
-    let page_header = read_page_header(&mut reader, 1024 * 1024)?;
-    let header_size = reader.seek(SeekFrom::Current(0)).unwrap() as usize;
-    let buffer = reader.into_inner();
-
-    // copy [data]
-    data.clear();
-    data.extend_from_slice(&buffer[header_size..]);
-    Ok(page_header)
-}
+    verify_crc: bool,
 
-fn read_dict_page<R: Read + Seek>(
-    reader: &mut R,
-    start: u64,
-    length: usize,
-    buffer: &mut Vec<u8>,
-    data: &mut Vec<u8>,
-    compression: Compression,
-    descriptor: &Descriptor,
-) -> Result<CompressedDictPage, Error> {
-    let page_header = read_page(reader, start, length, buffer, data)?;
-
-    let page = finish_page(page_header, data, compression, descriptor, None)?;
-    if let CompressedPage::Dict(page) = page {
-        Ok(page)
-    } else {
-        Err(Error::OutOfSpec(
-            "The first page is not a dictionary page but it should".to_string(),
-        ))
-    }
+    // whether the pages are encrypted but there is no decryptor
+    missing_decryptor: bool,
+
+    #[cfg(feature = "encryption")]
+    decryptor: Option<PageDecryptor>,
 }
 
 impl<R: Read + Seek> IndexedPageReader<R> {
@@ -112,6 +75,7 @@ impl<R: Read + Seek> IndexedPageReader<R> {
         data_buffer: Vec<u8>,
     ) -> Self {
         let pages = pages.into_iter().collect();
+        let missing_decryptor = column.missing_decryptor();
         Self {
             reader,
             column_start: column.column_start,
@@ -121,14 +85,73 @@ impl<R: Read + Seek> IndexedPageReader<R> {
             data_buffer,
             pages,
             state: State::MaybeDict,
+            verify_crc: column.verify_crc,
+            missing_decryptor,
+            #[cfg(feature = "encryption")]
+            decryptor: column.decryptor.map(PageDecryptor::new),
         }
     }
 
+    /// Sets whether the CRC32 checksum declared in the header of each page is verified against
+    /// its data, returning [`Error::ChecksumMismatch`] on mismatch. Defaults to `false`.
+    pub fn with_crc_verification(mut self, verify_crc: bool) -> Self {
+        self.verify_crc = verify_crc;
+        self
+    }
+
     /// consumes self into the reader and the two internal buffers
     pub fn into_inner(self) -> (R, Vec<u8>, Vec<u8>) {
         (self.reader, self.buffer, self.data_buffer)
     }
 
+    /// Reads the page `[header][data]` at `start` into `data`, returning its header.
+    fn read_header_and_data(
+        &mut self,
+        start: u64,
+        length: usize,
+        data: &mut Vec<u8>,
+    ) -> Result<ParquetPageHeader, Error> {
+        if self.missing_decryptor {
+            return Err(encrypted_column_error());
+        }
+
+        // seek to the page
+        self.reader.seek(SeekFrom::Start(start))?;
+
+        // read [header][data] to buffer
+        self.buffer.clear();
+        self.buffer.try_reserve(length)?;
+        self.reader
+            .by_ref()
+            .take(length as u64)
+            .read_to_end(&mut self.buffer)?;
+
+        // deserialize [header]
+        let mut reader = Cursor::new(&self.buffer);
+        #[cfg(feature = "encryption")]
+        let page_header = match self.decryptor.as_ref() {
+            Some(decryptor) => decryptor.read_header(&mut reader, length)?,
+            None => read_page_header(&mut reader, 1024 * 1024)?,
+        };
+        #[cfg(not(feature = "encryption"))]
+        let page_header = read_page_header(&mut reader, 1024 * 1024)?;
+        let header_size = reader.position() as usize;
+
+        // copy [data]
+        data.clear();
+        data.extend_from_slice(&self.buffer[header_size..]);
+
+        if self.verify_crc {
+            verify_crc(&page_header, data)?;
+        }
+
+        #[cfg(feature = "encryption")]
+        if let Some(decryptor) = self.decryptor.as_mut() {
+            decryptor.decrypt_page(data)?;
+        }
+        Ok(page_header)
+    }
+
     fn read_page(
         &mut self,
         start: u64,
@@ -138,7 +161,7 @@ impl<R: Read + Seek> IndexedPageReader<R> {
         // it will be read - take buffer
         let mut data = std::mem::take(&mut self.data_buffer);
 
-        let page_header = read_page(&mut self.reader, start, length, &mut self.buffer, &mut data)?;
+        let page_header = self.read_header_and_data(start, length, &mut data)?;
 
         finish_page(
             page_header,
@@ -152,12 +175,16 @@ impl<R: Read + Seek> IndexedPageReader<R> {
     fn read_dict(&mut self) -> Option<Result<CompressedPage, Error>> {
         // a dictionary page exists iff the first data page is not at the start of
         // the column
-        let (start, length) = match self.pages.get(0) {
+        let (start, length) = match self.pages.front() {
             Some(page) => {
                 let length = (page.start - self.column_start) as usize;
                 if length > 0 {
                     (self.column_start, length)
                 } else {
+                    #[cfg(feature = "encryption")]
+                    if let Some(decryptor) = self.decryptor.as_mut() {
+                        decryptor.seek(0);
+                    }
                     return None;
                 }
             }
@@ -167,15 +194,23 @@ impl<R: Read + Seek> IndexedPageReader<R> {
         // it will be read - take buffer
         let mut data = std::mem::take(&mut self.data_buffer);
 
-        let maybe_page = read_dict_page(
-            &mut self.reader,
-            start,
-            length,
-            &mut self.buffer,
-            &mut data,
-            self.compression,
-            &self.descriptor,
-        );
+        let maybe_page = self
+            .read_header_and_data(start, length, &mut data)
+            .and_then(|page_header| {
+                finish_page(
+                    page_header,
+                    &mut data,
+                    self.compression,
+                    &self.descriptor,
+                    None,
+                )
+            })
+            .and_then(|page| match page {
+                CompressedPage::Dict(page) => Ok(page),
+                _ => Err(Error::OutOfSpec(
+                    "The first page is not a dictionary page but it should".to_string(),
+                )),
+            });
         Some(maybe_page.map(CompressedPage::Dict))
     }
 }
@@ -196,6 +231,10 @@ impl<R: Read + Seek> Iterator for IndexedPageReader<R> {
             State::Data => {
                 if let Some(page) = self.pages.pop_front() {
                     if page.selected_rows.is_empty() {
+                        #[cfg(feature = "encryption")]
+                        if let Some(decryptor) = self.decryptor.as_mut() {
+                            decryptor.skip_page();
+                        }
                         self.next()
                     } else {
                         Some(self.read_page(page.start, page.length, page.selected_rows))
@@ -4,6 +4,8 @@ use std::{io::Read, sync::Arc};
 use parquet_format_safe::thrift::protocol::TCompactInputProtocol;
 
 use crate::compression::Compression;
+#[cfg(feature = "encryption")]
+use crate::encryption::{ColumnDecryptor, PageDecryptor};
 use crate::error::{Error, Result};
 use crate::indexes::Interval;
 use crate::metadata::{ColumnChunkMetaData, Descriptor};
@@ -13,10 +15,14 @@ use crate::page::{
     ParquetPageHeader,
 };
 use crate::parquet_bridge::Encoding;
+use crate::read::encrypted_column_error;
 
 use super::PageIterator;
 
 /// This meta is a small part of [`ColumnChunkMetaData`].
+///
+/// New fields may be added to this struct; construct it with [`PageMetaData::new`] or from a
+/// [`ColumnChunkMetaData`] to remain compatible with them.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PageMetaData {
     /// The start offset of this column chunk in file.
@@ -27,6 +33,17 @@ pub struct PageMetaData {
     pub compression: Compression,
     /// The descriptor of this parquet column
     pub descriptor: Descriptor,
+    /// Whether the CRC32 checksum of pages, when declared in their header, is verified against
+    /// their data as stored in the file (compressed and, if encrypted, before decryption).
+    /// Defaults to `false`.
+    pub verify_crc: bool,
+    // whether the pages of this column chunk are encrypted. Reading them errors when there is no
+    // decryptor. Being private, it also keeps the struct from being built from its fields, so
+    // that the `encryption` feature is additive.
+    encrypted: bool,
+    // decrypts the pages of this column chunk when it is encrypted
+    #[cfg(feature = "encryption")]
+    pub(super) decryptor: Option<Arc<ColumnDecryptor>>,
 }
 
 impl PageMetaData {
@@ -42,8 +59,48 @@ impl PageMetaData {
             num_values,
             compression,
             descriptor,
+            verify_crc: false,
+            encrypted: false,
+            #[cfg(feature = "encryption")]
+            decryptor: None,
         }
     }
+
+    /// Sets whether the CRC32 checksum of pages is verified when they are read.
+    pub fn with_crc_verification(mut self, verify_crc: bool) -> Self {
+        self.verify_crc = verify_crc;
+        self
+    }
+
+    /// Whether the pages of this column chunk are encrypted. Reading them errors when there is
+    /// no decryptor.
+    pub fn is_encrypted(&self) -> bool {
+        self.encrypted
+    }
+
+    /// Sets the [`ColumnDecryptor`] that decrypts the pages, which are then read as encrypted.
+    #[cfg(feature = "encryption")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "encryption")))]
+    pub fn with_decryptor(mut self, decryptor: Arc<ColumnDecryptor>) -> Self {
+        self.encrypted = true;
+        self.decryptor = Some(decryptor);
+        self
+    }
+
+    /// Returns the [`ColumnDecryptor`] that decrypts the pages, if any.
+    #[cfg(feature = "encryption")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "encryption")))]
+    pub fn decryptor(&self) -> Option<&Arc<ColumnDecryptor>> {
+        self.decryptor.as_ref()
+    }
+
+    /// Whether the pages are encrypted but can't be decrypted because there is no decryptor.
+    pub(super) fn missing_decryptor(&self) -> bool {
+        #[cfg(feature = "encryption")]
+        return self.encrypted && self.decryptor.is_none();
+        #[cfg(not(feature = "encryption"))]
+        self.encrypted
+    }
 }
 
 impl From<&ColumnChunkMetaData> for PageMetaData {
@@ -53,6 +110,10 @@ impl From<&ColumnChunkMetaData> for PageMetaData {
             num_values: column.num_values(),
             compression: column.compression(),
             descriptor: column.descriptor().descriptor.clone(),
+            verify_crc: false,
+            encrypted: column.is_encrypted(),
+            #[cfg(feature = "encryption")]
+            decryptor: column.decryptor().cloned(),
         }
     }
 }
@@ -85,6 +146,14 @@ pub struct PageReader<R: Read> {
     pub(crate) scratch: Vec<u8>,
 
     max_header_size: usize,
+
+    verify_crc: bool,
+
+    // whether the pages are encrypted but there is no decryptor
+    missing_decryptor: bool,
+
+    #[cfg(feature = "encryption")]
+    decryptor: Option<PageDecryptor>,
 }
 
 impl<R: Read> PageReader<R> {
@@ -118,6 +187,7 @@ impl<R: Read> PageReader<R> {
         scratch: Vec<u8>,
         max_header_size: usize,
     ) -> Self {
+        let missing_decryptor = reader_meta.missing_decryptor();
         Self {
             reader,
             total_num_values: reader_meta.num_values,
@@ -127,9 +197,20 @@ impl<R: Read> PageReader<R> {
             pages_filter,
             scratch,
             max_header_size,
+            verify_crc: reader_meta.verify_crc,
+            missing_decryptor,
+            #[cfg(feature = "encryption")]
+            decryptor: reader_meta.decryptor.map(PageDecryptor::new),
         }
     }
 
+    /// Sets whether the CRC32 checksum declared in the header of each page is verified against
+    /// its data, returning [`Error::ChecksumMismatch`] on mismatch. Defaults to `false`.
+    pub fn with_crc_verification(mut self, verify_crc: bool) -> Self {
+        self.verify_crc = verify_crc;
+        self
+    }
+
     /// Returns the reader and this Readers' interval buffer
     pub fn into_inner(self) -> (R, Vec<u8>) {
         (self.reader, self.scratch)
@@ -175,12 +256,33 @@ pub(super) fn read_page_header<R: Read>(
     Ok(page_header)
 }
 
+/// Verifies the CRC32 checksum declared in the header of a page, if any, against its `data` as
+/// stored in the file, i.e. compressed and, if the page is encrypted, before decryption.
+pub(super) fn verify_crc(header: &ParquetPageHeader, data: &[u8]) -> Result<()> {
+    if let Some(expected) = header.crc {
+        let expected = expected as u32;
+        let actual = crc32fast::hash(data);
+        if actual != expected {
+            return Err(Error::ChecksumMismatch { expected, actual });
+        }
+    }
+    Ok(())
+}
+
 /// This function is lightweight and executes a minimal amount of work so that it is IO bounded.
 // Any un-necessary CPU-intensive tasks SHOULD be executed on individual pages.
 fn next_page<R: Read>(
     reader: &mut PageReader<R>,
     buffer: &mut Vec<u8>,
 ) -> Result<Option<CompressedPage>> {
+    // a column that can't be decrypted may have no values in its metadata
+    if reader.missing_decryptor {
+        return Err(encrypted_column_error());
+    }
+    #[cfg(feature = "encryption")]
+    if let Some(decryptor) = reader.decryptor.as_ref() {
+        decryptor.check_key()?;
+    }
     if reader.seen_num_values >= reader.total_num_values {
         return Ok(None);
     };
@@ -191,6 +293,12 @@ pub(super) fn build_page<R: Read>(
     reader: &mut PageReader<R>,
     buffer: &mut Vec<u8>,
 ) -> Result<Option<CompressedPage>> {
+    #[cfg(feature = "encryption")]
+    let page_header = match reader.decryptor.as_ref() {
+        Some(decryptor) => decryptor.read_header(&mut reader.reader, reader.max_header_size)?,
+        None => read_page_header(&mut reader.reader, reader.max_header_size)?,
+    };
+    #[cfg(not(feature = "encryption"))]
     let page_header = read_page_header(&mut reader.reader, reader.max_header_size)?;
 
     reader.seen_num_values += get_page_header(&page_header)?
@@ -207,6 +315,15 @@ pub(super) fn build_page<R: Read>(
         .take(read_size as u64)
         .read_to_end(buffer)?;
 
+    if reader.verify_crc {
+        verify_crc(&page_header, buffer)?;
+    }
+
+    #[cfg(feature = "encryption")]
+    if let Some(decryptor) = reader.decryptor.as_mut() {
+        decryptor.decrypt_page(buffer)?;
+    }
+
     finish_page(
         page_header,
         buffer,
@@ -5,15 +5,20 @@ use futures::io::{copy, sink};
 use futures::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt, Stream};
 use parquet_format_safe::thrift::protocol::TCompactInputStreamProtocol;
 
-use crate::compression::Compression;
+#[cfg(feature = "encryption")]
+use crate::encryption::{read_module_async, PageDecryptor};
 use crate::error::Result;
-use crate::metadata::{ColumnChunkMetaData, Descriptor};
+use crate::metadata::ColumnChunkMetaData;
 use crate::page::{CompressedPage, ParquetPageHeader};
+use crate::read::encrypted_column_error;
 
-use super::reader::{finish_page, get_page_header, PageMetaData};
+use super::reader::{finish_page, get_page_header, verify_crc, PageMetaData};
 use super::PageFilter;
 
 /// Returns a stream of compressed data pages
+///
+/// The CRC32 checksum of pages is not verified; use [`get_page_stream_with_page_meta`] with
+/// [`PageMetaData::with_crc_verification`] to verify it.
 pub async fn get_page_stream<'a, RR: AsyncRead + Unpin + Send + AsyncSeek>(
     column_metadata: &'a ColumnChunkMetaData,
     reader: &'a mut RR,
@@ -39,12 +44,9 @@ pub async fn get_page_stream_from_column_start<'a, R: AsyncRead + Unpin + Send>(
     pages_filter: PageFilter,
     max_header_size: usize,
 ) -> Result<impl Stream<Item = Result<CompressedPage>> + 'a> {
-    let page_metadata: PageMetaData = column_metadata.into();
     Ok(_get_page_stream(
         reader,
-        page_metadata.num_values,
-        page_metadata.compression,
-        page_metadata.descriptor,
+        column_metadata.into(),
         scratch,
         pages_filter,
         max_header_size,
@@ -63,9 +65,7 @@ pub async fn get_page_stream_with_page_meta<RR: AsyncRead + Unpin + Send + Async
     reader.seek(SeekFrom::Start(column_start)).await?;
     Ok(_get_page_stream(
         reader,
-        page_metadata.num_values,
-        page_metadata.compression,
-        page_metadata.descriptor,
+        page_metadata,
         scratch,
         pages_filter,
         max_header_size,
@@ -74,17 +74,39 @@ pub async fn get_page_stream_with_page_meta<RR: AsyncRead + Unpin + Send + Async
 
 fn _get_page_stream<R: AsyncRead + Unpin + Send>(
     reader: &mut R,
-    total_num_values: i64,
-    compression: Compression,
-    descriptor: Descriptor,
+    page_metadata: PageMetaData,
     mut scratch: Vec<u8>,
     pages_filter: PageFilter,
     max_header_size: usize,
 ) -> impl Stream<Item = Result<CompressedPage>> + '_ {
+    let missing_decryptor = page_metadata.missing_decryptor();
+    let total_num_values = page_metadata.num_values;
+    let compression = page_metadata.compression;
+    let descriptor = page_metadata.descriptor;
+    let verify = page_metadata.verify_crc;
+    #[cfg(feature = "encryption")]
+    let mut decryptor = page_metadata.decryptor.map(PageDecryptor::new);
+
     let mut seen_values = 0i64;
     try_stream! {
+        // a column that can't be decrypted may have no values in its metadata
+        if missing_decryptor {
+            Err(encrypted_column_error())?;
+        }
+        #[cfg(feature = "encryption")]
+        if let Some(decryptor) = decryptor.as_ref() {
+            decryptor.check_key()?;
+        }
         while seen_values < total_num_values {
             // the header
+            #[cfg(feature = "encryption")]
+            let page_header = match decryptor.as_ref() {
+                Some(decryptor) => {
+                    decryptor.decrypt_header(&read_module_async(reader, max_header_size).await?)?
+                }
+                None => read_page_header(reader, max_header_size).await?,
+            };
+            #[cfg(not(feature = "encryption"))]
             let page_header = read_page_header(reader, max_header_size).await?;
 
             let data_header = get_page_header(&page_header)?;
@@ -96,6 +118,10 @@ fn _get_page_stream<R: AsyncRead + Unpin + Send>(
                 if !pages_filter(&descriptor, &data_header) {
                     // page to be skipped, we sill need to seek
                     copy(reader.take(read_size as u64), &mut sink()).await?;
+                    #[cfg(feature = "encryption")]
+                    if let Some(decryptor) = decryptor.as_mut() {
+                        decryptor.skip_page();
+                    }
                     continue
                 }
             }
@@ -107,6 +133,15 @@ fn _get_page_stream<R: AsyncRead + Unpin + Send>(
                 .take(read_size as u64)
                 .read_to_end(&mut scratch).await?;
 
+            if verify {
+                verify_crc(&page_header, &scratch)?;
+            }
+
+            #[cfg(feature = "encryption")]
+            if let Some(decryptor) = decryptor.as_mut() {
+                decryptor.decrypt_page(&mut scratch)?;
+            }
+
             yield finish_page(
                 page_header,
                 &mut scratch,
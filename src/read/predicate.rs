@@ -0,0 +1,1003 @@
+use std::cmp::Ordering;
+use std::io::{Read, Seek};
+
+use crate::error::{Error, Result};
+use crate::indexes::{
+    compute_page_row_intervals, select_pages, BooleanIndex, BoundaryOrder, ByteIndex, FilteredPage,
+    FixedLenByteIndex, Index, Interval, NativeIndex, PageIndex, PageLocation,
+};
+use crate::metadata::{get_sort_order, ColumnOrder, FileMetaData, RowGroupMetaData, SortOrder};
+use crate::schema::types::PhysicalType;
+use crate::statistics::{
+    BinaryStatistics, BooleanStatistics, FixedLenStatistics, PrimitiveStatistics, Statistics,
+};
+use crate::types::ord_signed_binary;
+
+/// A literal compared against the values of a column by a [`Predicate`].
+///
+/// Literals are expressed in the physical type of the column they are compared to, e.g.
+/// a `DATE` is an [`Value::Int32`] with the number of days since the epoch and a `DECIMAL`
+/// backed by a `FIXED_LEN_BYTE_ARRAY` is a [`Value::Bytes`] with its big-endian two's
+/// complement representation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// The value of a `BOOLEAN` column
+    Boolean(bool),
+    /// The value of an `INT32` column
+    Int32(i32),
+    /// The value of an `INT64` column
+    Int64(i64),
+    /// The value of a `FLOAT` column
+    Float(f32),
+    /// The value of a `DOUBLE` column
+    Double(f64),
+    /// The value of a `BYTE_ARRAY` or `FIXED_LEN_BYTE_ARRAY` column
+    Bytes(Vec<u8>),
+}
+
+impl Value {
+    pub(crate) fn is_of(&self, physical_type: &PhysicalType) -> bool {
+        matches!(
+            (self, physical_type),
+            (Value::Boolean(_), PhysicalType::Boolean)
+                | (Value::Int32(_), PhysicalType::Int32)
+                | (Value::Int64(_), PhysicalType::Int64)
+                | (Value::Float(_), PhysicalType::Float)
+                | (Value::Double(_), PhysicalType::Double)
+                | (Value::Bytes(_), PhysicalType::ByteArray)
+                | (Value::Bytes(_), PhysicalType::FixedLenByteArray(_))
+        )
+    }
+}
+
+/// A predicate over the columns of a file, where columns are identified by their path in
+/// the schema (see [`crate::metadata::ColumnDescriptor::path_in_schema`]).
+///
+/// Comparisons follow SQL semantics: they never hold for null values. Predicates are
+/// evaluated against statistics (e.g. via [`prune_row_groups`]), which can only tell
+/// whether some values _may_ satisfy them.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Predicate {
+    /// `column == value`
+    Eq(Vec<String>, Value),
+    /// `column < value`
+    Lt(Vec<String>, Value),
+    /// `column <= value`
+    LtEq(Vec<String>, Value),
+    /// `column > value`
+    Gt(Vec<String>, Value),
+    /// `column >= value`
+    GtEq(Vec<String>, Value),
+    /// `lower <= column <= upper`
+    Between(Vec<String>, Value, Value),
+    /// `column` equal to any of the values
+    In(Vec<String>, Vec<Value>),
+    /// `column` is null
+    IsNull(Vec<String>),
+    /// Both predicates hold
+    And(Box<Predicate>, Box<Predicate>),
+    /// Either predicate holds
+    Or(Box<Predicate>, Box<Predicate>),
+    /// The predicate does not hold
+    Not(Box<Predicate>),
+}
+
+impl Predicate {
+    /// Returns the predicate `self AND other`
+    pub fn and(self, other: Predicate) -> Self {
+        Self::And(Box::new(self), Box::new(other))
+    }
+
+    /// Returns the predicate `self OR other`
+    pub fn or(self, other: Predicate) -> Self {
+        Self::Or(Box::new(self), Box::new(other))
+    }
+
+    /// Returns whether some rows of the row group `row_group` of `metadata` may satisfy
+    /// this predicate, according to the statistics of its columns.
+    /// # Error
+    /// Errors if a column of the predicate is not a primitive column of the schema, if a
+    /// literal does not match the physical type of its column or if the statistics are
+    /// invalid.
+    pub fn may_match(&self, metadata: &FileMetaData, row_group: &RowGroupMetaData) -> Result<bool> {
+        let outcome = self.evaluate(&mut |path| {
+            let index = column_index(metadata, path)?;
+            row_group_summary(metadata, row_group, index)
+        })?;
+        Ok(outcome.maybe_true)
+    }
+
+    /// Pushes the paths of the columns of this predicate to `columns`
+    fn columns<'a>(&'a self, columns: &mut Vec<&'a [String]>) {
+        match self {
+            Predicate::Eq(path, _)
+            | Predicate::Lt(path, _)
+            | Predicate::LtEq(path, _)
+            | Predicate::Gt(path, _)
+            | Predicate::GtEq(path, _)
+            | Predicate::Between(path, _, _)
+            | Predicate::In(path, _)
+            | Predicate::IsNull(path) => columns.push(path),
+            Predicate::And(lhs, rhs) | Predicate::Or(lhs, rhs) => {
+                lhs.columns(columns);
+                rhs.columns(columns);
+            }
+            Predicate::Not(predicate) => predicate.columns(columns),
+        }
+    }
+
+    /// Evaluates this predicate against the [`Summary`] of each column returned by `summary`.
+    pub(crate) fn evaluate(
+        &self,
+        summary: &mut dyn FnMut(&[String]) -> Result<Summary>,
+    ) -> Result<Outcome> {
+        Ok(match self {
+            Predicate::Eq(path, value) => compare(summary(path)?, &[value], |min, max, order| {
+                let maybe_true = le(min, value, order)? && le(value, max, order)?;
+                let maybe_false = !(eq(min, value, order)? && eq(max, value, order)?);
+                Some((maybe_true, maybe_false))
+            })?,
+            Predicate::Lt(path, value) => compare(summary(path)?, &[value], |min, max, order| {
+                Some((lt(min, value, order)?, le(value, max, order)?))
+            })?,
+            Predicate::LtEq(path, value) => {
+                compare(summary(path)?, &[value], |min, max, order| {
+                    Some((le(min, value, order)?, lt(value, max, order)?))
+                })?
+            }
+            Predicate::Gt(path, value) => compare(summary(path)?, &[value], |min, max, order| {
+                Some((lt(value, max, order)?, le(min, value, order)?))
+            })?,
+            Predicate::GtEq(path, value) => {
+                compare(summary(path)?, &[value], |min, max, order| {
+                    Some((le(value, max, order)?, lt(min, value, order)?))
+                })?
+            }
+            Predicate::Between(path, lower, upper) => {
+                compare(summary(path)?, &[lower, upper], |min, max, order| {
+                    let maybe_true = le(lower, max, order)? && le(min, upper, order)?;
+                    let maybe_false = lt(min, lower, order)? || lt(upper, max, order)?;
+                    Some((maybe_true, maybe_false))
+                })?
+            }
+            Predicate::In(path, values) => {
+                let literals = values.iter().collect::<Vec<_>>();
+                compare(summary(path)?, &literals, |min, max, order| {
+                    let mut maybe_true = false;
+                    let mut all_equal = false;
+                    for value in values {
+                        maybe_true |= le(min, value, order)? && le(value, max, order)?;
+                        all_equal |= eq(min, value, order)? && eq(max, value, order)?;
+                    }
+                    Some((maybe_true, !all_equal))
+                })?
+            }
+            Predicate::IsNull(path) => {
+                let summary = summary(path)?;
+                Outcome {
+                    maybe_true: summary.null_count != Some(0),
+                    maybe_false: !summary.all_null,
+                    maybe_null: false,
+                }
+            }
+            Predicate::And(lhs, rhs) => lhs.evaluate(summary)?.and(rhs.evaluate(summary)?),
+            Predicate::Or(lhs, rhs) => lhs.evaluate(summary)?.or(rhs.evaluate(summary)?),
+            Predicate::Not(predicate) => predicate.evaluate(summary)?.not(),
+        })
+    }
+}
+
+impl std::ops::Not for Predicate {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        Self::Not(Box::new(self))
+    }
+}
+
+/// What is known about the values of a column in a set of rows (e.g. a row group or a page).
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Summary {
+    pub physical_type: PhysicalType,
+    /// The order in which `min` and `max` were computed
+    pub sort_order: SortOrder,
+    pub min: Option<Value>,
+    pub max: Option<Value>,
+    pub null_count: Option<i64>,
+    /// Whether all values are known to be null
+    pub all_null: bool,
+}
+
+/// Whether some rows may evaluate a predicate to true, false or null.
+/// Each flag is `true` unless it is known that no row evaluates to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Outcome {
+    pub maybe_true: bool,
+    pub maybe_false: bool,
+    pub maybe_null: bool,
+}
+
+impl Outcome {
+    fn and(self, other: Self) -> Self {
+        Self {
+            maybe_true: self.maybe_true && other.maybe_true,
+            maybe_false: self.maybe_false || other.maybe_false,
+            maybe_null: (self.maybe_null && (other.maybe_true || other.maybe_null))
+                || (other.maybe_null && (self.maybe_true || self.maybe_null)),
+        }
+    }
+
+    fn or(self, other: Self) -> Self {
+        Self {
+            maybe_true: self.maybe_true || other.maybe_true,
+            maybe_false: self.maybe_false && other.maybe_false,
+            maybe_null: (self.maybe_null && (other.maybe_false || other.maybe_null))
+                || (other.maybe_null && (self.maybe_false || self.maybe_null)),
+        }
+    }
+
+    fn not(self) -> Self {
+        Self {
+            maybe_true: self.maybe_false,
+            maybe_false: self.maybe_true,
+            maybe_null: self.maybe_null,
+        }
+    }
+}
+
+/// Evaluates a comparison of a column against `literals`. `op` returns whether some
+/// values in `[min, max]` may satisfy the comparison and whether some may not, or `None`
+/// if the values can't be compared.
+fn compare<F>(summary: Summary, literals: &[&Value], op: F) -> Result<Outcome>
+where
+    F: Fn(&Value, &Value, SortOrder) -> Option<(bool, bool)>,
+{
+    if let Some(literal) = literals.iter().find(|x| !x.is_of(&summary.physical_type)) {
+        return Err(Error::InvalidParameter(format!(
+            "The literal {:?} can't be compared to a column of physical type {:?}",
+            literal, summary.physical_type
+        )));
+    }
+    let maybe_null = summary.null_count != Some(0);
+    if summary.all_null {
+        return Ok(Outcome {
+            maybe_true: false,
+            maybe_false: false,
+            maybe_null,
+        });
+    }
+    let (maybe_true, maybe_false) = match (&summary.min, &summary.max) {
+        (Some(min), Some(max)) => op(min, max, summary.sort_order).unwrap_or((true, true)),
+        _ => (true, true),
+    };
+    Ok(Outcome {
+        maybe_true,
+        maybe_false,
+        maybe_null,
+    })
+}
+
+/// Compares two values of the same physical type according to `order`, returning `None`
+/// when they are not comparable (e.g. `NaN` or an undefined order).
+fn cmp(lhs: &Value, rhs: &Value, order: SortOrder) -> Option<Ordering> {
+    match (lhs, rhs, order) {
+        (_, _, SortOrder::Undefined) => None,
+        (Value::Boolean(lhs), Value::Boolean(rhs), _) => Some(lhs.cmp(rhs)),
+        (Value::Int32(lhs), Value::Int32(rhs), SortOrder::Signed) => Some(lhs.cmp(rhs)),
+        (Value::Int32(lhs), Value::Int32(rhs), SortOrder::Unsigned) => {
+            Some((*lhs as u32).cmp(&(*rhs as u32)))
+        }
+        (Value::Int64(lhs), Value::Int64(rhs), SortOrder::Signed) => Some(lhs.cmp(rhs)),
+        (Value::Int64(lhs), Value::Int64(rhs), SortOrder::Unsigned) => {
+            Some((*lhs as u64).cmp(&(*rhs as u64)))
+        }
+        (Value::Float(lhs), Value::Float(rhs), _) => lhs.partial_cmp(rhs),
+        (Value::Double(lhs), Value::Double(rhs), _) => lhs.partial_cmp(rhs),
+        (Value::Bytes(lhs), Value::Bytes(rhs), SortOrder::Unsigned) => Some(lhs.cmp(rhs)),
+        (Value::Bytes(lhs), Value::Bytes(rhs), SortOrder::Signed) => {
+            Some(ord_signed_binary(lhs, rhs))
+        }
+        _ => None,
+    }
+}
+
+fn lt(lhs: &Value, rhs: &Value, order: SortOrder) -> Option<bool> {
+    cmp(lhs, rhs, order).map(|x| x == Ordering::Less)
+}
+
+fn le(lhs: &Value, rhs: &Value, order: SortOrder) -> Option<bool> {
+    cmp(lhs, rhs, order).map(|x| x != Ordering::Greater)
+}
+
+fn eq(lhs: &Value, rhs: &Value, order: SortOrder) -> Option<bool> {
+    cmp(lhs, rhs, order).map(|x| x == Ordering::Equal)
+}
+
+/// Returns the index of the primitive column at `path` of the schema of `metadata`.
+pub(crate) fn column_index(metadata: &FileMetaData, path: &[String]) -> Result<usize> {
+    metadata
+        .schema()
+        .columns()
+        .iter()
+        .position(|column| column.path_in_schema == path)
+        .ok_or_else(|| {
+            Error::InvalidParameter(format!(
+                "The predicate's column {} is not a primitive column of the schema",
+                path.join(".")
+            ))
+        })
+}
+
+/// Returns the order in which the min and max of the `column`th column of `metadata` can be
+/// compared.
+pub(crate) fn sort_order(metadata: &FileMetaData, column: usize) -> SortOrder {
+    match metadata.column_order(column) {
+        ColumnOrder::TypeDefinedOrder(order) => order,
+        // legacy writers computed min and max with a signed comparison, which is only
+        // correct for types whose order is signed.
+        ColumnOrder::Undefined => {
+            let primitive_type = &metadata.schema().columns()[column]
+                .descriptor
+                .primitive_type;
+            match get_sort_order(
+                &primitive_type.logical_type,
+                &primitive_type.converted_type,
+                &primitive_type.physical_type,
+            ) {
+                SortOrder::Signed => SortOrder::Signed,
+                _ => SortOrder::Undefined,
+            }
+        }
+    }
+}
+
+fn row_group_summary(
+    metadata: &FileMetaData,
+    row_group: &RowGroupMetaData,
+    column: usize,
+) -> Result<Summary> {
+    let chunk = &row_group.columns()[column];
+    let physical_type = chunk.physical_type();
+    let sort_order = sort_order(metadata, column);
+
+    let statistics = chunk.statistics().transpose()?;
+    let (min, max, null_count) = statistics
+        .as_deref()
+        .map(min_max)
+        .unwrap_or((None, None, None));
+
+    Ok(Summary {
+        physical_type,
+        sort_order,
+        min,
+        max,
+        null_count,
+        all_null: null_count == Some(chunk.num_values()),
+    })
+}
+
+fn min_max(statistics: &dyn Statistics) -> (Option<Value>, Option<Value>, Option<i64>) {
+    fn primitive<T: crate::types::NativeType>(
+        statistics: &dyn Statistics,
+        f: fn(T) -> Value,
+    ) -> (Option<Value>, Option<Value>) {
+        let statistics = statistics
+            .as_any()
+            .downcast_ref::<PrimitiveStatistics<T>>()
+            .unwrap();
+        (statistics.min_value.map(f), statistics.max_value.map(f))
+    }
+
+    let (min, max) = match statistics.physical_type() {
+        PhysicalType::Boolean => {
+            let statistics = statistics
+                .as_any()
+                .downcast_ref::<BooleanStatistics>()
+                .unwrap();
+            (
+                statistics.min_value.map(Value::Boolean),
+                statistics.max_value.map(Value::Boolean),
+            )
+        }
+        PhysicalType::Int32 => primitive::<i32>(statistics, Value::Int32),
+        PhysicalType::Int64 => primitive::<i64>(statistics, Value::Int64),
+        PhysicalType::Float => primitive::<f32>(statistics, Value::Float),
+        PhysicalType::Double => primitive::<f64>(statistics, Value::Double),
+        // the order of int96 is undefined
+        PhysicalType::Int96 => (None, None),
+        PhysicalType::ByteArray => {
+            let statistics = statistics
+                .as_any()
+                .downcast_ref::<BinaryStatistics>()
+                .unwrap();
+            (
+                statistics.min_value.clone().map(Value::Bytes),
+                statistics.max_value.clone().map(Value::Bytes),
+            )
+        }
+        PhysicalType::FixedLenByteArray(_) => {
+            let statistics = statistics
+                .as_any()
+                .downcast_ref::<FixedLenStatistics>()
+                .unwrap();
+            (
+                statistics.min_value.clone().map(Value::Bytes),
+                statistics.max_value.clone().map(Value::Bytes),
+            )
+        }
+    };
+    (min, max, statistics.null_count())
+}
+
+/// Returns the indices of the row groups of `metadata` whose rows may satisfy `predicate`,
+/// according to the statistics of their columns.
+/// # Error
+/// Errors under the same conditions as [`Predicate::may_match`].
+pub fn prune_row_groups(metadata: &FileMetaData, predicate: &Predicate) -> Result<Vec<usize>> {
+    metadata
+        .row_groups
+        .iter()
+        .enumerate()
+        .filter_map(
+            |(i, row_group)| match predicate.may_match(metadata, row_group) {
+                Ok(true) => Some(Ok(i)),
+                Ok(false) => None,
+                Err(e) => Some(Err(e)),
+            },
+        )
+        .collect()
+}
+
+/// Returns the [`Summary`] of every page of `index` and the order of its pages
+fn page_summaries(index: &dyn Index, sort_order: SortOrder) -> (Vec<Summary>, BoundaryOrder) {
+    fn summaries<T: Clone>(
+        indexes: &[PageIndex<T>],
+        boundary_order: BoundaryOrder,
+        physical_type: PhysicalType,
+        sort_order: SortOrder,
+        f: fn(T) -> Option<Value>,
+    ) -> (Vec<Summary>, BoundaryOrder) {
+        let summaries = indexes
+            .iter()
+            .map(|page| Summary {
+                physical_type,
+                sort_order,
+                min: page.min.clone().and_then(f),
+                max: page.max.clone().and_then(f),
+                null_count: page.null_count,
+                // min and max are only missing from null pages
+                all_null: page.min.is_none() && page.max.is_none(),
+            })
+            .collect();
+        (summaries, boundary_order)
+    }
+    fn native<T: crate::types::NativeType>(
+        index: &dyn Index,
+        sort_order: SortOrder,
+        f: fn(T) -> Option<Value>,
+    ) -> (Vec<Summary>, BoundaryOrder) {
+        let index = index.as_any().downcast_ref::<NativeIndex<T>>().unwrap();
+        summaries(
+            &index.indexes,
+            index.boundary_order,
+            *index.physical_type(),
+            sort_order,
+            f,
+        )
+    }
+
+    match *index.physical_type() {
+        PhysicalType::Boolean => {
+            let index = index.as_any().downcast_ref::<BooleanIndex>().unwrap();
+            summaries(
+                &index.indexes,
+                index.boundary_order,
+                PhysicalType::Boolean,
+                sort_order,
+                |x| Some(Value::Boolean(x)),
+            )
+        }
+        PhysicalType::Int32 => native::<i32>(index, sort_order, |x| Some(Value::Int32(x))),
+        PhysicalType::Int64 => native::<i64>(index, sort_order, |x| Some(Value::Int64(x))),
+        PhysicalType::Float => native::<f32>(index, sort_order, |x| Some(Value::Float(x))),
+        PhysicalType::Double => native::<f64>(index, sort_order, |x| Some(Value::Double(x))),
+        // the order of int96 is undefined
+        PhysicalType::Int96 => native::<[u32; 3]>(index, sort_order, |_| None),
+        PhysicalType::ByteArray => {
+            let index = index.as_any().downcast_ref::<ByteIndex>().unwrap();
+            summaries(
+                &index.indexes,
+                index.boundary_order,
+                PhysicalType::ByteArray,
+                sort_order,
+                |x| Some(Value::Bytes(x)),
+            )
+        }
+        physical_type @ PhysicalType::FixedLenByteArray(_) => {
+            let index = index.as_any().downcast_ref::<FixedLenByteIndex>().unwrap();
+            summaries(
+                &index.indexes,
+                index.boundary_order,
+                physical_type,
+                sort_order,
+                |x| Some(Value::Bytes(x)),
+            )
+        }
+    }
+}
+
+/// The pages of a column involved in a predicate
+struct ColumnPages {
+    column: usize,
+    /// The first row of every page
+    starts: Vec<usize>,
+    summaries: Vec<Summary>,
+    boundary_order: BoundaryOrder,
+}
+
+impl ColumnPages {
+    /// The rows of the pages `pages`
+    fn rows(&self, pages: std::ops::Range<usize>, num_rows: usize) -> Option<Interval> {
+        (pages.start < pages.end).then(|| {
+            let start = self.starts[pages.start];
+            let end = self.starts.get(pages.end).copied().unwrap_or(num_rows);
+            Interval::new(start, end - start)
+        })
+    }
+}
+
+/// Returns the rows that may satisfy `predicate` using a binary search over the pages of
+/// the columns whose index is ordered (see [`BoundaryOrder`]), or `None` if `predicate` is
+/// not a combination of comparisons of such columns.
+fn search_pages(
+    metadata: &FileMetaData,
+    predicate: &Predicate,
+    columns: &[ColumnPages],
+    num_rows: usize,
+) -> Option<Vec<Interval>> {
+    // the bounds of the values that satisfy a comparison: `(value, inclusive)`
+    let (path, lower, upper) = match predicate {
+        Predicate::Eq(path, value) => (path, Some((value, true)), Some((value, true))),
+        Predicate::Lt(path, value) => (path, None, Some((value, false))),
+        Predicate::LtEq(path, value) => (path, None, Some((value, true))),
+        Predicate::Gt(path, value) => (path, Some((value, false)), None),
+        Predicate::GtEq(path, value) => (path, Some((value, true)), None),
+        Predicate::Between(path, lower, upper) => (path, Some((lower, true)), Some((upper, true))),
+        Predicate::And(lhs, rhs) => {
+            let lhs = search_pages(metadata, lhs, columns, num_rows)?;
+            let rhs = search_pages(metadata, rhs, columns, num_rows)?;
+            return Some(intersect(&lhs, &rhs));
+        }
+        Predicate::Or(lhs, rhs) => {
+            let lhs = search_pages(metadata, lhs, columns, num_rows)?;
+            let rhs = search_pages(metadata, rhs, columns, num_rows)?;
+            return Some(union(&lhs, &rhs));
+        }
+        _ => return None,
+    };
+    let column = column_index(metadata, path).ok()?;
+    let pages = columns.iter().find(|x| x.column == column)?;
+    let (physical_type, sort_order) = pages
+        .summaries
+        .first()
+        .map(|x| (x.physical_type, x.sort_order))?;
+    if pages.boundary_order == BoundaryOrder::Unordered
+        || [lower, upper]
+            .iter()
+            .flatten()
+            .any(|(value, _)| !value.is_of(&physical_type))
+    {
+        return None;
+    }
+
+    // null pages never satisfy comparisons; the others must have a min and a max
+    let non_null = pages
+        .summaries
+        .iter()
+        .enumerate()
+        .filter(|(_, summary)| !summary.all_null)
+        .map(|(page, summary)| Some((page, summary.min.as_ref()?, summary.max.as_ref()?)))
+        .collect::<Option<Vec<_>>>()?;
+
+    // whether the values of a page may satisfy the lower and upper bound. The pages that
+    // satisfy the lower bound are a suffix when ascending (a prefix when descending), and
+    // those that satisfy the upper bound a prefix when ascending (a suffix when descending).
+    let mut comparable = true;
+    let mut is_above_lower = |(_, _, max): &(usize, &Value, &Value)| {
+        lower.is_none_or(|(value, inclusive)| {
+            let ordering = cmp(max, value, sort_order);
+            comparable &= ordering.is_some();
+            ordering.is_some_and(|x| x.is_gt() || (inclusive && x.is_eq()))
+        })
+    };
+    let (first, last) = match pages.boundary_order {
+        BoundaryOrder::Ascending => {
+            let first = non_null.partition_point(|page| !is_above_lower(page));
+            (first, non_null.len())
+        }
+        _ => (0, non_null.partition_point(is_above_lower)),
+    };
+    let mut is_below_upper = |(_, min, _): &(usize, &Value, &Value)| {
+        upper.is_none_or(|(value, inclusive)| {
+            let ordering = cmp(min, value, sort_order);
+            comparable &= ordering.is_some();
+            ordering.is_some_and(|x| x.is_lt() || (inclusive && x.is_eq()))
+        })
+    };
+    let non_null = &non_null[first..last];
+    let (first, last) = match pages.boundary_order {
+        BoundaryOrder::Ascending => (0, non_null.partition_point(is_below_upper)),
+        _ => (
+            non_null.partition_point(|page| !is_below_upper(page)),
+            non_null.len(),
+        ),
+    };
+    if !comparable {
+        return None;
+    }
+
+    let mut intervals: Vec<Interval> = vec![];
+    for (page, _, _) in &non_null[first..last] {
+        let rows = pages.rows(*page..*page + 1, num_rows)?;
+        push_interval(&mut intervals, rows);
+    }
+    Some(intervals)
+}
+
+/// Appends `interval` to `intervals`, merging it with the last one when they are adjacent
+fn push_interval(intervals: &mut Vec<Interval>, interval: Interval) {
+    if interval.length == 0 {
+        return;
+    }
+    match intervals.last_mut() {
+        Some(last) if last.start + last.length == interval.start => last.length += interval.length,
+        _ => intervals.push(interval),
+    }
+}
+
+/// Returns the rows in both `lhs` and `rhs`, two sorted and disjoint sets of intervals
+fn intersect(lhs: &[Interval], rhs: &[Interval]) -> Vec<Interval> {
+    let mut intervals = vec![];
+    let (mut i, mut j) = (0, 0);
+    while i < lhs.len() && j < rhs.len() {
+        let start = lhs[i].start.max(rhs[j].start);
+        let lhs_end = lhs[i].start + lhs[i].length;
+        let rhs_end = rhs[j].start + rhs[j].length;
+        let end = lhs_end.min(rhs_end);
+        if start < end {
+            push_interval(&mut intervals, Interval::new(start, end - start));
+        }
+        if lhs_end < rhs_end {
+            i += 1
+        } else {
+            j += 1
+        }
+    }
+    intervals
+}
+
+/// Returns the rows in `lhs` or `rhs`, two sorted and disjoint sets of intervals
+fn union(lhs: &[Interval], rhs: &[Interval]) -> Vec<Interval> {
+    let mut all = lhs.iter().chain(rhs.iter()).copied().collect::<Vec<_>>();
+    all.sort_unstable_by_key(|x| x.start);
+
+    let mut intervals: Vec<Interval> = vec![];
+    for interval in all {
+        match intervals.last_mut() {
+            Some(last) if interval.start <= last.start + last.length => {
+                let end = (last.start + last.length).max(interval.start + interval.length);
+                last.length = end - last.start;
+            }
+            _ => intervals.push(interval),
+        }
+    }
+    intervals
+}
+
+/// Returns the [`Interval`]s of rows of `row_group` that may satisfy `predicate`, according
+/// to the column indexes of its columns.
+///
+/// `indexes` and `locations` are the column indexes and page locations of all columns of
+/// `row_group`, as returned by [`super::read_columns_indexes`] and
+/// [`super::read_pages_locations`]. When they are not available (i.e. empty), all rows of
+/// the row group are returned.
+///
+/// Comparisons (and their conjunctions and disjunctions) of columns whose pages are ordered
+/// (see [`BoundaryOrder`]) are resolved with a binary search over their pages.
+/// # Error
+/// Errors under the same conditions as [`Predicate::may_match`] and if the column index
+/// of a column is inconsistent with its page locations.
+pub fn prune_pages(
+    metadata: &FileMetaData,
+    row_group: &RowGroupMetaData,
+    predicate: &Predicate,
+    indexes: &[Box<dyn Index>],
+    locations: &[Vec<PageLocation>],
+) -> Result<Vec<Interval>> {
+    let num_rows = row_group.num_rows();
+    let num_columns = row_group.columns().len();
+    if indexes.len() != num_columns || locations.len() != num_columns {
+        return Ok(if num_rows == 0 {
+            vec![]
+        } else {
+            vec![Interval::new(0, num_rows)]
+        });
+    }
+
+    // the first row and the summary of every page of every column of the predicate
+    let mut paths = vec![];
+    predicate.columns(&mut paths);
+    let mut columns: Vec<ColumnPages> = vec![];
+    for path in paths {
+        let column = column_index(metadata, path)?;
+        if columns.iter().any(|x| x.column == column) {
+            continue;
+        }
+        let starts = compute_page_row_intervals(&locations[column], num_rows)?
+            .into_iter()
+            .map(|interval| interval.start)
+            .collect::<Vec<_>>();
+        let primitive_type = &metadata.schema().columns()[column]
+            .descriptor
+            .primitive_type;
+        // column indexes are always computed in the order of the column's type
+        let sort_order = get_sort_order(
+            &primitive_type.logical_type,
+            &primitive_type.converted_type,
+            &primitive_type.physical_type,
+        );
+        let (summaries, boundary_order) = page_summaries(indexes[column].as_ref(), sort_order);
+        if starts.len() != summaries.len() {
+            return Err(Error::OutOfSpec(
+                "The column index and the offset index of a column must have the same number of pages"
+                    .to_string(),
+            ));
+        }
+        columns.push(ColumnPages {
+            column,
+            starts,
+            summaries,
+            boundary_order,
+        });
+    }
+
+    if let Some(intervals) = search_pages(metadata, predicate, &columns, num_rows) {
+        return Ok(intervals);
+    }
+
+    // split the rows into segments, each within a single page of every column
+    let mut boundaries = columns
+        .iter()
+        .flat_map(|x| x.starts.iter().copied())
+        .chain(std::iter::once(0))
+        .filter(|start| *start < num_rows)
+        .collect::<Vec<_>>();
+    boundaries.sort_unstable();
+    boundaries.dedup();
+
+    let mut intervals: Vec<Interval> = vec![];
+    for (i, &start) in boundaries.iter().enumerate() {
+        let end = boundaries.get(i + 1).copied().unwrap_or(num_rows);
+        let outcome = predicate.evaluate(&mut |path| {
+            let column = column_index(metadata, path)?;
+            let pages = columns.iter().find(|x| x.column == column).unwrap();
+            let page = pages
+                .starts
+                .partition_point(|page_start| *page_start <= start)
+                .checked_sub(1)
+                .ok_or_else(|| {
+                    Error::OutOfSpec("The first page of a column must start at row 0".to_string())
+                })?;
+            Ok(pages.summaries[page].clone())
+        })?;
+        if outcome.maybe_true {
+            push_interval(&mut intervals, Interval::new(start, end - start));
+        }
+    }
+    Ok(intervals)
+}
+
+/// Reads the page indexes of the `row_group`th row group of `metadata` and returns, for each
+/// of its columns, the [`FilteredPage`]s with the rows that may satisfy `predicate` (see
+/// [`prune_pages`]), to be read with [`super::IndexedPageReader`].
+/// # Error
+/// Errors if the row group has no page index, if the indexes can't be read or under the
+/// same conditions as [`prune_pages`].
+pub fn read_filtered_pages<R: Read + Seek>(
+    reader: &mut R,
+    metadata: &FileMetaData,
+    row_group: usize,
+    predicate: &Predicate,
+) -> Result<Vec<Vec<FilteredPage>>> {
+    let row_group = &metadata.row_groups[row_group];
+    let columns = row_group.columns();
+
+    let indexes = super::read_columns_indexes(reader, columns)?;
+    let locations = super::read_pages_locations(reader, columns)?;
+    if indexes.len() != columns.len() || locations.len() != columns.len() {
+        return Err(Error::InvalidParameter(
+            "The row group has no page index".to_string(),
+        ));
+    }
+
+    let intervals = prune_pages(metadata, row_group, predicate, &indexes, &locations)?;
+    locations
+        .iter()
+        .map(|locations| select_pages(&intervals, locations, row_group.num_rows()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path() -> Vec<String> {
+        vec!["a".to_string()]
+    }
+
+    fn int32(min: i32, max: i32, null_count: i64) -> Summary {
+        Summary {
+            physical_type: PhysicalType::Int32,
+            sort_order: SortOrder::Signed,
+            min: Some(Value::Int32(min)),
+            max: Some(Value::Int32(max)),
+            null_count: Some(null_count),
+            all_null: false,
+        }
+    }
+
+    fn may_match(predicate: &Predicate, summary: &Summary) -> Result<bool> {
+        Ok(predicate.evaluate(&mut |_| Ok(summary.clone()))?.maybe_true)
+    }
+
+    #[test]
+    fn comparisons() -> Result<()> {
+        let summary = int32(-5, 10, 0);
+        let cases = [
+            (Predicate::Eq(path(), Value::Int32(-5)), true),
+            (Predicate::Eq(path(), Value::Int32(11)), false),
+            (Predicate::Lt(path(), Value::Int32(-5)), false),
+            (Predicate::LtEq(path(), Value::Int32(-5)), true),
+            (Predicate::Gt(path(), Value::Int32(10)), false),
+            (Predicate::GtEq(path(), Value::Int32(10)), true),
+            (
+                Predicate::Between(path(), Value::Int32(11), Value::Int32(20)),
+                false,
+            ),
+            (
+                Predicate::Between(path(), Value::Int32(-20), Value::Int32(-5)),
+                true,
+            ),
+            (
+                Predicate::In(path(), vec![Value::Int32(-6), Value::Int32(11)]),
+                false,
+            ),
+            (
+                Predicate::In(path(), vec![Value::Int32(-6), Value::Int32(0)]),
+                true,
+            ),
+            (Predicate::IsNull(path()), false),
+            (!Predicate::IsNull(path()), true),
+        ];
+        for (predicate, expected) in cases {
+            assert_eq!(
+                may_match(&predicate, &summary)?,
+                expected,
+                "{:?}",
+                predicate
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn logical() -> Result<()> {
+        let constant = int32(1, 1, 0);
+        let eq = Predicate::Eq(path(), Value::Int32(1));
+        assert!(may_match(&eq, &constant)?);
+        assert!(!may_match(&!eq.clone(), &constant)?);
+        assert!(!may_match(&eq.clone().and(!eq.clone()), &constant)?);
+        assert!(may_match(&eq.clone().or(!eq.clone()), &constant)?);
+
+        // null values satisfy neither a comparison nor its negation
+        let with_nulls = int32(1, 1, 2);
+        assert!(!may_match(&!eq.clone(), &with_nulls)?);
+        assert!(may_match(
+            &(!eq.clone()).or(Predicate::IsNull(path())),
+            &with_nulls
+        )?);
+
+        let all_null = Summary {
+            min: None,
+            max: None,
+            all_null: true,
+            ..with_nulls
+        };
+        assert!(!may_match(&eq, &all_null)?);
+        assert!(!may_match(&!eq, &all_null)?);
+        assert!(may_match(&Predicate::IsNull(path()), &all_null)?);
+        Ok(())
+    }
+
+    #[test]
+    fn sort_orders() -> Result<()> {
+        // -1 is the largest unsigned integer
+        let unsigned = Summary {
+            sort_order: SortOrder::Unsigned,
+            ..int32(0, -1, 0)
+        };
+        assert!(may_match(
+            &Predicate::Gt(path(), Value::Int32(i32::MAX)),
+            &unsigned
+        )?);
+        assert!(!may_match(
+            &Predicate::Gt(path(), Value::Int32(i32::MAX)),
+            &int32(0, 10, 0)
+        )?);
+
+        let undefined = Summary {
+            sort_order: SortOrder::Undefined,
+            ..int32(0, 10, 0)
+        };
+        assert!(may_match(
+            &Predicate::Gt(path(), Value::Int32(20)),
+            &undefined
+        )?);
+
+        // signed (decimal) byte arrays of different lengths
+        let decimal = Summary {
+            physical_type: PhysicalType::ByteArray,
+            sort_order: SortOrder::Signed,
+            min: Some(Value::Bytes(vec![0xff, 0x00])), // -256
+            max: Some(Value::Bytes(vec![0x7f])),       // 127
+            null_count: Some(0),
+            all_null: false,
+        };
+        assert!(may_match(
+            &Predicate::Lt(path(), Value::Bytes(vec![0x80])), // -128
+            &decimal
+        )?);
+        assert!(!may_match(
+            &Predicate::Gt(path(), Value::Bytes(vec![0x00, 0x80])), // 128
+            &decimal
+        )?);
+        let unsigned = Summary {
+            sort_order: SortOrder::Unsigned,
+            min: Some(Value::Bytes(b"b".to_vec())),
+            max: Some(Value::Bytes(b"d".to_vec())),
+            ..decimal
+        };
+        assert!(!may_match(
+            &Predicate::Eq(path(), Value::Bytes(b"\xe9".to_vec())),
+            &unsigned
+        )?);
+        Ok(())
+    }
+
+    #[test]
+    fn nan() -> Result<()> {
+        let summary = Summary {
+            physical_type: PhysicalType::Double,
+            sort_order: SortOrder::Signed,
+            min: Some(Value::Double(0.0)),
+            max: Some(Value::Double(1.0)),
+            null_count: Some(0),
+            all_null: false,
+        };
+        assert!(may_match(
+            &Predicate::Eq(path(), Value::Double(f64::NAN)),
+            &summary
+        )?);
+        assert!(!may_match(
+            &Predicate::Eq(path(), Value::Double(2.0)),
+            &summary
+        )?);
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_literal() {
+        let predicate = Predicate::Eq(path(), Value::Int64(1));
+        assert!(may_match(&predicate, &int32(0, 1, 0)).is_err());
+    }
+}
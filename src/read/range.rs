@@ -0,0 +1,529 @@
+use std::io::{Read, Seek, SeekFrom};
+use std::sync::{Arc, Mutex};
+
+use crate::error::{Error, Result};
+
+use super::lru::Lru;
+
+/// A source of byte ranges of a file, e.g. a local file, an in-memory buffer or an object
+/// in object storage fetched with ranged requests.
+///
+/// Use [`RangeCursor`] to read from it with the [`Read`] and [`Seek`] based APIs of this
+/// crate, such as [`super::read_metadata`], [`super::get_page_iterator`] or
+/// [`super::read_columns_indexes`], and [`super::ReadPlan::read_ranges`] to read column
+/// chunks with few requests.
+pub trait RangeReader {
+    /// Returns the `length` bytes of the file starting at `start`.
+    /// # Error
+    /// Errors if the range is not within the file or can't be read.
+    fn get_range(&self, start: u64, length: usize) -> Result<Vec<u8>>;
+
+    /// Returns the size of the file in bytes.
+    fn size(&self) -> Result<u64>;
+}
+
+fn out_of_bounds(start: u64, length: usize, size: u64) -> Error {
+    Error::OutOfSpec(format!(
+        "The range of {} bytes starting at {} is not within the file of {} bytes",
+        length, start, size
+    ))
+}
+
+impl RangeReader for [u8] {
+    fn get_range(&self, start: u64, length: usize) -> Result<Vec<u8>> {
+        usize::try_from(start)
+            .ok()
+            .and_then(|start| self.get(start..start.checked_add(length)?))
+            .map(|range| range.to_vec())
+            .ok_or_else(|| out_of_bounds(start, length, self.len() as u64))
+    }
+
+    fn size(&self) -> Result<u64> {
+        Ok(self.len() as u64)
+    }
+}
+
+impl RangeReader for Vec<u8> {
+    fn get_range(&self, start: u64, length: usize) -> Result<Vec<u8>> {
+        self.as_slice().get_range(start, length)
+    }
+
+    fn size(&self) -> Result<u64> {
+        self.as_slice().size()
+    }
+}
+
+impl RangeReader for std::fs::File {
+    fn get_range(&self, start: u64, length: usize) -> Result<Vec<u8>> {
+        let mut data = vec![];
+        data.try_reserve(length)?;
+        data.resize(length, 0);
+
+        #[cfg(unix)]
+        std::os::unix::fs::FileExt::read_exact_at(self, &mut data, start)?;
+        #[cfg(not(unix))]
+        {
+            // `&File` implements `Read` and `Seek`, sharing the position of the file
+            let mut file = self;
+            file.seek(SeekFrom::Start(start))?;
+            file.read_exact(&mut data)?;
+        }
+        Ok(data)
+    }
+
+    fn size(&self) -> Result<u64> {
+        Ok(self.metadata()?.len())
+    }
+}
+
+impl<R: RangeReader + ?Sized> RangeReader for &R {
+    fn get_range(&self, start: u64, length: usize) -> Result<Vec<u8>> {
+        (**self).get_range(start, length)
+    }
+
+    fn size(&self) -> Result<u64> {
+        (**self).size()
+    }
+}
+
+impl<R: RangeReader + ?Sized> RangeReader for Box<R> {
+    fn get_range(&self, start: u64, length: usize) -> Result<Vec<u8>> {
+        (**self).get_range(start, length)
+    }
+
+    fn size(&self) -> Result<u64> {
+        (**self).size()
+    }
+}
+
+impl<R: RangeReader + ?Sized> RangeReader for Arc<R> {
+    fn get_range(&self, start: u64, length: usize) -> Result<Vec<u8>> {
+        (**self).get_range(start, length)
+    }
+
+    fn size(&self) -> Result<u64> {
+        (**self).size()
+    }
+}
+
+/// The default maximum number of bytes cached by a [`CachingRangeReader`]
+const DEFAULT_CACHE_SIZE: usize = 64 * 1024 * 1024;
+
+/// The default number of bytes read ahead by a [`RangeCursor`]
+const DEFAULT_READ_AHEAD: usize = 8 * 1024;
+
+#[derive(Debug, Default)]
+struct Cache {
+    size: Option<u64>,
+    blocks: Lru<u64, Arc<[u8]>>,
+    // the number of bytes of `blocks`
+    cached: usize,
+}
+
+/// A [`RangeReader`] that caches the bytes it reads from another [`RangeReader`].
+///
+/// The file is divided in blocks of `block_size` bytes, and ranges are read from the inner
+/// reader as whole blocks, with a single request for consecutive blocks that are not yet
+/// cached. Besides avoiding repeated requests, this makes small reads, such as those of page
+/// headers, be served from memory. Up to 64 MiB are cached by default (see
+/// [`Self::with_max_size`]), evicting the least recently used blocks.
+///
+/// The inner reader is not called while the cache is locked, so that threads sharing this
+/// reader read concurrently; concurrent misses of the same block may read it more than once.
+#[derive(Debug)]
+pub struct CachingRangeReader<R: RangeReader> {
+    inner: R,
+    block_size: u64,
+    max_size: usize,
+    cache: Mutex<Cache>,
+}
+
+impl<R: RangeReader> CachingRangeReader<R> {
+    /// Returns a new [`CachingRangeReader`] reading blocks of `block_size` bytes from `inner`.
+    /// # Panics
+    /// Panics if `block_size` is zero.
+    pub fn new(inner: R, block_size: usize) -> Self {
+        assert!(block_size > 0, "The block size must be positive");
+        Self {
+            inner,
+            block_size: block_size as u64,
+            max_size: DEFAULT_CACHE_SIZE,
+            cache: Default::default(),
+        }
+    }
+
+    /// Sets the maximum number of bytes cached. The blocks of a single [`RangeReader::get_range`]
+    /// are returned even when they exceed it.
+    pub fn with_max_size(mut self, max_size: usize) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// Consumes this reader, returning the inner reader
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Reads the blocks `first + i` that are `None` in `blocks` from the inner reader, with
+    /// one request per run of consecutive missing blocks.
+    fn fetch(&self, blocks: &mut [Option<Arc<[u8]>>], first: u64, size: u64) -> Result<()> {
+        let mut i = 0;
+        while i < blocks.len() {
+            if blocks[i].is_some() {
+                i += 1;
+                continue;
+            }
+            let mut end = i;
+            while end + 1 < blocks.len() && blocks[end + 1].is_none() {
+                end += 1;
+            }
+
+            let start = (first + i as u64) * self.block_size;
+            let length = (((first + end as u64 + 1) * self.block_size).min(size) - start) as usize;
+            let data = self.inner.get_range(start, length)?;
+            if data.len() != length {
+                return Err(Error::OutOfSpec(format!(
+                    "Requested {} bytes at {} but received {}",
+                    length,
+                    start,
+                    data.len()
+                )));
+            }
+            for (j, chunk) in data.chunks(self.block_size as usize).enumerate() {
+                blocks[i + j] = Some(chunk.into());
+            }
+            i = end + 1;
+        }
+        Ok(())
+    }
+}
+
+impl<R: RangeReader> RangeReader for CachingRangeReader<R> {
+    fn get_range(&self, start: u64, length: usize) -> Result<Vec<u8>> {
+        let size = self.size()?;
+        let end = start
+            .checked_add(length as u64)
+            .filter(|end| *end <= size)
+            .ok_or_else(|| out_of_bounds(start, length, size))?;
+        if length == 0 {
+            return Ok(vec![]);
+        }
+
+        let first = start / self.block_size;
+        let last = (end - 1) / self.block_size;
+        let mut blocks = {
+            let mut cache = self.cache.lock().unwrap();
+            (first..=last)
+                .map(|block| cache.blocks.get(&block).cloned())
+                .collect::<Vec<_>>()
+        };
+        let missing = blocks.iter().any(|block| block.is_none());
+        if missing {
+            self.fetch(&mut blocks, first, size)?;
+
+            let mut cache = self.cache.lock().unwrap();
+            for (block, bytes) in (first..).zip(&blocks) {
+                if !cache.blocks.contains_key(&block) {
+                    let bytes = bytes.clone().unwrap();
+                    cache.cached += bytes.len();
+                    cache.blocks.insert(block, bytes);
+                }
+            }
+            while cache.cached > self.max_size {
+                let (_, bytes) = cache.blocks.pop_oldest().unwrap();
+                cache.cached -= bytes.len();
+            }
+        }
+
+        let mut data = Vec::with_capacity(length);
+        for (block, bytes) in (first..).zip(blocks) {
+            let bytes = bytes.unwrap();
+            let block_start = block * self.block_size;
+            let from = start.saturating_sub(block_start) as usize;
+            let to = ((end - block_start) as usize).min(bytes.len());
+            data.extend_from_slice(&bytes[from..to]);
+        }
+        Ok(data)
+    }
+
+    fn size(&self) -> Result<u64> {
+        if let Some(size) = self.cache.lock().unwrap().size {
+            return Ok(size);
+        }
+        let size = self.inner.size()?;
+        self.cache.lock().unwrap().size = Some(size);
+        Ok(size)
+    }
+}
+
+/// A [`Read`] and [`Seek`] over a [`RangeReader`], so that it can be used by the readers of
+/// this crate.
+///
+/// Like [`std::io::BufReader`], it reads at least 8 KiB (see [`Self::with_read_ahead`]) per
+/// call to [`RangeReader::get_range`] and serves smaller reads, such as those of page
+/// headers, from this buffer; larger reads are not buffered. The size of the file is
+/// requested once. Wrap remote readers in a [`CachingRangeReader`] to also cache the bytes
+/// across cursors.
+#[derive(Debug, Clone)]
+pub struct RangeCursor<R: RangeReader> {
+    reader: R,
+    position: u64,
+    size: Option<u64>,
+    read_ahead: usize,
+    // the bytes of the file starting at `buffer_start`
+    buffer: Vec<u8>,
+    buffer_start: u64,
+}
+
+impl<R: RangeReader> RangeCursor<R> {
+    /// Returns a new [`RangeCursor`] at the start of `reader`
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            position: 0,
+            size: None,
+            read_ahead: DEFAULT_READ_AHEAD,
+            buffer: vec![],
+            buffer_start: 0,
+        }
+    }
+
+    /// Sets the minimum number of bytes requested per read. Zero disables the buffer.
+    pub fn with_read_ahead(mut self, read_ahead: usize) -> Self {
+        self.read_ahead = read_ahead;
+        self
+    }
+
+    /// Consumes this cursor, returning the [`RangeReader`]
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    fn size(&mut self) -> std::io::Result<u64> {
+        if let Some(size) = self.size {
+            return Ok(size);
+        }
+        let size = self.reader.size().map_err(to_io_error)?;
+        self.size = Some(size);
+        Ok(size)
+    }
+
+    /// Returns the `length` bytes of the file starting at `start`
+    fn get_range(&self, start: u64, length: usize) -> std::io::Result<Vec<u8>> {
+        let data = self.reader.get_range(start, length).map_err(to_io_error)?;
+        if data.len() != length {
+            return Err(to_io_error(Error::OutOfSpec(format!(
+                "Requested {} bytes at {} but received {}",
+                length,
+                start,
+                data.len()
+            ))));
+        }
+        Ok(data)
+    }
+
+    /// Returns the buffered bytes from the current position
+    fn buffered(&self) -> &[u8] {
+        self.position
+            .checked_sub(self.buffer_start)
+            .and_then(|offset| self.buffer.get(usize::try_from(offset).ok()?..))
+            .unwrap_or_default()
+    }
+}
+
+fn to_io_error(error: Error) -> std::io::Error {
+    std::io::Error::other(error)
+}
+
+impl<R: RangeReader> Read for RangeCursor<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let buffered = self.buffered();
+        if !buffered.is_empty() {
+            let length = buffered.len().min(buf.len());
+            buf[..length].copy_from_slice(&buffered[..length]);
+            self.position += length as u64;
+            return Ok(length);
+        }
+
+        let remaining = self.size()?.saturating_sub(self.position);
+        let length = remaining.min(buf.len() as u64) as usize;
+        if length == 0 {
+            return Ok(0);
+        }
+        if length >= self.read_ahead {
+            let data = self.get_range(self.position, length)?;
+            buf[..length].copy_from_slice(&data);
+        } else {
+            let read_ahead = remaining.min(self.read_ahead as u64) as usize;
+            self.buffer = self.get_range(self.position, read_ahead)?;
+            self.buffer_start = self.position;
+            buf[..length].copy_from_slice(&self.buffer[..length]);
+        }
+        self.position += length as u64;
+        Ok(length)
+    }
+}
+
+impl<R: RangeReader> Seek for RangeCursor<R> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        let position = match pos {
+            SeekFrom::Start(position) => Some(position),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+            SeekFrom::End(offset) => self.size()?.checked_add_signed(offset),
+        };
+        self.position = position.ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "Invalid seek to a negative or overflowing position",
+            )
+        })?;
+        Ok(self.position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    /// An in-memory reader counting its requests
+    struct Counting {
+        data: Vec<u8>,
+        requests: AtomicUsize,
+    }
+
+    impl RangeReader for Counting {
+        fn get_range(&self, start: u64, length: usize) -> Result<Vec<u8>> {
+            self.requests.fetch_add(1, Ordering::Relaxed);
+            self.data.get_range(start, length)
+        }
+
+        fn size(&self) -> Result<u64> {
+            self.data.size()
+        }
+    }
+
+    #[test]
+    fn in_memory() -> Result<()> {
+        let data = (0..10u8).collect::<Vec<_>>();
+        assert_eq!(data.get_range(2, 3)?, vec![2, 3, 4]);
+        assert_eq!(data.get_range(10, 0)?, Vec::<u8>::new());
+        assert!(data.get_range(8, 3).is_err());
+        assert!(data.get_range(u64::MAX, 1).is_err());
+        assert_eq!(data.size()?, 10);
+        Ok(())
+    }
+
+    #[test]
+    fn caching() -> Result<()> {
+        let data = (0..100u8).collect::<Vec<_>>();
+        let reader = CachingRangeReader::new(
+            Counting {
+                data: data.clone(),
+                requests: AtomicUsize::new(0),
+            },
+            16,
+        );
+        let requests =
+            |reader: &CachingRangeReader<Counting>| reader.inner.requests.load(Ordering::Relaxed);
+
+        assert_eq!(reader.get_range(10, 10)?, data[10..20]);
+        assert_eq!(requests(&reader), 1);
+        // within the cached blocks [0, 32)
+        assert_eq!(reader.get_range(0, 32)?, data[..32]);
+        assert_eq!(requests(&reader), 1);
+        // the missing blocks [32, 64) are fetched in a single request
+        assert_eq!(reader.get_range(30, 30)?, data[30..60]);
+        assert_eq!(requests(&reader), 2);
+        // the last block is shorter
+        assert_eq!(reader.get_range(90, 10)?, data[90..]);
+        assert!(reader.get_range(90, 11).is_err());
+        assert_eq!(requests(&reader), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn caching_evicts() -> Result<()> {
+        let data = (0..100u8).collect::<Vec<_>>();
+        let reader = CachingRangeReader::new(
+            Counting {
+                data: data.clone(),
+                requests: AtomicUsize::new(0),
+            },
+            16,
+        )
+        .with_max_size(32);
+        let requests =
+            |reader: &CachingRangeReader<Counting>| reader.inner.requests.load(Ordering::Relaxed);
+
+        // ranges larger than the cache are still returned
+        assert_eq!(reader.get_range(0, 48)?, data[..48]);
+        assert_eq!(requests(&reader), 1);
+        // of which only the last 2 blocks are kept
+        assert_eq!(reader.get_range(16, 32)?, data[16..48]);
+        assert_eq!(requests(&reader), 1);
+        assert_eq!(reader.get_range(0, 1)?, data[..1]);
+        assert_eq!(requests(&reader), 2);
+        // block 1 was used less recently than block 2
+        assert_eq!(reader.get_range(32, 1)?, data[32..33]);
+        assert_eq!(reader.get_range(16, 1)?, data[16..17]);
+        assert_eq!(requests(&reader), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn cursor() -> Result<()> {
+        let data = (0..10u8).collect::<Vec<_>>();
+        let mut cursor = RangeCursor::new(&data);
+        cursor.seek(SeekFrom::End(-3))?;
+        let mut buf = vec![];
+        cursor.read_to_end(&mut buf)?;
+        assert_eq!(buf, vec![7, 8, 9]);
+
+        cursor.seek(SeekFrom::Start(2))?;
+        let mut buf = [0; 2];
+        cursor.read_exact(&mut buf)?;
+        assert_eq!(buf, [2, 3]);
+        assert!(cursor.seek(SeekFrom::Current(-5)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn cursor_reads_ahead() -> Result<()> {
+        let data = (0..100u8).collect::<Vec<_>>();
+        let reader = Counting {
+            data: data.clone(),
+            requests: AtomicUsize::new(0),
+        };
+        let requests = || reader.requests.load(Ordering::Relaxed);
+
+        // small reads are served from a buffer of 10 bytes
+        let mut cursor = RangeCursor::new(&reader).with_read_ahead(10);
+        let mut buf = [0; 4];
+        cursor.read_exact(&mut buf)?;
+        cursor.read_exact(&mut buf)?;
+        assert_eq!(buf, [4, 5, 6, 7]);
+        assert_eq!(requests(), 1);
+        // reads across the end of the buffer fill it again
+        cursor.read_exact(&mut buf)?;
+        assert_eq!(buf, [8, 9, 10, 11]);
+        assert_eq!(requests(), 2);
+        // and seeks within it do not
+        cursor.seek(SeekFrom::Current(-2))?;
+        cursor.read_exact(&mut buf)?;
+        assert_eq!(buf, [10, 11, 12, 13]);
+        assert_eq!(requests(), 2);
+
+        // large reads are not buffered
+        let mut buf = vec![0; 20];
+        cursor.read_exact(&mut buf)?;
+        assert_eq!(buf, data[14..34]);
+        assert_eq!(requests(), 3);
+        cursor.seek(SeekFrom::End(-5))?;
+        let mut buf = vec![];
+        cursor.read_to_end(&mut buf)?;
+        assert_eq!(buf, data[95..]);
+        Ok(())
+    }
+}
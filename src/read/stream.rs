@@ -2,8 +2,10 @@ use std::io::SeekFrom;
 
 use futures::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt};
 
-use super::super::{metadata::FileMetaData, DEFAULT_FOOTER_READ_SIZE, FOOTER_SIZE, PARQUET_MAGIC};
-use super::metadata::{deserialize_metadata, metadata_len};
+use super::super::{metadata::FileMetaData, DEFAULT_FOOTER_READ_SIZE, FOOTER_SIZE};
+use super::metadata::{deserialize_metadata, encrypted_footer_error, footer_magic, metadata_len};
+#[cfg(feature = "encryption")]
+use crate::encryption::{decrypt_metadata, FileDecryptionProperties};
 use crate::error::{Error, Result};
 use crate::HEADER_SIZE;
 
@@ -22,10 +24,11 @@ async fn stream_len(
     Ok(len)
 }
 
-/// Asynchronously reads the files' metadata
-pub async fn read_metadata<R: AsyncRead + AsyncSeek + Send + std::marker::Unpin>(
+/// Asynchronously reads the footer of the file, returning the bytes of its metadata and
+/// whether the footer is encrypted.
+async fn read_footer<R: AsyncRead + AsyncSeek + Send + std::marker::Unpin>(
     reader: &mut R,
-) -> Result<FileMetaData> {
+) -> Result<(Vec<u8>, bool)> {
     let file_size = stream_len(reader).await?;
 
     if file_size < HEADER_SIZE + FOOTER_SIZE {
@@ -48,11 +51,7 @@ pub async fn read_metadata<R: AsyncRead + AsyncSeek + Send + std::marker::Unpin>
         .await?;
 
     // check this is indeed a parquet file
-    if buffer[default_end_len - 4..] != PARQUET_MAGIC {
-        return Err(Error::OutOfSpec(
-            "Invalid Parquet file. Corrupt footer".to_string(),
-        ));
-    }
+    let encrypted = footer_magic(&buffer[default_end_len - 4..])?;
 
     let metadata_len = metadata_len(&buffer, default_end_len);
     let metadata_len: u64 = metadata_len.try_into()?;
@@ -64,10 +63,10 @@ pub async fn read_metadata<R: AsyncRead + AsyncSeek + Send + std::marker::Unpin>
         ));
     }
 
-    let reader = if (footer_len as usize) < buffer.len() {
+    if (footer_len as usize) < buffer.len() {
         // the whole metadata is in the bytes we already read
         let remaining = buffer.len() - footer_len as usize;
-        &buffer[remaining..]
+        buffer.drain(..remaining);
     } else {
         // the end of file read by default is not long enough, read again including the metadata.
         reader.seek(SeekFrom::End(-(footer_len as i64))).await?;
@@ -78,12 +77,37 @@ pub async fn read_metadata<R: AsyncRead + AsyncSeek + Send + std::marker::Unpin>
             .take(footer_len as u64)
             .read_to_end(&mut buffer)
             .await?;
+    }
+    buffer.truncate(metadata_len as usize);
+
+    Ok((buffer, encrypted))
+}
 
-        &buffer
-    };
+/// Asynchronously reads the files' metadata
+/// # Errors
+/// Errors if the footer of the file is encrypted.
+pub async fn read_metadata<R: AsyncRead + AsyncSeek + Send + std::marker::Unpin>(
+    reader: &mut R,
+) -> Result<FileMetaData> {
+    let (metadata, encrypted) = read_footer(reader).await?;
+    if encrypted {
+        return Err(encrypted_footer_error());
+    }
 
     // a highly nested but sparse struct could result in many allocations
-    let max_size = reader.len() * 2 + 1024;
+    let max_size = metadata.len() * 2 + 1024;
 
-    deserialize_metadata(reader, max_size)
+    deserialize_metadata(metadata.as_slice(), max_size)
+}
+
+/// Asynchronously reads the metadata of an encrypted file, decrypting it with the keys of
+/// `properties`.
+/// See [`super::read_metadata_with_decryption`].
+#[cfg(feature = "encryption")]
+pub async fn read_metadata_with_decryption<R: AsyncRead + AsyncSeek + Send + std::marker::Unpin>(
+    reader: &mut R,
+    properties: &FileDecryptionProperties,
+) -> Result<FileMetaData> {
+    let (metadata, encrypted) = read_footer(reader).await?;
+    decrypt_metadata(&metadata, encrypted, properties)
 }
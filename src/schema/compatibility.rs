@@ -0,0 +1,625 @@
+//! Comparison of schemas, e.g. to decide whether files of a new schema can be appended to a
+//! dataset of an existing one.
+//!
+//! Fields are matched by their field id when both have one and by their name otherwise, so that
+//! renaming a field with an id is not seen as removing and adding it.
+//!
+//! # Example
+//!
+//! ```rust
+//! use parquet2::metadata::SchemaDescriptor;
+//! use parquet2::schema::compatibility::{compare, SchemaChange};
+//! use parquet2::schema::Repetition;
+//!
+//! let old = SchemaDescriptor::try_from_message(
+//!     "message schema { required int32 a = 1; required float b; }",
+//! )
+//! .unwrap();
+//! let new = SchemaDescriptor::try_from_message(
+//!     "message schema { required int64 c = 1; optional float b; optional binary d; }",
+//! )
+//! .unwrap();
+//!
+//! let changes = compare(&old, &new);
+//! assert_eq!(changes.len(), 4);
+//! assert!(changes.iter().all(|change| change.is_compatible()));
+//! assert_eq!(
+//!     changes[2],
+//!     SchemaChange::RepetitionChanged {
+//!         path: vec!["b".to_string()],
+//!         from: Repetition::Required,
+//!         to: Repetition::Optional,
+//!     }
+//! );
+//! ```
+use crate::metadata::SchemaDescriptor;
+
+use super::types::{
+    GroupConvertedType, GroupLogicalType, IntegerType, ParquetType, PhysicalType,
+    PrimitiveConvertedType, PrimitiveLogicalType, PrimitiveType,
+};
+use super::Repetition;
+
+/// A difference between two schemas. Paths are those of the new schema, except for removed
+/// fields.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaChange {
+    /// A field only in the new schema
+    Added {
+        /// The path of the field
+        path: Vec<String>,
+        /// The repetition of the field
+        repetition: Repetition,
+    },
+    /// A field only in the old schema
+    Removed {
+        /// The path of the field
+        path: Vec<String>,
+        /// The repetition of the field
+        repetition: Repetition,
+    },
+    /// A field whose name changed, matched by its field id
+    Renamed {
+        /// The id of the field
+        id: i32,
+        /// The path of the field in the old schema
+        from: Vec<String>,
+        /// The path of the field in the new schema
+        to: Vec<String>,
+    },
+    /// A field whose repetition changed
+    RepetitionChanged {
+        /// The path of the field
+        path: Vec<String>,
+        /// The repetition in the old schema
+        from: Repetition,
+        /// The repetition in the new schema
+        to: Repetition,
+    },
+    /// A primitive field whose physical type changed
+    PhysicalTypeChanged {
+        /// The path of the field
+        path: Vec<String>,
+        /// The physical type in the old schema
+        from: PhysicalType,
+        /// The physical type in the new schema
+        to: PhysicalType,
+    },
+    /// A primitive field whose logical type changed
+    LogicalTypeChanged {
+        /// The path of the field
+        path: Vec<String>,
+        /// The logical type in the old schema
+        from: Option<PrimitiveLogicalType>,
+        /// The logical type in the new schema
+        to: Option<PrimitiveLogicalType>,
+        /// The converted type in the old schema
+        converted_type: Option<PrimitiveConvertedType>,
+    },
+    /// A primitive field whose converted type changed
+    ConvertedTypeChanged {
+        /// The path of the field
+        path: Vec<String>,
+        /// The converted type in the old schema
+        from: Option<PrimitiveConvertedType>,
+        /// The converted type in the new schema
+        to: Option<PrimitiveConvertedType>,
+    },
+    /// A group whose logical type changed
+    GroupLogicalTypeChanged {
+        /// The path of the group
+        path: Vec<String>,
+        /// The logical type in the old schema
+        from: Option<GroupLogicalType>,
+        /// The logical type in the new schema
+        to: Option<GroupLogicalType>,
+        /// The converted type in the old schema
+        converted_type: Option<GroupConvertedType>,
+    },
+    /// A group whose converted type changed
+    GroupConvertedTypeChanged {
+        /// The path of the group
+        path: Vec<String>,
+        /// The converted type in the old schema
+        from: Option<GroupConvertedType>,
+        /// The converted type in the new schema
+        to: Option<GroupConvertedType>,
+    },
+    /// A primitive field that became a group or vice-versa
+    NestingChanged {
+        /// The path of the field
+        path: Vec<String>,
+    },
+}
+
+impl SchemaChange {
+    /// Returns whether this change is compatible, i.e. whether files written with the old schema
+    /// can be read with the new schema. This is the case for:
+    /// * added fields that are not required and removed or renamed fields;
+    /// * repetitions widened from required to optional;
+    /// * physical types promoted from `INT32` to `INT64` and from `FLOAT` to `DOUBLE`;
+    /// * logical and converted types of integers widened with the same signedness and of
+    ///   decimals widened with the same scale;
+    /// * logical types added to a field whose converted type is the one they imply, e.g. `STRING`
+    ///   to a field annotated with `UTF8`.
+    pub fn is_compatible(&self) -> bool {
+        match self {
+            Self::Added { repetition, .. } => *repetition != Repetition::Required,
+            Self::Removed { .. } | Self::Renamed { .. } => true,
+            Self::RepetitionChanged { from, to, .. } => {
+                *from == Repetition::Required && *to == Repetition::Optional
+            }
+            Self::PhysicalTypeChanged { from, to, .. } => matches!(
+                (from, to),
+                (PhysicalType::Int32, PhysicalType::Int64)
+                    | (PhysicalType::Float, PhysicalType::Double)
+            ),
+            Self::LogicalTypeChanged {
+                from,
+                to,
+                converted_type,
+                ..
+            } => match (from, to) {
+                (None, Some(to)) => {
+                    converted_type.is_some()
+                        && PrimitiveConvertedType::from_logical(*to) == *converted_type
+                }
+                (
+                    Some(PrimitiveLogicalType::Decimal(from_precision, from_scale)),
+                    Some(PrimitiveLogicalType::Decimal(to_precision, to_scale)),
+                ) => is_decimal_widened((*from_precision, *from_scale), (*to_precision, *to_scale)),
+                (
+                    Some(PrimitiveLogicalType::Integer(from)),
+                    Some(PrimitiveLogicalType::Integer(to)),
+                ) => {
+                    let from: (usize, bool) = (*from).into();
+                    let to: (usize, bool) = (*to).into();
+                    is_integer_widened(from, to)
+                }
+                _ => false,
+            },
+            Self::ConvertedTypeChanged { from, to, .. } => match (from, to) {
+                (
+                    Some(PrimitiveConvertedType::Decimal(from_precision, from_scale)),
+                    Some(PrimitiveConvertedType::Decimal(to_precision, to_scale)),
+                ) => is_decimal_widened((*from_precision, *from_scale), (*to_precision, *to_scale)),
+                (Some(from), Some(to)) => {
+                    match (converted_integer(*from), converted_integer(*to)) {
+                        (Some(from), Some(to)) => is_integer_widened(from.into(), to.into()),
+                        _ => false,
+                    }
+                }
+                _ => false,
+            },
+            Self::GroupLogicalTypeChanged {
+                from,
+                to,
+                converted_type,
+                ..
+            } => matches!(
+                (from, to, converted_type),
+                (
+                    None,
+                    Some(GroupLogicalType::List),
+                    Some(GroupConvertedType::List)
+                ) | (
+                    None,
+                    Some(GroupLogicalType::Map),
+                    Some(GroupConvertedType::Map)
+                )
+            ),
+            Self::GroupConvertedTypeChanged { .. } | Self::NestingChanged { .. } => false,
+        }
+    }
+}
+
+fn is_decimal_widened(from: (usize, usize), to: (usize, usize)) -> bool {
+    from.1 == to.1 && from.0 <= to.0
+}
+
+fn is_integer_widened(from: (usize, bool), to: (usize, bool)) -> bool {
+    from.1 == to.1 && from.0 <= to.0
+}
+
+fn converted_integer(converted_type: PrimitiveConvertedType) -> Option<IntegerType> {
+    Some(match converted_type {
+        PrimitiveConvertedType::Int8 => IntegerType::Int8,
+        PrimitiveConvertedType::Int16 => IntegerType::Int16,
+        PrimitiveConvertedType::Int32 => IntegerType::Int32,
+        PrimitiveConvertedType::Int64 => IntegerType::Int64,
+        PrimitiveConvertedType::Uint8 => IntegerType::UInt8,
+        PrimitiveConvertedType::Uint16 => IntegerType::UInt16,
+        PrimitiveConvertedType::Uint32 => IntegerType::UInt32,
+        PrimitiveConvertedType::Uint64 => IntegerType::UInt64,
+        _ => return None,
+    })
+}
+
+/// Returns the differences from the schema `old` to the schema `new`.
+pub fn compare(old: &SchemaDescriptor, new: &SchemaDescriptor) -> Vec<SchemaChange> {
+    let mut changes = vec![];
+    compare_fields(old.fields(), new.fields(), &[], &[], &mut changes);
+    changes
+}
+
+/// Returns whether files written with the schema `old` can be read with the schema `new`, i.e.
+/// whether all [`SchemaChange`]s between them are compatible.
+pub fn is_compatible(old: &SchemaDescriptor, new: &SchemaDescriptor) -> bool {
+    compare(old, new).iter().all(SchemaChange::is_compatible)
+}
+
+fn child_path(path: &[String], field: &ParquetType) -> Vec<String> {
+    let mut path = path.to_vec();
+    path.push(field.name().to_string());
+    path
+}
+
+/// Returns whether `old` and `new` are the same field
+fn is_match(old: &ParquetType, new: &ParquetType) -> bool {
+    match (old.get_field_info().id, new.get_field_info().id) {
+        (Some(old_id), Some(new_id)) => old_id == new_id,
+        _ => old.name() == new.name(),
+    }
+}
+
+fn compare_fields(
+    old: &[ParquetType],
+    new: &[ParquetType],
+    old_path: &[String],
+    new_path: &[String],
+    changes: &mut Vec<SchemaChange>,
+) {
+    let mut matched = vec![false; old.len()];
+    for new_field in new {
+        let position = old
+            .iter()
+            .zip(matched.iter())
+            .position(|(old_field, matched)| !matched && is_match(old_field, new_field));
+        match position {
+            Some(position) => {
+                matched[position] = true;
+                compare_field(&old[position], new_field, old_path, new_path, changes);
+            }
+            None => changes.push(SchemaChange::Added {
+                path: child_path(new_path, new_field),
+                repetition: new_field.get_field_info().repetition,
+            }),
+        }
+    }
+    for (old_field, _) in old.iter().zip(matched).filter(|(_, matched)| !matched) {
+        changes.push(SchemaChange::Removed {
+            path: child_path(old_path, old_field),
+            repetition: old_field.get_field_info().repetition,
+        })
+    }
+}
+
+fn compare_field(
+    old: &ParquetType,
+    new: &ParquetType,
+    old_path: &[String],
+    new_path: &[String],
+    changes: &mut Vec<SchemaChange>,
+) {
+    let old_path = child_path(old_path, old);
+    let path = child_path(new_path, new);
+
+    if old.name() != new.name() {
+        changes.push(SchemaChange::Renamed {
+            // fields are only matched by different names via their id
+            id: new.get_field_info().id.unwrap_or_default(),
+            from: old_path.clone(),
+            to: path.clone(),
+        })
+    }
+
+    let (from, to) = (
+        old.get_field_info().repetition,
+        new.get_field_info().repetition,
+    );
+    if from != to {
+        changes.push(SchemaChange::RepetitionChanged {
+            path: path.clone(),
+            from,
+            to,
+        })
+    }
+
+    match (old, new) {
+        (ParquetType::PrimitiveType(old), ParquetType::PrimitiveType(new)) => {
+            compare_primitive(old, new, path, changes)
+        }
+        (
+            ParquetType::GroupType {
+                logical_type: old_logical_type,
+                converted_type: old_converted_type,
+                fields: old_fields,
+                ..
+            },
+            ParquetType::GroupType {
+                logical_type,
+                converted_type,
+                fields,
+                ..
+            },
+        ) => {
+            if old_logical_type != logical_type {
+                changes.push(SchemaChange::GroupLogicalTypeChanged {
+                    path: path.clone(),
+                    from: *old_logical_type,
+                    to: *logical_type,
+                    converted_type: *old_converted_type,
+                })
+            }
+            if old_converted_type != converted_type {
+                changes.push(SchemaChange::GroupConvertedTypeChanged {
+                    path: path.clone(),
+                    from: *old_converted_type,
+                    to: *converted_type,
+                })
+            }
+            compare_fields(old_fields, fields, &old_path, &path, changes)
+        }
+        _ => changes.push(SchemaChange::NestingChanged { path }),
+    }
+}
+
+fn compare_primitive(
+    old: &PrimitiveType,
+    new: &PrimitiveType,
+    path: Vec<String>,
+    changes: &mut Vec<SchemaChange>,
+) {
+    if old.physical_type != new.physical_type {
+        changes.push(SchemaChange::PhysicalTypeChanged {
+            path: path.clone(),
+            from: old.physical_type,
+            to: new.physical_type,
+        })
+    }
+    if old.logical_type != new.logical_type {
+        changes.push(SchemaChange::LogicalTypeChanged {
+            path: path.clone(),
+            from: old.logical_type,
+            to: new.logical_type,
+            converted_type: old.converted_type,
+        })
+    }
+    if old.converted_type != new.converted_type {
+        changes.push(SchemaChange::ConvertedTypeChanged {
+            path,
+            from: old.converted_type,
+            to: new.converted_type,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema(message: &str) -> SchemaDescriptor {
+        SchemaDescriptor::try_from_message(message).unwrap()
+    }
+
+    fn path(path: &[&str]) -> Vec<String> {
+        path.iter().map(|x| x.to_string()).collect()
+    }
+
+    #[test]
+    fn equal() {
+        let message = "message schema {
+            required int32 a (INTEGER(8,true));
+            optional group b (LIST) {
+                repeated group list {
+                    optional binary element (STRING);
+                }
+            }
+        }";
+        assert_eq!(compare(&schema(message), &schema(message)), vec![]);
+        assert!(is_compatible(&schema(message), &schema(message)));
+    }
+
+    #[test]
+    fn added_and_removed() {
+        let old =
+            schema("message schema { required int32 a; optional group b { required int32 c; } }");
+        let new =
+            schema("message schema { optional group b { required int32 d; } optional int32 e; }");
+        let changes = compare(&old, &new);
+        assert_eq!(
+            changes,
+            vec![
+                SchemaChange::Added {
+                    path: path(&["b", "d"]),
+                    repetition: Repetition::Required
+                },
+                SchemaChange::Removed {
+                    path: path(&["b", "c"]),
+                    repetition: Repetition::Required
+                },
+                SchemaChange::Added {
+                    path: path(&["e"]),
+                    repetition: Repetition::Optional
+                },
+                SchemaChange::Removed {
+                    path: path(&["a"]),
+                    repetition: Repetition::Required
+                },
+            ]
+        );
+        let compatible = changes
+            .iter()
+            .map(|x| x.is_compatible())
+            .collect::<Vec<_>>();
+        assert_eq!(compatible, vec![false, true, true, true]);
+    }
+
+    #[test]
+    fn renamed() {
+        let old = schema(
+            "message schema {
+                required int32 a = 1;
+                optional group b = 2 { required int32 c = 3; }
+            }",
+        );
+        let new = schema(
+            "message schema {
+                optional group x = 2 { required int64 y = 3; }
+                required int32 a = 4;
+            }",
+        );
+        let changes = compare(&old, &new);
+        assert_eq!(
+            changes,
+            vec![
+                SchemaChange::Renamed {
+                    id: 2,
+                    from: path(&["b"]),
+                    to: path(&["x"])
+                },
+                SchemaChange::Renamed {
+                    id: 3,
+                    from: path(&["b", "c"]),
+                    to: path(&["x", "y"])
+                },
+                SchemaChange::PhysicalTypeChanged {
+                    path: path(&["x", "y"]),
+                    from: PhysicalType::Int32,
+                    to: PhysicalType::Int64
+                },
+                // a field with the same name but another id is another field
+                SchemaChange::Added {
+                    path: path(&["a"]),
+                    repetition: Repetition::Required
+                },
+                SchemaChange::Removed {
+                    path: path(&["a"]),
+                    repetition: Repetition::Required
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn repetition() {
+        let old =
+            schema("message schema { required int32 a; optional int32 b; optional int32 c; }");
+        let new =
+            schema("message schema { optional int32 a; required int32 b; repeated int32 c; }");
+        let compatible = compare(&old, &new)
+            .iter()
+            .map(|x| x.is_compatible())
+            .collect::<Vec<_>>();
+        assert_eq!(compatible, vec![true, false, false]);
+    }
+
+    #[test]
+    fn logical_type_added() {
+        let old = schema(
+            "message schema {
+                required binary a (UTF8);
+                required int32 b (INT_8);
+                required int64 c (TIMESTAMP_MICROS);
+                required int64 d (TIMESTAMP_MICROS);
+                required binary e;
+            }",
+        );
+        let new = schema(
+            "message schema {
+                required binary a (STRING);
+                required int32 b (INTEGER(8,true));
+                required int64 c (TIMESTAMP(MICROS,true));
+                required int64 d (TIMESTAMP(MICROS,false));
+                required binary e (STRING);
+            }",
+        );
+        let changes = compare(&old, &new);
+        assert_eq!(
+            changes[0],
+            SchemaChange::LogicalTypeChanged {
+                path: path(&["a"]),
+                from: None,
+                to: Some(PrimitiveLogicalType::String),
+                converted_type: Some(PrimitiveConvertedType::Utf8),
+            }
+        );
+        let compatible = changes
+            .iter()
+            .map(|x| x.is_compatible())
+            .collect::<Vec<_>>();
+        // the converted type of `d` is removed and `e` gains one
+        assert_eq!(
+            compatible,
+            vec![true, true, true, false, false, false, false]
+        );
+
+        // groups can't be annotated with only the converted type `LIST` in a message
+        assert!(SchemaChange::GroupLogicalTypeChanged {
+            path: path(&["f"]),
+            from: None,
+            to: Some(GroupLogicalType::List),
+            converted_type: Some(GroupConvertedType::List),
+        }
+        .is_compatible());
+    }
+
+    #[test]
+    fn types() {
+        let old = schema(
+            "message schema {
+                required int32 a;
+                required float b;
+                required int64 c;
+                required int32 d (INTEGER(8,true));
+                required int32 e (INTEGER(32,false));
+                required int32 f (DECIMAL(5,2));
+                required int32 g (DECIMAL(5,2));
+                required int32 h (INT_8);
+                required binary i (STRING);
+                required int32 j;
+                required group k (LIST) { repeated int32 list; }
+            }",
+        );
+        let new = schema(
+            "message schema {
+                required int64 a;
+                required double b;
+                required int32 c;
+                required int32 d (INTEGER(16,true));
+                required int32 e (INTEGER(16,false));
+                required int32 f (DECIMAL(9,2));
+                required int32 g (DECIMAL(9,3));
+                required int32 h (INT_16);
+                required binary i (JSON);
+                required group j { required int32 j; }
+                required group k { repeated int32 list; }
+            }",
+        );
+        let changes = compare(&old, &new);
+        let compatible = changes
+            .iter()
+            .map(|x| x.is_compatible())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            compatible,
+            vec![
+                true, true, false, true, true, false, false, true, true, false, false, true, false,
+                false, false, false, false
+            ]
+        );
+        assert_eq!(
+            changes[14],
+            SchemaChange::NestingChanged { path: path(&["j"]) }
+        );
+        assert_eq!(
+            changes[16],
+            SchemaChange::GroupConvertedTypeChanged {
+                path: path(&["k"]),
+                from: Some(GroupConvertedType::List),
+                to: None
+            }
+        );
+        assert!(!is_compatible(&old, &new));
+    }
+}
@@ -19,6 +19,12 @@
 //! Provides methods to parse and validate string message type into Parquet
 //! [`ParquetType`](crate::schema::types::ParquetType).
 //!
+//! As in parquet-mr, a primitive type is annotated with its logical type, e.g. `(STRING)`, or its
+//! converted type, e.g. `(UTF8)` or `(INTERVAL)`, and a group with its converted type, e.g.
+//! `(LIST)`. A logical type implies its converted type, e.g. `(STRING)` is parsed into the
+//! logical type `STRING` and the converted type `UTF8`, and the converted types `LIST` and `MAP`
+//! imply the logical type of the same name.
+//!
 //! # Example
 //!
 //! ```rust
@@ -48,7 +54,7 @@ use types::PrimitiveLogicalType;
 use super::super::types::{ParquetType, TimeUnit};
 use super::super::*;
 use crate::error::{Error, Result};
-use crate::schema::types::{GroupConvertedType, PrimitiveConvertedType};
+use crate::schema::types::{GroupConvertedType, GroupLogicalType, PrimitiveConvertedType};
 
 fn is_logical_type(s: &str) -> bool {
     matches!(
@@ -66,7 +72,6 @@ fn is_logical_type(s: &str) -> bool {
             | "BSON"
             | "UUID"
             | "UNKNOWN"
-            | "INTERVAL"
     )
 }
 
@@ -130,6 +135,14 @@ fn converted_primitive_from_str(s: &str) -> Option<PrimitiveConvertedType> {
     })
 }
 
+fn logical_group_from_str(s: &str) -> Option<GroupLogicalType> {
+    match s {
+        "MAP" => Some(GroupLogicalType::Map),
+        "LIST" => Some(GroupLogicalType::List),
+        _ => None,
+    }
+}
+
 fn repetition_from_str(s: &str) -> Result<Repetition> {
     Ok(match s {
         "REQUIRED" => Repetition::Required,
@@ -163,6 +176,11 @@ pub fn from_message(message_type: &str) -> Result<ParquetType> {
     parser.parse_message_type()
 }
 
+/// Returns whether `name` is a single token, i.e. can be the name of a type
+pub(super) fn is_valid_name(name: &str) -> bool {
+    !name.is_empty() && !name.contains(|c: char| c.is_whitespace() || Tokenizer::is_schema_delim(c))
+}
+
 /// Tokenizer to split message type string into tokens that are separated using characters
 /// defined in `is_schema_delim` method. Tokenizer also preserves delimiters as tokens.
 /// Tokenizer provides Iterator interface to process tokens; it also allows to step back
@@ -347,18 +365,19 @@ impl<'a> Parser<'a> {
             .next()
             .ok_or_else(|| general_err!("Expected name, found None"))?;
 
-        // Parse converted type if exists
-        let converted_type = if let Some("(") = self.tokenizer.next() {
-            let converted_type = self
+        // Parse converted type if exists: as in parquet-mr, `LIST` and `MAP` are also logical types
+        let (converted_type, logical_type) = if let Some("(") = self.tokenizer.next() {
+            let token = self
                 .tokenizer
                 .next()
-                .ok_or_else(|| general_err!("Expected converted type, found None"))
-                .and_then(|v| converted_group_from_str(&v.to_uppercase()))?;
+                .ok_or_else(|| general_err!("Expected converted type, found None"))?
+                .to_uppercase();
+            let converted_type = converted_group_from_str(&token)?;
             assert_token(self.tokenizer.next(), ")")?;
-            Some(converted_type)
+            (Some(converted_type), logical_group_from_str(&token))
         } else {
             self.tokenizer.backtrack();
-            None
+            (None, None)
         };
 
         // Parse optional id
@@ -371,11 +390,12 @@ impl<'a> Parser<'a> {
 
         let fields = self.parse_child_types()?;
 
-        Ok(ParquetType::from_converted(
+        Ok(ParquetType::from_group(
             name.to_string(),
-            fields,
             repetition,
             converted_type,
+            logical_type,
+            fields,
             id,
         ))
     }
@@ -405,42 +425,31 @@ impl<'a> Parser<'a> {
             .next()
             .ok_or_else(|| general_err!("Expected name, found None"))?;
 
-        // Parse logical types
+        // Parse logical or converted type: as in parquet-mr, a logical type implies its converted
+        // type, if any
         let (converted_type, logical_type) = if let Some("(") = self.tokenizer.next() {
-            let (is_logical_type, converted_type, token) = self
+            let string = self
                 .tokenizer
                 .next()
-                .ok_or_else(|| general_err!("Expected converted or logical type, found None"))
-                .and_then(|v| {
-                    let string = v.to_uppercase();
-                    Ok(if is_logical_type(&string) {
-                        (true, None, string)
-                    } else if is_converted_type(&string) {
-                        (false, converted_primitive_from_str(&string), string)
-                    } else {
-                        return Err(general_err!(
-                            "Expected converted or logical type, found {}",
-                            string
-                        ));
-                    })
-                })?;
-
-            let logical_type = if is_logical_type {
-                Some(self.parse_logical_type(&token)?)
+                .ok_or_else(|| general_err!("Expected converted or logical type, found None"))?
+                .to_uppercase();
+            let types = if is_logical_type(&string) {
+                let logical_type = self.parse_logical_type(&string)?;
+                (
+                    PrimitiveConvertedType::from_logical(logical_type),
+                    Some(logical_type),
+                )
+            } else if is_converted_type(&string) {
+                self.tokenizer.backtrack();
+                (Some(self.parse_converted_type()?), None)
             } else {
-                None
+                return Err(general_err!(
+                    "Expected converted or logical type, found {}",
+                    string
+                ));
             };
-
-            // converted type decimal
-            let converted_type = match converted_type {
-                Some(PrimitiveConvertedType::Decimal(_, _)) => {
-                    Some(self.parse_converted_decimal()?)
-                }
-                other => other,
-            };
-
             assert_token(self.tokenizer.next(), ")")?;
-            (converted_type, logical_type)
+            types
         } else {
             self.tokenizer.backtrack();
             (None, None)
@@ -465,6 +474,21 @@ impl<'a> Parser<'a> {
         )
     }
 
+    fn parse_converted_type(&mut self) -> Result<PrimitiveConvertedType> {
+        let converted_type = self
+            .tokenizer
+            .next()
+            .ok_or_else(|| general_err!("Expected converted type, found None"))
+            .and_then(|v| {
+                converted_primitive_from_str(&v.to_uppercase())
+                    .ok_or_else(|| general_err!("Expected converted type, found {}", v))
+            })?;
+        match converted_type {
+            PrimitiveConvertedType::Decimal(_, _) => self.parse_converted_decimal(),
+            other => Ok(other),
+        }
+    }
+
     fn parse_converted_decimal(&mut self) -> Result<PrimitiveConvertedType> {
         assert_token(self.tokenizer.next(), "(")?;
         // Parse precision
@@ -612,7 +636,6 @@ impl<'a> Parser<'a> {
             "BSON" => PrimitiveLogicalType::Bson,
             "UUID" => PrimitiveLogicalType::Uuid,
             "UNKNOWN" => PrimitiveLogicalType::Unknown,
-            "INTERVAL" => return Err(general_err!("Interval logical type not yet supported")),
             _ => unreachable!(),
         })
     }
@@ -866,7 +889,7 @@ mod tests {
                 "f1".to_string(),
                 PhysicalType::FixedLenByteArray(5),
                 Repetition::Optional,
-                None,
+                Some(PrimitiveConvertedType::Decimal(9, 3)),
                 Some(PrimitiveLogicalType::Decimal(9, 3)),
                 None,
             )?,
@@ -874,7 +897,7 @@ mod tests {
                 "f2".to_string(),
                 PhysicalType::FixedLenByteArray(16),
                 Repetition::Optional,
-                None,
+                Some(PrimitiveConvertedType::Decimal(38, 18)),
                 Some(PrimitiveLogicalType::Decimal(38, 18)),
                 None,
             )?,
@@ -919,11 +942,12 @@ mod tests {
             None,
             None,
         )?;
-        let a1 = ParquetType::from_converted(
+        let a1 = ParquetType::from_group(
             "a1".to_string(),
-            vec![a2],
             Repetition::Optional,
             Some(GroupConvertedType::List),
+            Some(GroupLogicalType::List),
+            vec![a2],
             None,
         );
         let b2 = ParquetType::from_converted(
@@ -936,11 +960,12 @@ mod tests {
             None,
             None,
         );
-        let b1 = ParquetType::from_converted(
+        let b1 = ParquetType::from_group(
             "b1".to_string(),
-            vec![b2],
             Repetition::Optional,
             Some(GroupConvertedType::List),
+            Some(GroupLogicalType::List),
+            vec![b2],
             None,
         );
         let a0 = ParquetType::from_converted(
@@ -1012,7 +1037,7 @@ mod tests {
             "_5".to_string(),
             PhysicalType::Int32,
             Repetition::Optional,
-            None,
+            Some(PrimitiveConvertedType::Date),
             Some(PrimitiveLogicalType::Date),
             None,
         )?;
@@ -1058,7 +1083,7 @@ mod tests {
             "_1".to_string(),
             PhysicalType::Int32,
             Repetition::Required,
-            None,
+            Some(PrimitiveConvertedType::Int8),
             Some(PrimitiveLogicalType::Integer(IntegerType::Int8)),
             None,
         )?;
@@ -1066,7 +1091,7 @@ mod tests {
             "_2".to_string(),
             PhysicalType::Int32,
             Repetition::Required,
-            None,
+            Some(PrimitiveConvertedType::Uint16),
             Some(PrimitiveLogicalType::Integer(IntegerType::UInt16)),
             None,
         )?;
@@ -1090,7 +1115,7 @@ mod tests {
             "_5".to_string(),
             PhysicalType::Int32,
             Repetition::Optional,
-            None,
+            Some(PrimitiveConvertedType::Date),
             Some(PrimitiveLogicalType::Date),
             None,
         )?;
@@ -1109,7 +1134,7 @@ mod tests {
             "_7".to_string(),
             PhysicalType::Int64,
             Repetition::Optional,
-            None,
+            Some(PrimitiveConvertedType::TimeMicros),
             Some(PrimitiveLogicalType::Time {
                 is_adjusted_to_utc: true,
                 unit: TimeUnit::Microseconds,
@@ -1120,7 +1145,7 @@ mod tests {
             "_8".to_string(),
             PhysicalType::Int64,
             Repetition::Optional,
-            None,
+            Some(PrimitiveConvertedType::TimestampMillis),
             Some(PrimitiveLogicalType::Timestamp {
                 is_adjusted_to_utc: true,
                 unit: TimeUnit::Milliseconds,
@@ -1143,7 +1168,7 @@ mod tests {
             "_10".to_string(),
             PhysicalType::ByteArray,
             Repetition::Optional,
-            None,
+            Some(PrimitiveConvertedType::Utf8),
             Some(PrimitiveLogicalType::String),
             None,
         )?;
@@ -1,3 +1,6 @@
 mod from_message;
+mod to_message;
 
 pub use from_message::from_message;
+pub use to_message::to_message;
+pub(crate) use to_message::write_message;
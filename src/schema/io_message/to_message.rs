@@ -0,0 +1,610 @@
+//! Parquet schema printer.
+//! Provides methods to serialize a Parquet [`ParquetType`] into the message type parsed by
+//! [`from_message`](super::from_message), e.g. to display or to diff schemas.
+//!
+//! # Example
+//!
+//! ```rust
+//! use parquet2::schema::io_message::{from_message, to_message};
+//!
+//! let message_type = "message spark_schema {
+//!   optional binary a (STRING);
+//!   required int32 b = 1;
+//!   optional group c (LIST) {
+//!     repeated group list {
+//!       required fixed_len_byte_array(16) element (DECIMAL(38,2));
+//!     }
+//!   }
+//! }
+//! ";
+//!
+//! let schema = from_message(message_type).expect("Expected valid schema");
+//! assert_eq!(to_message(&schema).unwrap(), message_type);
+//! ```
+
+use super::from_message::is_valid_name;
+use crate::error::{Error, Result};
+use crate::schema::types::{
+    GroupConvertedType, GroupLogicalType, ParquetType, PhysicalType, PrimitiveConvertedType,
+    PrimitiveLogicalType, PrimitiveType, TimeUnit,
+};
+use crate::schema::Repetition;
+
+const INDENT: usize = 2;
+
+/// Serializes the schema `type_` into a message type, in the syntax parsed by
+/// [`from_message`](super::from_message) and by parquet-mr. Only the name and the fields of the
+/// root of the schema are part of the message type.
+///
+/// As in parquet-mr, a type is annotated with its logical type or, without one, its converted
+/// type. The message is parsed back into `type_` when the converted type of every type with a
+/// logical type is the one the logical type implies (e.g. `STRING` and `UTF8`, as written by
+/// parquet-mr and pyarrow), since [`from_message`](super::from_message) infers it. Types
+/// annotated with a converted type named like a logical type, e.g. `DATE`, are parsed back with
+/// both.
+/// # Error
+/// Errors iff `type_` is not a group or the name of a field can't be written, e.g. because it
+/// contains whitespace.
+pub fn to_message(type_: &ParquetType) -> Result<String> {
+    match type_ {
+        ParquetType::GroupType {
+            field_info, fields, ..
+        } => write_message(&field_info.name, fields),
+        ParquetType::PrimitiveType(_) => Err(Error::InvalidParameter(
+            "The root of a schema must be a group type".to_string(),
+        )),
+    }
+}
+
+/// Serializes the schema named `name` with `fields` into a message type.
+pub(crate) fn write_message(name: &str, fields: &[ParquetType]) -> Result<String> {
+    let mut message = format!("message {} {{\n", checked_name(name)?);
+    for field in fields {
+        write_type(&mut message, field, INDENT)?;
+    }
+    message.push_str("}\n");
+    Ok(message)
+}
+
+fn checked_name(name: &str) -> Result<&str> {
+    if is_valid_name(name) {
+        Ok(name)
+    } else {
+        Err(Error::InvalidParameter(format!(
+            "The name \"{}\" can't be written to a message type",
+            name
+        )))
+    }
+}
+
+fn write_type(message: &mut String, type_: &ParquetType, indent: usize) -> Result<()> {
+    let field_info = type_.get_field_info();
+    let repetition = repetition_to_str(field_info.repetition);
+    let name = checked_name(&field_info.name)?;
+    let id = field_info
+        .id
+        .map(|id| format!(" = {}", id))
+        .unwrap_or_default();
+
+    match type_ {
+        ParquetType::PrimitiveType(PrimitiveType {
+            physical_type,
+            logical_type,
+            converted_type,
+            ..
+        }) => {
+            let annotation = primitive_annotation(*logical_type, *converted_type)
+                .map(|annotation| format!(" ({})", annotation))
+                .unwrap_or_default();
+            message.push_str(&format!(
+                "{:indent$}{} {} {}{}{};\n",
+                "",
+                repetition,
+                physical_type_to_str(physical_type),
+                name,
+                annotation,
+                id,
+                indent = indent
+            ));
+        }
+        ParquetType::GroupType {
+            logical_type,
+            converted_type,
+            fields,
+            ..
+        } => {
+            let annotation = group_annotation(*logical_type, *converted_type)
+                .map(|annotation| format!(" ({})", annotation))
+                .unwrap_or_default();
+            message.push_str(&format!(
+                "{:indent$}{} group {}{}{} {{\n",
+                "",
+                repetition,
+                name,
+                annotation,
+                id,
+                indent = indent
+            ));
+            for field in fields {
+                write_type(message, field, indent + INDENT)?;
+            }
+            message.push_str(&format!("{:indent$}}}\n", "", indent = indent));
+        }
+    }
+    Ok(())
+}
+
+fn repetition_to_str(repetition: Repetition) -> &'static str {
+    match repetition {
+        Repetition::Required => "required",
+        Repetition::Optional => "optional",
+        Repetition::Repeated => "repeated",
+    }
+}
+
+fn physical_type_to_str(physical_type: &PhysicalType) -> String {
+    match physical_type {
+        PhysicalType::Boolean => "boolean".to_string(),
+        PhysicalType::Int32 => "int32".to_string(),
+        PhysicalType::Int64 => "int64".to_string(),
+        PhysicalType::Int96 => "int96".to_string(),
+        PhysicalType::Float => "float".to_string(),
+        PhysicalType::Double => "double".to_string(),
+        PhysicalType::ByteArray => "binary".to_string(),
+        PhysicalType::FixedLenByteArray(length) => format!("fixed_len_byte_array({})", length),
+    }
+}
+
+fn time_unit_to_str(unit: TimeUnit) -> &'static str {
+    match unit {
+        TimeUnit::Milliseconds => "MILLIS",
+        TimeUnit::Microseconds => "MICROS",
+        TimeUnit::Nanoseconds => "NANOS",
+    }
+}
+
+fn logical_primitive_to_str(logical_type: PrimitiveLogicalType) -> String {
+    match logical_type {
+        PrimitiveLogicalType::String => "STRING".to_string(),
+        PrimitiveLogicalType::Enum => "ENUM".to_string(),
+        PrimitiveLogicalType::Decimal(precision, scale) => {
+            format!("DECIMAL({},{})", precision, scale)
+        }
+        PrimitiveLogicalType::Date => "DATE".to_string(),
+        PrimitiveLogicalType::Time {
+            unit,
+            is_adjusted_to_utc,
+        } => format!("TIME({},{})", time_unit_to_str(unit), is_adjusted_to_utc),
+        PrimitiveLogicalType::Timestamp {
+            unit,
+            is_adjusted_to_utc,
+        } => format!(
+            "TIMESTAMP({},{})",
+            time_unit_to_str(unit),
+            is_adjusted_to_utc
+        ),
+        PrimitiveLogicalType::Integer(integer) => {
+            let (bit_width, is_signed): (usize, bool) = integer.into();
+            format!("INTEGER({},{})", bit_width, is_signed)
+        }
+        PrimitiveLogicalType::Unknown => "UNKNOWN".to_string(),
+        PrimitiveLogicalType::Json => "JSON".to_string(),
+        PrimitiveLogicalType::Bson => "BSON".to_string(),
+        PrimitiveLogicalType::Uuid => "UUID".to_string(),
+    }
+}
+
+fn converted_primitive_to_str(converted_type: PrimitiveConvertedType) -> String {
+    use PrimitiveConvertedType::*;
+    match converted_type {
+        Utf8 => "UTF8",
+        Enum => "ENUM",
+        Decimal(precision, scale) => return format!("DECIMAL({},{})", precision, scale),
+        Date => "DATE",
+        TimeMillis => "TIME_MILLIS",
+        TimeMicros => "TIME_MICROS",
+        TimestampMillis => "TIMESTAMP_MILLIS",
+        TimestampMicros => "TIMESTAMP_MICROS",
+        Uint8 => "UINT_8",
+        Uint16 => "UINT_16",
+        Uint32 => "UINT_32",
+        Uint64 => "UINT_64",
+        Int8 => "INT_8",
+        Int16 => "INT_16",
+        Int32 => "INT_32",
+        Int64 => "INT_64",
+        Json => "JSON",
+        Bson => "BSON",
+        Interval => "INTERVAL",
+    }
+    .to_string()
+}
+
+/// The annotation of a primitive type: its logical type or, without one, its converted type.
+fn primitive_annotation(
+    logical_type: Option<PrimitiveLogicalType>,
+    converted_type: Option<PrimitiveConvertedType>,
+) -> Option<String> {
+    match (logical_type, converted_type) {
+        (Some(logical_type), _) => Some(logical_primitive_to_str(logical_type)),
+        (None, Some(converted_type)) => Some(converted_primitive_to_str(converted_type)),
+        (None, None) => None,
+    }
+}
+
+fn logical_group_to_str(logical_type: GroupLogicalType) -> &'static str {
+    match logical_type {
+        GroupLogicalType::Map => "MAP",
+        GroupLogicalType::List => "LIST",
+    }
+}
+
+fn converted_group_to_str(converted_type: GroupConvertedType) -> &'static str {
+    match converted_type {
+        GroupConvertedType::Map => "MAP",
+        GroupConvertedType::MapKeyValue => "MAP_KEY_VALUE",
+        GroupConvertedType::List => "LIST",
+    }
+}
+
+/// The annotation of a group type: its logical type or, without one, its converted type.
+fn group_annotation(
+    logical_type: Option<GroupLogicalType>,
+    converted_type: Option<GroupConvertedType>,
+) -> Option<String> {
+    match (logical_type, converted_type) {
+        (Some(logical_type), _) => Some(logical_group_to_str(logical_type).to_string()),
+        (None, Some(converted_type)) => Some(converted_group_to_str(converted_type).to_string()),
+        (None, None) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::from_message;
+    use super::*;
+
+    fn primitive(
+        name: &str,
+        physical_type: PhysicalType,
+        converted_type: Option<PrimitiveConvertedType>,
+        logical_type: Option<PrimitiveLogicalType>,
+        id: Option<i32>,
+    ) -> ParquetType {
+        ParquetType::try_from_primitive(
+            name.to_string(),
+            physical_type,
+            Repetition::Optional,
+            converted_type,
+            logical_type,
+            id,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn message() -> Result<()> {
+        let message = "message schema {
+  required int32 a (INTEGER(8,true)) = 1;
+  optional binary b (UTF8);
+  repeated fixed_len_byte_array(16) c (DECIMAL(38,10));
+  optional group d (LIST) = 2 {
+    repeated group list {
+      optional int64 element (TIMESTAMP(NANOS,false));
+    }
+  }
+  required group e (MAP) {
+  }
+}
+";
+        let schema = from_message(message)?;
+        assert_eq!(to_message(&schema)?, message);
+        Ok(())
+    }
+
+    #[test]
+    fn annotations() -> Result<()> {
+        use PrimitiveConvertedType as Converted;
+        use PrimitiveLogicalType as Logical;
+
+        // a schema as written by pyarrow and parquet-mr: types with a logical type are also
+        // annotated with the converted type it implies
+        let fields = vec![
+            primitive(
+                "a",
+                PhysicalType::ByteArray,
+                Some(Converted::Utf8),
+                Some(Logical::String),
+                None,
+            ),
+            primitive(
+                "b",
+                PhysicalType::Int64,
+                Some(Converted::TimestampMicros),
+                Some(Logical::Timestamp {
+                    unit: TimeUnit::Microseconds,
+                    is_adjusted_to_utc: true,
+                }),
+                None,
+            ),
+            primitive(
+                "c",
+                PhysicalType::Int64,
+                None,
+                Some(Logical::Timestamp {
+                    unit: TimeUnit::Nanoseconds,
+                    is_adjusted_to_utc: false,
+                }),
+                None,
+            ),
+            primitive(
+                "d",
+                PhysicalType::Int32,
+                Some(Converted::Uint32),
+                Some(Logical::Integer(crate::schema::types::IntegerType::UInt32)),
+                None,
+            ),
+            primitive(
+                "e",
+                PhysicalType::Int32,
+                Some(Converted::Date),
+                Some(Logical::Date),
+                None,
+            ),
+            primitive(
+                "f",
+                PhysicalType::FixedLenByteArray(12),
+                Some(Converted::Interval),
+                None,
+                None,
+            ),
+            ParquetType::from_group(
+                "g".to_string(),
+                Repetition::Optional,
+                Some(GroupConvertedType::Map),
+                Some(GroupLogicalType::Map),
+                vec![ParquetType::from_group(
+                    "key_value".to_string(),
+                    Repetition::Repeated,
+                    Some(GroupConvertedType::MapKeyValue),
+                    None,
+                    vec![primitive("key", PhysicalType::ByteArray, None, None, None)],
+                    None,
+                )],
+                None,
+            ),
+            ParquetType::from_group(
+                "h".to_string(),
+                Repetition::Optional,
+                Some(GroupConvertedType::List),
+                Some(GroupLogicalType::List),
+                vec![],
+                None,
+            ),
+        ];
+        let schema = ParquetType::new_root("schema".to_string(), fields);
+
+        let message = to_message(&schema)?;
+        assert_eq!(
+            message,
+            "message schema {
+  optional binary a (STRING);
+  optional int64 b (TIMESTAMP(MICROS,true));
+  optional int64 c (TIMESTAMP(NANOS,false));
+  optional int32 d (INTEGER(32,false));
+  optional int32 e (DATE);
+  optional fixed_len_byte_array(12) f (INTERVAL);
+  optional group g (MAP) {
+    repeated group key_value (MAP_KEY_VALUE) {
+      optional binary key;
+    }
+  }
+  optional group h (LIST) {
+  }
+}
+"
+        );
+        assert_eq!(from_message(&message)?, schema);
+        Ok(())
+    }
+
+    #[test]
+    fn implied_converted_type() -> Result<()> {
+        use PrimitiveConvertedType as Converted;
+        use PrimitiveLogicalType as Logical;
+
+        // a logical type is parsed with the converted type it implies and a converted type named
+        // like a logical type is parsed as both
+        let fields = vec![
+            primitive(
+                "a",
+                PhysicalType::ByteArray,
+                None,
+                Some(Logical::String),
+                None,
+            ),
+            primitive("b", PhysicalType::Int32, Some(Converted::Date), None, None),
+            ParquetType::from_group(
+                "c".to_string(),
+                Repetition::Optional,
+                Some(GroupConvertedType::List),
+                None,
+                vec![],
+                None,
+            ),
+        ];
+        let schema = ParquetType::new_root("schema".to_string(), fields);
+
+        let message = to_message(&schema)?;
+        assert_eq!(
+            message,
+            "message schema {
+  optional binary a (STRING);
+  optional int32 b (DATE);
+  optional group c (LIST) {
+  }
+}
+"
+        );
+        let expected = vec![
+            primitive(
+                "a",
+                PhysicalType::ByteArray,
+                Some(Converted::Utf8),
+                Some(Logical::String),
+                None,
+            ),
+            primitive(
+                "b",
+                PhysicalType::Int32,
+                Some(Converted::Date),
+                Some(Logical::Date),
+                None,
+            ),
+            ParquetType::from_group(
+                "c".to_string(),
+                Repetition::Optional,
+                Some(GroupConvertedType::List),
+                Some(GroupLogicalType::List),
+                vec![],
+                None,
+            ),
+        ];
+        assert_eq!(
+            from_message(&message)?,
+            ParquetType::new_root("schema".to_string(), expected)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn roundtrip() -> Result<()> {
+        use PrimitiveConvertedType as Converted;
+        use PrimitiveLogicalType as Logical;
+
+        // types without annotations, with a converted type or with a logical type and the
+        // converted type it implies are parsed back into themselves
+        let fields = vec![
+            primitive("a", PhysicalType::Boolean, None, None, None),
+            primitive("b", PhysicalType::Int96, None, None, Some(-1)),
+            primitive("c", PhysicalType::Float, None, None, None),
+            primitive("d", PhysicalType::Double, None, None, None),
+            primitive(
+                "e",
+                PhysicalType::Int32,
+                Some(Converted::Date),
+                Some(Logical::Date),
+                None,
+            ),
+            primitive(
+                "f",
+                PhysicalType::Int32,
+                Some(Converted::Decimal(9, 2)),
+                Some(Logical::Decimal(9, 2)),
+                None,
+            ),
+            primitive(
+                "g",
+                PhysicalType::Int64,
+                Some(Converted::Uint64),
+                None,
+                None,
+            ),
+            primitive(
+                "h",
+                PhysicalType::Int64,
+                Some(Converted::TimeMicros),
+                Some(Logical::Time {
+                    unit: TimeUnit::Microseconds,
+                    is_adjusted_to_utc: true,
+                }),
+                None,
+            ),
+            primitive(
+                "i",
+                PhysicalType::Int64,
+                Some(Converted::TimestampMillis),
+                None,
+                None,
+            ),
+            primitive(
+                "j",
+                PhysicalType::FixedLenByteArray(12),
+                Some(Converted::Interval),
+                None,
+                None,
+            ),
+            primitive(
+                "k",
+                PhysicalType::ByteArray,
+                Some(Converted::Utf8),
+                Some(Logical::String),
+                None,
+            ),
+            primitive(
+                "l",
+                PhysicalType::ByteArray,
+                Some(Converted::Json),
+                Some(Logical::Json),
+                None,
+            ),
+            primitive(
+                "m",
+                PhysicalType::FixedLenByteArray(16),
+                None,
+                Some(Logical::Uuid),
+                Some(7),
+            ),
+            primitive("n", PhysicalType::Int32, None, Some(Logical::Unknown), None),
+            ParquetType::from_group(
+                "o".to_string(),
+                Repetition::Optional,
+                Some(GroupConvertedType::Map),
+                Some(GroupLogicalType::Map),
+                vec![ParquetType::from_group(
+                    "key_value".to_string(),
+                    Repetition::Repeated,
+                    Some(GroupConvertedType::MapKeyValue),
+                    None,
+                    vec![
+                        primitive("key", PhysicalType::ByteArray, None, None, None),
+                        ParquetType::from_group(
+                            "value".to_string(),
+                            Repetition::Required,
+                            Some(GroupConvertedType::List),
+                            Some(GroupLogicalType::List),
+                            vec![],
+                            Some(3),
+                        ),
+                    ],
+                    None,
+                )],
+                Some(4),
+            ),
+        ];
+        let schema = ParquetType::new_root("schema".to_string(), fields);
+
+        let message = to_message(&schema)?;
+        assert_eq!(from_message(&message)?, schema);
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_name() {
+        for name in ["", "a b", "a;", "a(b)", "a=b", "{"] {
+            let schema = ParquetType::new_root(
+                "schema".to_string(),
+                vec![ParquetType::from_physical(
+                    name.to_string(),
+                    PhysicalType::Int32,
+                )],
+            );
+            assert!(to_message(&schema).is_err());
+        }
+        assert!(to_message(&ParquetType::from_physical(
+            "a".to_string(),
+            PhysicalType::Int32
+        ))
+        .is_err());
+    }
+}
@@ -2,6 +2,7 @@ pub use super::thrift_format::SchemaElement;
 
 pub use crate::parquet_bridge::Repetition;
 
+pub mod compatibility;
 pub mod io_message;
 pub mod io_thrift;
 
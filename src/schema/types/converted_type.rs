@@ -1,6 +1,8 @@
 use crate::error::Error;
 use parquet_format_safe::ConvertedType;
 
+use super::{IntegerType, PrimitiveLogicalType, TimeUnit};
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum PrimitiveConvertedType {
     Utf8,
@@ -142,6 +144,54 @@ impl TryFrom<(ConvertedType, Option<(i32, i32)>)> for PrimitiveConvertedType {
     }
 }
 
+impl PrimitiveConvertedType {
+    /// Returns the converted type that corresponds to `logical_type`, i.e. that writers annotate
+    /// a type with alongside `logical_type` for backward compatibility, if any.
+    /// See <https://github.com/apache/parquet-format/blob/master/LogicalTypes.md>.
+    pub fn from_logical(logical_type: PrimitiveLogicalType) -> Option<Self> {
+        use PrimitiveConvertedType::*;
+        Some(match logical_type {
+            PrimitiveLogicalType::String => Utf8,
+            PrimitiveLogicalType::Enum => Enum,
+            PrimitiveLogicalType::Decimal(precision, scale) => Decimal(precision, scale),
+            PrimitiveLogicalType::Date => Date,
+            // only times and timestamps adjusted to UTC have a converted type
+            PrimitiveLogicalType::Time {
+                unit,
+                is_adjusted_to_utc: true,
+            } => match unit {
+                TimeUnit::Milliseconds => TimeMillis,
+                TimeUnit::Microseconds => TimeMicros,
+                TimeUnit::Nanoseconds => return None,
+            },
+            PrimitiveLogicalType::Timestamp {
+                unit,
+                is_adjusted_to_utc: true,
+            } => match unit {
+                TimeUnit::Milliseconds => TimestampMillis,
+                TimeUnit::Microseconds => TimestampMicros,
+                TimeUnit::Nanoseconds => return None,
+            },
+            PrimitiveLogicalType::Integer(integer) => match integer {
+                IntegerType::Int8 => Int8,
+                IntegerType::Int16 => Int16,
+                IntegerType::Int32 => Int32,
+                IntegerType::Int64 => Int64,
+                IntegerType::UInt8 => Uint8,
+                IntegerType::UInt16 => Uint16,
+                IntegerType::UInt32 => Uint32,
+                IntegerType::UInt64 => Uint64,
+            },
+            PrimitiveLogicalType::Json => Json,
+            PrimitiveLogicalType::Bson => Bson,
+            PrimitiveLogicalType::Time { .. }
+            | PrimitiveLogicalType::Timestamp { .. }
+            | PrimitiveLogicalType::Unknown
+            | PrimitiveLogicalType::Uuid => return None,
+        })
+    }
+}
+
 impl TryFrom<ConvertedType> for GroupConvertedType {
     type Error = Error;
 
@@ -17,6 +17,31 @@ pub struct BinaryStatistics {
     pub min_value: Option<Vec<u8>>,
 }
 
+impl BinaryStatistics {
+    /// Truncates the min and max values to at most `length` bytes, so that they remain a lower
+    /// and an upper bound of the values: the min is truncated to its prefix and the max to its
+    /// prefix with the last byte that is not `0xFF` incremented. A max whose first `length`
+    /// bytes are all `0xFF` has no such bound and is kept as is.
+    ///
+    /// Truncated values are no longer exact. The thrift definitions used by this crate have no
+    /// `is_min_value_exact` nor `is_max_value_exact`, so truncated statistics are written without
+    /// marking them as inexact: readers must (and this crate does) only use the min and max of
+    /// byte arrays as bounds, never as values of the column.
+    pub fn truncate(&mut self, length: usize) {
+        if let Some(min) = self.min_value.as_mut() {
+            min.truncate(length);
+        }
+        if let Some(max) = self.max_value.as_mut() {
+            if max.len() > length {
+                if let Some(last) = max[..length].iter().rposition(|x| *x != u8::MAX) {
+                    max.truncate(last + 1);
+                    max[last] += 1;
+                }
+            }
+        }
+    }
+}
+
 impl Statistics for BinaryStatistics {
     fn as_any(&self) -> &dyn std::any::Any {
         self
@@ -51,3 +76,43 @@ pub fn write(v: &BinaryStatistics) -> ParquetStatistics {
         max: None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn truncate(min: &[u8], max: &[u8], length: usize) -> (Vec<u8>, Vec<u8>) {
+        let mut statistics = BinaryStatistics {
+            primitive_type: PrimitiveType::from_physical("a".to_string(), PhysicalType::ByteArray),
+            null_count: None,
+            distinct_count: None,
+            max_value: Some(max.to_vec()),
+            min_value: Some(min.to_vec()),
+        };
+        statistics.truncate(length);
+        (statistics.min_value.unwrap(), statistics.max_value.unwrap())
+    }
+
+    #[test]
+    fn truncate_values() {
+        assert_eq!(
+            truncate(b"abcdef", b"uvwxyz", 3),
+            (b"abc".to_vec(), b"uvx".to_vec())
+        );
+        // values that fit are kept
+        assert_eq!(
+            truncate(b"ab", b"uvw", 3),
+            (b"ab".to_vec(), b"uvw".to_vec())
+        );
+        // trailing 0xFF can't be incremented and are dropped
+        assert_eq!(
+            truncate(b"a", &[1, 0xFF, 0xFF, 3], 3),
+            (b"a".to_vec(), vec![2])
+        );
+        // a max without an upper bound of `length` bytes is kept
+        assert_eq!(
+            truncate(b"", &[0xFF, 0xFF, 0xFF, 3], 3),
+            (vec![], vec![0xFF, 0xFF, 0xFF, 3])
+        );
+    }
+}
@@ -112,7 +112,9 @@ pub fn int96_to_i64_ns(value: [u32; 3]) -> i64 {
     seconds * NANOS_PER_SECOND + nanoseconds
 }
 
-/// Returns the ordering of two binary values.
+/// Returns the ordering of two binary values, comparing their bytes as unsigned integers.
+///
+/// A value is smaller than the values it is a strict prefix of, e.g. `b"ab" < b"abc"`.
 pub fn ord_binary<'a>(a: &'a [u8], b: &'a [u8]) -> std::cmp::Ordering {
     use std::cmp::Ordering::*;
     match (a.is_empty(), b.is_empty()) {
@@ -128,7 +130,30 @@ pub fn ord_binary<'a>(a: &'a [u8], b: &'a [u8]) -> std::cmp::Ordering {
             other => return other,
         }
     }
-    Equal
+    // a prefix is smaller than the values it prefixes
+    a.len().cmp(&b.len())
+}
+
+/// Returns the ordering of two big-endian two's complement integers of arbitrary length,
+/// e.g. decimals stored as binary.
+pub(crate) fn ord_signed_binary(a: &[u8], b: &[u8]) -> std::cmp::Ordering {
+    use std::cmp::Ordering::*;
+    let is_negative = |x: &[u8]| matches!(x.first(), Some(byte) if *byte >= 0x80);
+    match (is_negative(a), is_negative(b)) {
+        (true, false) => Less,
+        (false, true) => Greater,
+        (is_negative, _) => {
+            // sign-extend the shortest to the length of the longest
+            let pad = if is_negative { 0xff } else { 0 };
+            let length = a.len().max(b.len());
+            let extend = |x: &[u8]| {
+                let mut extended = vec![pad; length - x.len()];
+                extended.extend_from_slice(x);
+                extended
+            };
+            extend(a).cmp(&extend(b))
+        }
+    }
 }
 
 #[inline]
@@ -139,3 +164,24 @@ pub fn decode<T: NativeType>(chunk: &[u8]) -> T {
     };
     T::from_le_bytes(chunk)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering::*;
+
+    use super::*;
+
+    #[test]
+    fn binary_order() {
+        assert_eq!(ord_binary(b"", b""), Equal);
+        assert_eq!(ord_binary(b"", b"a"), Less);
+        assert_eq!(ord_binary(b"a", b"b"), Less);
+        assert_eq!(ord_binary(b"b", b"ab"), Greater);
+        assert_eq!(ord_binary(b"abc", b"abc"), Equal);
+        // bytes are unsigned
+        assert_eq!(ord_binary(&[0x7f], &[0x80]), Less);
+        // a strict prefix is smaller than the values it prefixes
+        assert_eq!(ord_binary(b"ab", b"abc"), Less);
+        assert_eq!(ord_binary(b"abc", b"ab"), Greater);
+    }
+}
@@ -0,0 +1,89 @@
+use std::io::Write;
+
+#[cfg(feature = "async")]
+use futures::{AsyncWrite, AsyncWriteExt};
+#[cfg(feature = "async")]
+use parquet_format_safe::thrift::protocol::TCompactOutputStreamProtocol;
+
+use parquet_format_safe::thrift::protocol::TCompactOutputProtocol;
+use parquet_format_safe::{
+    BloomFilterAlgorithm, BloomFilterCompression, BloomFilterHash, BloomFilterHeader,
+    SplitBlockAlgorithm, Uncompressed, XxHash,
+};
+
+use crate::error::{Error, Result};
+
+use super::ColumnEncryptor;
+
+fn serialize_header(bitset: &[u8]) -> Result<BloomFilterHeader> {
+    if bitset.is_empty() || !bitset.len().is_multiple_of(32) {
+        return Err(Error::OutOfSpec(format!(
+            "The bitset of a bloom filter must be a non-empty multiple of 32 bytes. This one contains {}",
+            bitset.len()
+        )));
+    }
+    let num_bytes = bitset.len().try_into().map_err(|_| {
+        Error::OutOfSpec(format!(
+            "A bloom filter can only contain i32::MAX bytes. This one contains {}",
+            bitset.len()
+        ))
+    })?;
+
+    Ok(BloomFilterHeader {
+        num_bytes,
+        algorithm: BloomFilterAlgorithm::BLOCK(SplitBlockAlgorithm {}),
+        hash: BloomFilterHash::XXHASH(XxHash {}),
+        compression: BloomFilterCompression::UNCOMPRESSED(Uncompressed {}),
+    })
+}
+
+/// Writes a split-block bloom filter (its header followed by `bitset`) to `writer`,
+/// returning the number of bytes written.
+pub fn write_bloom_filter<W: Write>(
+    writer: &mut W,
+    bitset: &[u8],
+    encryptor: Option<&ColumnEncryptor>,
+) -> Result<u64> {
+    let header = serialize_header(bitset)?;
+    if let Some(encryptor) = encryptor {
+        let (header, bitset) = encrypt(&header, bitset, encryptor)?;
+        writer.write_all(&header)?;
+        writer.write_all(&bitset)?;
+        return Ok((header.len() + bitset.len()) as u64);
+    }
+    let mut protocol = TCompactOutputProtocol::new(&mut *writer);
+    let header_size = header.write_to_out_protocol(&mut protocol)? as u64;
+    writer.write_all(bitset)?;
+    Ok(header_size + bitset.len() as u64)
+}
+
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub async fn write_bloom_filter_async<W: AsyncWrite + Unpin + Send>(
+    writer: &mut W,
+    bitset: &[u8],
+    encryptor: Option<&ColumnEncryptor>,
+) -> Result<u64> {
+    let header = serialize_header(bitset)?;
+    if let Some(encryptor) = encryptor {
+        let (header, bitset) = encrypt(&header, bitset, encryptor)?;
+        writer.write_all(&header).await?;
+        writer.write_all(&bitset).await?;
+        return Ok((header.len() + bitset.len()) as u64);
+    }
+    let mut protocol = TCompactOutputStreamProtocol::new(&mut *writer);
+    let header_size = header.write_to_out_stream_protocol(&mut protocol).await? as u64;
+    writer.write_all(bitset).await?;
+    Ok(header_size + bitset.len() as u64)
+}
+
+/// Encrypts the header and the bitset of a bloom filter into two modules
+fn encrypt(
+    header: &BloomFilterHeader,
+    bitset: &[u8],
+    encryptor: &ColumnEncryptor,
+) -> Result<(Vec<u8>, Vec<u8>)> {
+    let mut buffer = vec![];
+    header.write_to_out_protocol(&mut TCompactOutputProtocol::new(&mut buffer))?;
+    encryptor.encrypt_bloom_filter(&buffer, bitset)
+}
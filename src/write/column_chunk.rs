@@ -15,22 +15,24 @@ use crate::{
     compression::Compression,
     encoding::Encoding,
     error::{Error, Result},
-    metadata::ColumnDescriptor,
+    metadata::{get_sort_order, ColumnDescriptor},
     page::{CompressedPage, PageType},
 };
 
 #[cfg(feature = "async")]
 use super::page::write_page_async;
 
-use super::page::{write_page, PageWriteSpec};
+use super::page::{is_data_page, write_page, PageWriteSpec};
 use super::statistics::reduce;
-use super::DynStreamingIterator;
+use super::{DynStreamingIterator, PageEncryptor, WriteOptions};
 
 pub fn write_column_chunk<'a, W, E>(
     writer: &mut W,
     mut offset: u64,
     descriptor: &ColumnDescriptor,
     mut compressed_pages: DynStreamingIterator<'a, CompressedPage, E>,
+    options: WriteOptions,
+    mut encryptor: Option<PageEncryptor>,
 ) -> Result<(ColumnChunk, Vec<PageWriteSpec>, u64)>
 where
     W: Write,
@@ -43,7 +45,7 @@ where
 
     let mut specs = vec![];
     while let Some(compressed_page) = compressed_pages.next()? {
-        let spec = write_page(writer, offset, compressed_page)?;
+        let spec = write_page(writer, offset, compressed_page, options, encryptor.as_mut())?;
         offset += spec.bytes_written;
         specs.push(spec);
     }
@@ -51,14 +53,16 @@ where
 
     let column_chunk = build_column_chunk(&specs, descriptor)?;
 
-    // write metadata
-    let mut protocol = TCompactOutputProtocol::new(writer);
-    bytes_written += column_chunk
-        .meta_data
-        .as_ref()
-        .unwrap()
-        .write_to_out_protocol(&mut protocol)? as u64;
-    protocol.flush()?;
+    // write metadata. The metadata of encrypted columns is only written (encrypted) in the footer
+    if encryptor.is_none() {
+        let mut protocol = TCompactOutputProtocol::new(writer);
+        bytes_written += column_chunk
+            .meta_data
+            .as_ref()
+            .unwrap()
+            .write_to_out_protocol(&mut protocol)? as u64;
+        protocol.flush()?;
+    }
 
     Ok((column_chunk, specs, bytes_written))
 }
@@ -70,6 +74,8 @@ pub async fn write_column_chunk_async<W, E>(
     mut offset: u64,
     descriptor: &ColumnDescriptor,
     mut compressed_pages: DynStreamingIterator<'_, CompressedPage, E>,
+    options: WriteOptions,
+    mut encryptor: Option<PageEncryptor>,
 ) -> Result<(ColumnChunk, Vec<PageWriteSpec>, u64)>
 where
     W: AsyncWrite + Unpin + Send,
@@ -80,7 +86,8 @@ where
     // write every page
     let mut specs = vec![];
     while let Some(compressed_page) = compressed_pages.next()? {
-        let spec = write_page_async(writer, offset, compressed_page).await?;
+        let spec =
+            write_page_async(writer, offset, compressed_page, options, encryptor.as_mut()).await?;
         offset += spec.bytes_written;
         specs.push(spec);
     }
@@ -88,15 +95,17 @@ where
 
     let column_chunk = build_column_chunk(&specs, descriptor)?;
 
-    // write metadata
-    let mut protocol = TCompactOutputStreamProtocol::new(writer);
-    bytes_written += column_chunk
-        .meta_data
-        .as_ref()
-        .unwrap()
-        .write_to_out_stream_protocol(&mut protocol)
-        .await? as u64;
-    protocol.flush().await?;
+    // write metadata. The metadata of encrypted columns is only written (encrypted) in the footer
+    if encryptor.is_none() {
+        let mut protocol = TCompactOutputStreamProtocol::new(writer);
+        bytes_written += column_chunk
+            .meta_data
+            .as_ref()
+            .unwrap()
+            .write_to_out_stream_protocol(&mut protocol)
+            .await? as u64;
+        protocol.flush().await?;
+    }
 
     Ok((column_chunk, specs, bytes_written))
 }
@@ -131,7 +140,17 @@ fn build_column_chunk(
         .iter()
         .map(|x| x.header_size as i64 + x.header.uncompressed_page_size as i64)
         .sum();
-    let data_page_offset = specs.first().map(|spec| spec.offset).unwrap_or(0) as i64;
+    let offset = specs.first().map(|spec| spec.offset).unwrap_or(0) as i64;
+    // SPEC: a dictionary page, if any, is the first page of the column chunk
+    let dictionary_page_offset = specs
+        .first()
+        .filter(|spec| !is_data_page(spec))
+        .map(|spec| spec.offset as i64);
+    let data_page_offset = specs
+        .iter()
+        .find(|spec| is_data_page(spec))
+        .map(|spec| spec.offset as i64)
+        .unwrap_or(offset);
     let num_values = specs
         .iter()
         .map(|spec| {
@@ -179,7 +198,13 @@ fn build_column_chunk(
     encodings.sort();
 
     let statistics = specs.iter().map(|x| &x.statistics).collect::<Vec<_>>();
-    let statistics = reduce(&statistics)?;
+    let primitive_type = &descriptor.descriptor.primitive_type;
+    let sort_order = get_sort_order(
+        &primitive_type.logical_type,
+        &primitive_type.converted_type,
+        &primitive_type.physical_type,
+    );
+    let statistics = reduce(&statistics, sort_order)?;
     let statistics = statistics.map(|x| serialize_statistics(x.as_ref()));
 
     let (type_, _): (Type, Option<i32>) = descriptor.descriptor.primitive_type.physical_type.into();
@@ -195,7 +220,7 @@ fn build_column_chunk(
         key_value_metadata: None,
         data_page_offset,
         index_page_offset: None,
-        dictionary_page_offset: None,
+        dictionary_page_offset,
         statistics,
         encoding_stats: None,
         bloom_filter_offset: None,
@@ -203,7 +228,7 @@ fn build_column_chunk(
 
     Ok(ColumnChunk {
         file_path: None, // same file for now.
-        file_offset: data_page_offset + total_compressed_size,
+        file_offset: offset + total_compressed_size,
         meta_data: Some(metadata),
         offset_index_offset: None,
         offset_index_length: None,
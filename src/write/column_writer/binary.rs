@@ -0,0 +1,248 @@
+use parquet_format_safe::Statistics as ParquetStatistics;
+
+use crate::encoding::{delta_byte_array, delta_length_byte_array, Encoding};
+use crate::error::{Error, Result};
+use crate::metadata::{Descriptor, SortOrder};
+use crate::page::CompressedPage;
+use crate::schema::types::PhysicalType;
+use crate::statistics::{serialize_statistics, BinaryStatistics};
+use crate::write::WriteOptions;
+
+use super::{
+    compare_binary, sort_order, unsupported_encoding, ColumnWriterOptions, Dictionary, Levels,
+    PageBuilder, Push, Values,
+};
+
+#[derive(Debug)]
+struct BinaryValues {
+    values: Vec<u8>,
+    offsets: Vec<usize>,
+    dictionary: Option<Dictionary>,
+    max_statistics_size: Option<usize>,
+}
+
+impl BinaryValues {
+    fn iter(&self) -> impl Iterator<Item = &[u8]> + Clone {
+        self.offsets.windows(2).map(|x| &self.values[x[0]..x[1]])
+    }
+}
+
+impl Values for BinaryValues {
+    fn len(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    fn size(&self) -> usize {
+        self.values.len() + self.len() * std::mem::size_of::<u32>()
+    }
+
+    fn statistics(&self, descriptor: &Descriptor, null_count: usize) -> ParquetStatistics {
+        let sort_order = sort_order(descriptor);
+        let mut statistics = BinaryStatistics {
+            primitive_type: descriptor.primitive_type.clone(),
+            null_count: Some(null_count as i64),
+            distinct_count: None,
+            max_value: self
+                .iter()
+                .max_by(|x, y| compare_binary(sort_order, x, y))
+                .map(|x| x.to_vec()),
+            min_value: self
+                .iter()
+                .min_by(|x, y| compare_binary(sort_order, x, y))
+                .map(|x| x.to_vec()),
+        };
+        // truncated values are only bounds of byte arrays ordered as unsigned bytes
+        if let (Some(length), SortOrder::Unsigned) = (self.max_statistics_size, sort_order) {
+            statistics.truncate(length);
+        }
+        serialize_statistics(&statistics)
+    }
+
+    fn encode(&mut self, encoding: Encoding, buffer: &mut Vec<u8>) -> Result<()> {
+        match encoding {
+            Encoding::Plain => self.iter().for_each(|x| {
+                buffer.extend_from_slice(&(x.len() as u32).to_le_bytes());
+                buffer.extend_from_slice(x);
+            }),
+            Encoding::DeltaLengthByteArray => delta_length_byte_array::encode(self.iter(), buffer),
+            Encoding::DeltaByteArray => delta_byte_array::encode(self.iter(), buffer),
+            Encoding::RleDictionary => self.dictionary.as_mut().unwrap().encode(buffer)?,
+            _ => unreachable!(),
+        }
+        self.values.clear();
+        self.offsets.truncate(1);
+        Ok(())
+    }
+
+    fn dictionary(&mut self) -> Option<&mut Dictionary> {
+        self.dictionary.as_mut()
+    }
+}
+
+impl<A: AsRef<[u8]>> Push<A> for BinaryValues {
+    fn push(&mut self, value: A) {
+        if let Some(dictionary) = self.dictionary.as_mut() {
+            dictionary.push(value.as_ref());
+        }
+        self.values.extend_from_slice(value.as_ref());
+        self.offsets.push(self.values.len());
+    }
+}
+
+/// Writes byte arrays into the pages of a column chunk.
+///
+/// Supported encodings are `Plain`, `DeltaLengthByteArray` and `DeltaByteArray`. Values can
+/// also be dictionary-encoded (see [`ColumnWriterOptions::max_dictionary_size`]).
+#[derive(Debug)]
+pub struct BinaryColumnWriter {
+    builder: PageBuilder,
+    values: BinaryValues,
+}
+
+impl BinaryColumnWriter {
+    /// Returns a new [`BinaryColumnWriter`] of the column described by `descriptor`.
+    /// # Errors
+    /// Errors if the physical type of the column is not `ByteArray` or if the encoding is not
+    /// supported for byte arrays.
+    pub fn new(
+        descriptor: Descriptor,
+        options: WriteOptions,
+        column_options: ColumnWriterOptions,
+    ) -> Result<Self> {
+        if descriptor.primitive_type.physical_type != PhysicalType::ByteArray {
+            return Err(Error::InvalidParameter(format!(
+                "The column has physical type {:?} but the writer writes ByteArray",
+                descriptor.primitive_type.physical_type,
+            )));
+        }
+        if !matches!(
+            column_options.encoding,
+            Encoding::Plain | Encoding::DeltaLengthByteArray | Encoding::DeltaByteArray
+        ) {
+            return Err(unsupported_encoding(column_options.encoding, &descriptor));
+        }
+        Ok(Self {
+            builder: PageBuilder::new(descriptor, options, column_options),
+            values: BinaryValues {
+                values: vec![],
+                offsets: vec![0],
+                dictionary: column_options
+                    .max_dictionary_size
+                    .map(|size| Dictionary::new(size, true)),
+                max_statistics_size: column_options.max_statistics_size,
+            },
+        })
+    }
+
+    /// Writes `values` with `levels` to the column chunk, finishing pages as they fill up.
+    /// # Errors
+    /// Errors if the number of values does not match `levels` or if `levels` is not valid
+    /// for the column.
+    pub fn write<A: AsRef<[u8]>, I: IntoIterator<Item = A>>(
+        &mut self,
+        values: I,
+        levels: Levels,
+    ) -> Result<()> {
+        self.builder.write(&mut self.values, values, levels)
+    }
+
+    /// Finishes the last page and returns all pages of the column chunk.
+    /// The pages can be passed to [`FileWriter`](crate::write::FileWriter) via
+    /// [`convert`](crate::fallible_streaming_iterator::convert).
+    pub fn finish(mut self) -> Result<Vec<CompressedPage>> {
+        self.builder.finish(&mut self.values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::page::{split_buffer, Page};
+    use crate::read::decompress;
+    use crate::schema::types::{PrimitiveLogicalType, PrimitiveType};
+    use crate::write::Version;
+
+    #[test]
+    fn delta_length_byte_array() -> Result<()> {
+        let descriptor = Descriptor {
+            primitive_type: PrimitiveType::from_physical("a".to_string(), PhysicalType::ByteArray),
+            max_def_level: 0,
+            max_rep_level: 0,
+        };
+        let options = WriteOptions {
+            write_statistics: true,
+            version: Version::V1,
+            write_page_crc: false,
+        };
+        let column_options = ColumnWriterOptions {
+            encoding: Encoding::DeltaLengthByteArray,
+            ..Default::default()
+        };
+        let mut writer = BinaryColumnWriter::new(descriptor.clone(), options, column_options)?;
+        writer.write(["aa", "b", ""], Levels::Required)?;
+        writer.write(["ccc"], Levels::Required)?;
+        let mut pages = writer.finish()?;
+
+        let page = match decompress(pages.pop().unwrap(), &mut vec![])? {
+            Page::Data(page) => page,
+            _ => unreachable!(),
+        };
+        let (_, _, buffer) = split_buffer(&page)?;
+        let mut decoder = delta_length_byte_array::Decoder::new(buffer);
+        let lengths = decoder.by_ref().collect::<Vec<_>>();
+        assert_eq!(lengths, [2, 1, 0, 3]);
+        assert_eq!(decoder.into_values(), b"aabccc");
+
+        let statistics = page.statistics().unwrap()?;
+        let expected = BinaryStatistics {
+            primitive_type: descriptor.primitive_type,
+            null_count: Some(0),
+            distinct_count: None,
+            max_value: Some(b"ccc".to_vec()),
+            min_value: Some(vec![]),
+        };
+        assert_eq!(
+            statistics.as_any().downcast_ref::<BinaryStatistics>(),
+            Some(&expected)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn truncated_decimal_statistics() -> Result<()> {
+        let mut primitive_type =
+            PrimitiveType::from_physical("a".to_string(), PhysicalType::ByteArray);
+        primitive_type.logical_type = Some(PrimitiveLogicalType::Decimal(5, 0));
+        let descriptor = Descriptor {
+            primitive_type,
+            max_def_level: 0,
+            max_rep_level: 0,
+        };
+        let options = WriteOptions {
+            write_statistics: true,
+            version: Version::V1,
+            write_page_crc: false,
+        };
+        let column_options = ColumnWriterOptions {
+            max_statistics_size: Some(1),
+            ..Default::default()
+        };
+        let mut writer = BinaryColumnWriter::new(descriptor, options, column_options)?;
+        // 256 and -255: prefixes are not bounds of signed values
+        writer.write([[1u8, 0], [0xFF, 1]], Levels::Required)?;
+        let page = match decompress(writer.finish()?.pop().unwrap(), &mut vec![])? {
+            Page::Data(page) => page,
+            _ => unreachable!(),
+        };
+
+        let statistics = page.statistics().unwrap()?;
+        let statistics = statistics
+            .as_any()
+            .downcast_ref::<BinaryStatistics>()
+            .unwrap();
+        assert_eq!(statistics.min_value, Some(vec![0xFF, 1]));
+        assert_eq!(statistics.max_value, Some(vec![1, 0]));
+        Ok(())
+    }
+}
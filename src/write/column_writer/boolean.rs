@@ -0,0 +1,110 @@
+use parquet_format_safe::Statistics as ParquetStatistics;
+
+use crate::encoding::hybrid_rle::{bitpacked_encode, encode_bool};
+use crate::encoding::Encoding;
+use crate::error::{Error, Result};
+use crate::metadata::Descriptor;
+use crate::page::CompressedPage;
+use crate::schema::types::PhysicalType;
+use crate::statistics::{serialize_statistics, BooleanStatistics};
+use crate::write::WriteOptions;
+
+use super::{unsupported_encoding, ColumnWriterOptions, Levels, PageBuilder, Push, Values};
+
+#[derive(Debug)]
+struct BooleanValues {
+    values: Vec<bool>,
+}
+
+impl Values for BooleanValues {
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn size(&self) -> usize {
+        crate::encoding::ceil8(self.values.len())
+    }
+
+    fn statistics(&self, _: &Descriptor, null_count: usize) -> ParquetStatistics {
+        serialize_statistics(&BooleanStatistics {
+            null_count: Some(null_count as i64),
+            distinct_count: None,
+            max_value: self.values.iter().max().copied(),
+            min_value: self.values.iter().min().copied(),
+        })
+    }
+
+    fn encode(&mut self, encoding: Encoding, buffer: &mut Vec<u8>) -> Result<()> {
+        match encoding {
+            Encoding::Plain => bitpacked_encode(buffer, self.values.iter().copied())?,
+            Encoding::Rle => {
+                // RLE-encoded booleans are prefixed by their length
+                let start = buffer.len();
+                buffer.extend_from_slice(&[0; 4]);
+                encode_bool(buffer, self.values.iter().copied())?;
+                let length = (buffer.len() - start - 4) as u32;
+                buffer[start..start + 4].copy_from_slice(&length.to_le_bytes());
+            }
+            _ => unreachable!(),
+        }
+        self.values.clear();
+        Ok(())
+    }
+}
+
+impl Push<bool> for BooleanValues {
+    fn push(&mut self, value: bool) {
+        self.values.push(value);
+    }
+}
+
+/// Writes booleans into the pages of a column chunk.
+///
+/// Supported encodings are `Plain` and `Rle`.
+#[derive(Debug)]
+pub struct BooleanColumnWriter {
+    builder: PageBuilder,
+    values: BooleanValues,
+}
+
+impl BooleanColumnWriter {
+    /// Returns a new [`BooleanColumnWriter`] of the column described by `descriptor`.
+    /// # Errors
+    /// Errors if the physical type of the column is not `Boolean` or if the encoding is not
+    /// supported for booleans.
+    pub fn new(
+        descriptor: Descriptor,
+        options: WriteOptions,
+        column_options: ColumnWriterOptions,
+    ) -> Result<Self> {
+        if descriptor.primitive_type.physical_type != PhysicalType::Boolean {
+            return Err(Error::InvalidParameter(format!(
+                "The column has physical type {:?} but the writer writes Boolean",
+                descriptor.primitive_type.physical_type,
+            )));
+        }
+        if !matches!(column_options.encoding, Encoding::Plain | Encoding::Rle) {
+            return Err(unsupported_encoding(column_options.encoding, &descriptor));
+        }
+        Ok(Self {
+            builder: PageBuilder::new(descriptor, options, column_options),
+            values: BooleanValues { values: vec![] },
+        })
+    }
+
+    /// Writes `values` with `levels` to the column chunk, finishing pages as they fill up.
+    /// # Errors
+    /// Errors if the number of values does not match `levels` or if `levels` is not valid
+    /// for the column.
+    pub fn write(&mut self, values: &[bool], levels: Levels) -> Result<()> {
+        self.builder
+            .write(&mut self.values, values.iter().copied(), levels)
+    }
+
+    /// Finishes the last page and returns all pages of the column chunk.
+    /// The pages can be passed to [`FileWriter`](crate::write::FileWriter) via
+    /// [`convert`](crate::fallible_streaming_iterator::convert).
+    pub fn finish(mut self) -> Result<Vec<CompressedPage>> {
+        self.builder.finish(&mut self.values)
+    }
+}
@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+
+use crate::encoding::hybrid_rle::encode_u32;
+use crate::error::Result;
+use crate::page::DictPage;
+
+/// Builds the dictionary of a column chunk from its values until the dictionary exceeds
+/// a maximum size, after which it stops accepting values.
+#[derive(Debug)]
+pub(super) struct Dictionary {
+    max_size: usize,
+    // whether values are prefixed by their length in the dictionary page (i.e. byte arrays)
+    length_prefixed: bool,
+    indices: HashMap<Vec<u8>, u32>,
+    // the plain-encoded values of the dictionary
+    buffer: Vec<u8>,
+    // the indices of the values of the current page
+    page_indices: Vec<u32>,
+    // the length of `buffer` and of `indices` when the current page started
+    page_start: (usize, usize),
+    // whether the dictionary exceeded `max_size`
+    has_fallen_back: bool,
+}
+
+impl Dictionary {
+    pub fn new(max_size: usize, length_prefixed: bool) -> Self {
+        Self {
+            max_size,
+            length_prefixed,
+            indices: HashMap::new(),
+            buffer: vec![],
+            page_indices: vec![],
+            page_start: (0, 0),
+            has_fallen_back: false,
+        }
+    }
+
+    /// Adds `value` to the current page.
+    pub fn push(&mut self, value: &[u8]) {
+        if self.has_fallen_back {
+            return;
+        }
+        let index = if let Some(index) = self.indices.get(value) {
+            *index
+        } else {
+            let index = self.indices.len() as u32;
+            if self.length_prefixed {
+                self.buffer
+                    .extend_from_slice(&(value.len() as u32).to_le_bytes());
+            }
+            self.buffer.extend_from_slice(value);
+            self.indices.insert(value.to_vec(), index);
+            index
+        };
+        self.page_indices.push(index);
+    }
+
+    /// Whether the dictionary exceeded its maximum size and the current page should be finished
+    pub fn is_full(&self) -> bool {
+        !self.has_fallen_back && self.buffer.len() > self.max_size
+    }
+
+    /// Finishes the current page, returning whether it is dictionary-encoded.
+    /// When the dictionary exceeded its maximum size, the values of the current page are
+    /// removed from it and it stops accepting values.
+    /// Pages are not dictionary-encoded while the dictionary is empty (e.g. pages of nulls),
+    /// since no dictionary page is written for an empty dictionary.
+    pub fn finish_page(&mut self) -> bool {
+        if self.has_fallen_back || self.indices.is_empty() {
+            return false;
+        }
+        if self.is_full() {
+            let (size, length) = self.page_start;
+            self.buffer.truncate(size);
+            self.indices.retain(|_, index| (*index as usize) < length);
+            self.page_indices.clear();
+            self.has_fallen_back = true;
+            return false;
+        }
+        true
+    }
+
+    /// Encodes the indices of the current page into `buffer`.
+    pub fn encode(&mut self, buffer: &mut Vec<u8>) -> Result<()> {
+        let max_index = self.indices.len().saturating_sub(1) as u32;
+        let num_bits = (32 - max_index.leading_zeros()).max(1) as u8;
+
+        // SPEC: the bit width used to encode the entry ids stored as 1 byte,
+        // SPEC: followed by the values encoded using RLE/Bit packed (with the given bit width).
+        buffer.push(num_bits);
+        encode_u32(buffer, self.page_indices.iter().copied(), num_bits)?;
+
+        self.page_indices.clear();
+        self.page_start = (self.buffer.len(), self.indices.len());
+        Ok(())
+    }
+
+    /// Returns the dictionary page of the column chunk, if any value was dictionary-encoded.
+    pub fn dict_page(&mut self) -> Option<DictPage> {
+        (!self.indices.is_empty())
+            .then(|| DictPage::new(std::mem::take(&mut self.buffer), self.indices.len(), false))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::encoding::hybrid_rle::HybridRleDecoder;
+
+    #[test]
+    fn indices() -> Result<()> {
+        let mut dictionary = Dictionary::new(usize::MAX, true);
+        for value in ["a", "bb", "a", "c", "bb"] {
+            dictionary.push(value.as_bytes());
+        }
+        assert!(dictionary.finish_page());
+        let mut buffer = vec![];
+        dictionary.encode(&mut buffer)?;
+
+        assert_eq!(buffer[0], 2);
+        let indices = HybridRleDecoder::new(&buffer[1..], 2, 5).collect::<Vec<_>>();
+        assert_eq!(indices, [0, 1, 0, 2, 1]);
+
+        let page = dictionary.dict_page().unwrap();
+        assert_eq!(page.num_values, 3);
+        assert_eq!(page.buffer, b"\x01\0\0\0a\x02\0\0\0bb\x01\0\0\0c");
+        Ok(())
+    }
+
+    #[test]
+    fn empty() {
+        let mut dictionary = Dictionary::new(usize::MAX, true);
+        assert!(!dictionary.finish_page());
+        assert!(dictionary.dict_page().is_none());
+
+        dictionary.push(b"a");
+        assert!(dictionary.finish_page());
+    }
+
+    #[test]
+    fn fallback() -> Result<()> {
+        let mut dictionary = Dictionary::new(8, false);
+        dictionary.push(&[1, 2, 3, 4]);
+        dictionary.push(&[1, 2, 3, 4]);
+        assert!(!dictionary.is_full());
+        assert!(dictionary.finish_page());
+        dictionary.encode(&mut vec![])?;
+
+        dictionary.push(&[5, 6, 7, 8]);
+        dictionary.push(&[9, 10, 11, 12]);
+        assert!(dictionary.is_full());
+        // the values of the page are removed from the dictionary
+        assert!(!dictionary.finish_page());
+        assert!(!dictionary.is_full());
+
+        // and the dictionary no longer accepts values
+        dictionary.push(&[13, 14, 15, 16]);
+        assert!(!dictionary.finish_page());
+
+        let page = dictionary.dict_page().unwrap();
+        assert_eq!(page.num_values, 1);
+        assert_eq!(page.buffer, [1, 2, 3, 4]);
+        Ok(())
+    }
+}
@@ -0,0 +1,202 @@
+use parquet_format_safe::Statistics as ParquetStatistics;
+
+use crate::encoding::{byte_stream_split, delta_byte_array, Encoding};
+use crate::error::{Error, Result};
+use crate::metadata::Descriptor;
+use crate::page::CompressedPage;
+use crate::schema::types::PhysicalType;
+use crate::statistics::{serialize_statistics, FixedLenStatistics};
+use crate::write::WriteOptions;
+
+use super::{
+    compare_binary, sort_order, unsupported_encoding, ColumnWriterOptions, Dictionary, Levels,
+    PageBuilder, Push, Values,
+};
+
+#[derive(Debug)]
+struct FixedLenValues {
+    values: Vec<u8>,
+    size: usize,
+    dictionary: Option<Dictionary>,
+}
+
+impl Values for FixedLenValues {
+    fn len(&self) -> usize {
+        self.values.len() / self.size
+    }
+
+    fn size(&self) -> usize {
+        self.values.len()
+    }
+
+    fn statistics(&self, descriptor: &Descriptor, null_count: usize) -> ParquetStatistics {
+        let sort_order = sort_order(descriptor);
+        serialize_statistics(&FixedLenStatistics {
+            primitive_type: descriptor.primitive_type.clone(),
+            null_count: Some(null_count as i64),
+            distinct_count: None,
+            max_value: self
+                .values
+                .chunks_exact(self.size)
+                .max_by(|x, y| compare_binary(sort_order, x, y))
+                .map(|x| x.to_vec()),
+            min_value: self
+                .values
+                .chunks_exact(self.size)
+                .min_by(|x, y| compare_binary(sort_order, x, y))
+                .map(|x| x.to_vec()),
+        })
+    }
+
+    fn encode(&mut self, encoding: Encoding, buffer: &mut Vec<u8>) -> Result<()> {
+        match encoding {
+            Encoding::Plain => buffer.extend_from_slice(&self.values),
+            Encoding::ByteStreamSplit => {
+                byte_stream_split::encode_fixed_len(&self.values, self.size, buffer)?
+            }
+            Encoding::DeltaByteArray => {
+                delta_byte_array::encode(self.values.chunks_exact(self.size), buffer)
+            }
+            Encoding::RleDictionary => self.dictionary.as_mut().unwrap().encode(buffer)?,
+            _ => unreachable!(),
+        }
+        self.values.clear();
+        Ok(())
+    }
+
+    fn dictionary(&mut self) -> Option<&mut Dictionary> {
+        self.dictionary.as_mut()
+    }
+}
+
+impl<A: AsRef<[u8]>> Push<A> for FixedLenValues {
+    fn check(&self, value: &A) -> Result<()> {
+        let value = value.as_ref();
+        if value.len() != self.size {
+            return Err(Error::InvalidParameter(format!(
+                "A value of a fixed-length column of size {} has {} bytes",
+                self.size,
+                value.len()
+            )));
+        }
+        Ok(())
+    }
+
+    fn push(&mut self, value: A) {
+        let value = value.as_ref();
+        if let Some(dictionary) = self.dictionary.as_mut() {
+            dictionary.push(value);
+        }
+        self.values.extend_from_slice(value);
+    }
+}
+
+/// Writes fixed-length byte arrays into the pages of a column chunk.
+///
+/// Supported encodings are `Plain`, `ByteStreamSplit` and `DeltaByteArray`. Values can also
+/// be dictionary-encoded (see [`ColumnWriterOptions::max_dictionary_size`]).
+#[derive(Debug)]
+pub struct FixedLenColumnWriter {
+    builder: PageBuilder,
+    values: FixedLenValues,
+}
+
+impl FixedLenColumnWriter {
+    /// Returns a new [`FixedLenColumnWriter`] of the column described by `descriptor`.
+    /// # Errors
+    /// Errors if the physical type of the column is not `FixedLenByteArray` or if the
+    /// encoding is not supported for fixed-length byte arrays.
+    pub fn new(
+        descriptor: Descriptor,
+        options: WriteOptions,
+        column_options: ColumnWriterOptions,
+    ) -> Result<Self> {
+        let size = match descriptor.primitive_type.physical_type {
+            PhysicalType::FixedLenByteArray(size) if size > 0 => size,
+            other => {
+                return Err(Error::InvalidParameter(format!(
+                    "The column has physical type {:?} but the writer writes FixedLenByteArray",
+                    other,
+                )))
+            }
+        };
+        if !matches!(
+            column_options.encoding,
+            Encoding::Plain | Encoding::ByteStreamSplit | Encoding::DeltaByteArray
+        ) {
+            return Err(unsupported_encoding(column_options.encoding, &descriptor));
+        }
+        Ok(Self {
+            builder: PageBuilder::new(descriptor, options, column_options),
+            values: FixedLenValues {
+                values: vec![],
+                size,
+                dictionary: column_options
+                    .max_dictionary_size
+                    .map(|size| Dictionary::new(size, false)),
+            },
+        })
+    }
+
+    /// Writes `values` with `levels` to the column chunk, finishing pages as they fill up.
+    /// # Errors
+    /// Errors if the number of values does not match `levels`, if `levels` is not valid
+    /// for the column or if a value does not have the size of the column.
+    pub fn write<A: AsRef<[u8]>, I: IntoIterator<Item = A>>(
+        &mut self,
+        values: I,
+        levels: Levels,
+    ) -> Result<()> {
+        self.builder.write(&mut self.values, values, levels)
+    }
+
+    /// Finishes the last page and returns all pages of the column chunk.
+    /// The pages can be passed to [`FileWriter`](crate::write::FileWriter) via
+    /// [`convert`](crate::fallible_streaming_iterator::convert).
+    pub fn finish(mut self) -> Result<Vec<CompressedPage>> {
+        self.builder.finish(&mut self.values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::page::Page;
+    use crate::read::decompress;
+    use crate::schema::types::{PrimitiveLogicalType, PrimitiveType};
+    use crate::write::Version;
+
+    #[test]
+    fn decimal_statistics() -> Result<()> {
+        let mut primitive_type =
+            PrimitiveType::from_physical("a".to_string(), PhysicalType::FixedLenByteArray(2));
+        primitive_type.logical_type = Some(PrimitiveLogicalType::Decimal(4, 2));
+        let descriptor = Descriptor {
+            primitive_type,
+            max_def_level: 0,
+            max_rep_level: 0,
+        };
+        let options = WriteOptions {
+            write_statistics: true,
+            version: Version::V1,
+            write_page_crc: false,
+        };
+        let mut writer = FixedLenColumnWriter::new(descriptor, options, Default::default())?;
+        // 1, -2 and 0 as big-endian two's complement
+        writer.write([[0u8, 1], [0xFF, 0xFE], [0, 0]], Levels::Required)?;
+        let page = match decompress(writer.finish()?.pop().unwrap(), &mut vec![])? {
+            Page::Data(page) => page,
+            _ => unreachable!(),
+        };
+
+        let statistics = page.statistics().unwrap()?;
+        let statistics = statistics
+            .as_any()
+            .downcast_ref::<FixedLenStatistics>()
+            .unwrap();
+        assert_eq!(statistics.min_value, Some(vec![0xFF, 0xFE]));
+        assert_eq!(statistics.max_value, Some(vec![0, 1]));
+        Ok(())
+    }
+}
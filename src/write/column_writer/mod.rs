@@ -0,0 +1,568 @@
+//! Typed writers that build the pages of a column chunk from values.
+mod binary;
+mod boolean;
+mod dictionary;
+mod fixed_len;
+mod native;
+
+pub use binary::BinaryColumnWriter;
+pub use boolean::BooleanColumnWriter;
+pub use fixed_len::FixedLenColumnWriter;
+pub use native::NativeColumnWriter;
+
+use parquet_format_safe::Statistics as ParquetStatistics;
+
+use crate::compression::CompressionOptions;
+use crate::encoding::delta_bitpacked::BlockLayout;
+use crate::encoding::hybrid_rle::encode_u32;
+use crate::encoding::Encoding;
+use crate::error::{Error, Result};
+use crate::metadata::{get_sort_order, Descriptor, SortOrder};
+use crate::page::{
+    CompressedPage, DataPage, DataPageHeader, DataPageHeaderV1, DataPageHeaderV2, EncodedPage,
+};
+use crate::read::levels::get_bit_width;
+
+use super::statistics::{compare_binary, compare_native};
+use super::{compress, Version, WriteOptions};
+
+use dictionary::Dictionary;
+
+/// The levels of the values passed to a column writer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Levels<'a> {
+    /// Every value is a row and no row is null.
+    Required,
+    /// Every item is a row, which is null when `false`.
+    /// Only the values of non-null rows are passed to the writer.
+    Optional(&'a [bool]),
+    /// The repetition and definition levels of every item. `rep` must be empty when the column
+    /// is not repeated. Only the values whose definition level is the maximum definition level
+    /// of the column are passed to the writer.
+    Nested { rep: &'a [u32], def: &'a [u32] },
+}
+
+/// Options of the typed column writers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ColumnWriterOptions {
+    /// The encoding of the values
+    pub encoding: Encoding,
+    /// The compression of the pages
+    pub compression: CompressionOptions,
+    /// The size in bytes of the (uncompressed) values of a page after which a new page is
+    /// started. Pages only end at row boundaries and can thus be larger than this.
+    pub max_page_size: usize,
+    /// When set, values are dictionary-encoded until the (plain-encoded) dictionary of the
+    /// column chunk exceeds this size in bytes, after which the remaining pages fall back to
+    /// `encoding`. Ignored for booleans.
+    pub max_dictionary_size: Option<usize>,
+    /// The layout of the blocks of `DELTA_BINARY_PACKED`-encoded values. Ignored for other
+    /// encodings.
+    pub delta_block_layout: BlockLayout,
+    /// When set, the min and max statistics of byte arrays, of the pages and thus of the
+    /// column chunk and of its column index, are truncated to this size in bytes and are then
+    /// only bounds (see [`BinaryStatistics::truncate`](crate::statistics::BinaryStatistics::truncate)).
+    /// Ignored for other physical types, whose statistics have a fixed size, and for byte
+    /// arrays with a signed order (decimals), whose prefixes are not bounds.
+    pub max_statistics_size: Option<usize>,
+}
+
+impl Default for ColumnWriterOptions {
+    fn default() -> Self {
+        Self {
+            encoding: Encoding::Plain,
+            compression: CompressionOptions::Uncompressed,
+            max_page_size: 1024 * 1024,
+            max_dictionary_size: None,
+            delta_block_layout: Default::default(),
+            max_statistics_size: None,
+        }
+    }
+}
+
+/// A typed buffer of the values of the page being built.
+trait Values {
+    /// The number of buffered values
+    fn len(&self) -> usize;
+
+    /// The size in bytes of the buffered values once plain encoded
+    fn size(&self) -> usize;
+
+    /// The statistics of the buffered values
+    fn statistics(&self, descriptor: &Descriptor, null_count: usize) -> ParquetStatistics;
+
+    /// Encodes the buffered values into `buffer`, clearing them.
+    fn encode(&mut self, encoding: Encoding, buffer: &mut Vec<u8>) -> Result<()>;
+
+    /// The dictionary of the column chunk, if its values are dictionary-encoded
+    fn dictionary(&mut self) -> Option<&mut Dictionary> {
+        None
+    }
+}
+
+/// A [`Values`] that accepts values of type `A`.
+trait Push<A> {
+    /// Errors if `value` can't be pushed, e.g. because it has the wrong size.
+    fn check(&self, _value: &A) -> Result<()> {
+        Ok(())
+    }
+
+    fn push(&mut self, value: A);
+}
+
+fn unsupported_encoding(encoding: Encoding, descriptor: &Descriptor) -> Error {
+    Error::InvalidParameter(format!(
+        "The encoding {:?} is not supported for the physical type {:?}",
+        encoding, descriptor.primitive_type.physical_type
+    ))
+}
+
+/// The order of the statistics of the column described by `descriptor`
+fn sort_order(descriptor: &Descriptor) -> SortOrder {
+    let primitive_type = &descriptor.primitive_type;
+    get_sort_order(
+        &primitive_type.logical_type,
+        &primitive_type.converted_type,
+        &primitive_type.physical_type,
+    )
+}
+
+/// Writes `levels` to `buffer`, returning the number of bytes written.
+fn write_levels(
+    buffer: &mut Vec<u8>,
+    levels: &[u32],
+    max_level: i16,
+    version: Version,
+) -> Result<usize> {
+    if max_level == 0 {
+        return Ok(0);
+    }
+    let num_bits = get_bit_width(max_level) as u8;
+    match version {
+        Version::V1 => {
+            // v1 levels are prefixed by their length
+            let start = buffer.len();
+            buffer.extend_from_slice(&[0; 4]);
+            encode_u32(buffer, levels.iter().copied(), num_bits)?;
+            let length = (buffer.len() - start - 4) as u32;
+            buffer[start..start + 4].copy_from_slice(&length.to_le_bytes());
+            Ok(buffer.len() - start)
+        }
+        Version::V2 => {
+            let start = buffer.len();
+            encode_u32(buffer, levels.iter().copied(), num_bits)?;
+            Ok(buffer.len() - start)
+        }
+    }
+}
+
+/// The state shared by all typed column writers: the levels of the page being built
+/// and the pages already finished.
+#[derive(Debug)]
+struct PageBuilder {
+    descriptor: Descriptor,
+    options: WriteOptions,
+    column_options: ColumnWriterOptions,
+    rep_levels: Vec<u32>,
+    def_levels: Vec<u32>,
+    // the number of levels (values and nulls) of the current page
+    num_values: usize,
+    num_rows: usize,
+    pages: Vec<CompressedPage>,
+}
+
+impl PageBuilder {
+    fn new(
+        descriptor: Descriptor,
+        options: WriteOptions,
+        column_options: ColumnWriterOptions,
+    ) -> Self {
+        Self {
+            descriptor,
+            options,
+            column_options,
+            rep_levels: vec![],
+            def_levels: vec![],
+            num_values: 0,
+            num_rows: 0,
+            pages: vec![],
+        }
+    }
+
+    fn write<V, A, I>(&mut self, buffer: &mut V, values: I, levels: Levels) -> Result<()>
+    where
+        V: Values + Push<A>,
+        I: IntoIterator<Item = A>,
+    {
+        let max_rep = self.descriptor.max_rep_level as u32;
+        let max_def = self.descriptor.max_def_level as u32;
+
+        // validate everything before mutating, so that a failed write leaves the writer unchanged
+        let num_valid = self.check_levels(levels)?;
+        let values = values.into_iter().collect::<Vec<_>>();
+        let num_valid = num_valid.unwrap_or(values.len());
+        if values.len() != num_valid {
+            return Err(Error::InvalidParameter(format!(
+                "There are {} values but {} non-null levels",
+                values.len(),
+                num_valid
+            )));
+        }
+        values.iter().try_for_each(|value| buffer.check(value))?;
+        let mut values = values.into_iter();
+
+        match levels {
+            Levels::Required => {
+                for value in values {
+                    self.start_row(buffer)?;
+                    if max_def > 0 {
+                        self.def_levels.push(max_def);
+                    }
+                    self.num_values += 1;
+                    buffer.push(value);
+                }
+            }
+            Levels::Optional(validity) => {
+                for is_valid in validity {
+                    self.start_row(buffer)?;
+                    self.def_levels.push(*is_valid as u32);
+                    self.num_values += 1;
+                    if *is_valid {
+                        buffer.push(values.next().unwrap());
+                    }
+                }
+            }
+            Levels::Nested { rep, def } => {
+                for (i, def) in def.iter().copied().enumerate() {
+                    let rep = rep.get(i).copied().unwrap_or(0);
+                    if rep == 0 {
+                        self.start_row(buffer)?;
+                    }
+                    if max_rep > 0 {
+                        self.rep_levels.push(rep);
+                    }
+                    if max_def > 0 {
+                        self.def_levels.push(def);
+                    }
+                    self.num_values += 1;
+                    if def == max_def {
+                        buffer.push(values.next().unwrap());
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Validates `levels` against the column, returning the number of values they declare
+    /// as non-null, if they declare it.
+    fn check_levels(&self, levels: Levels) -> Result<Option<usize>> {
+        let max_rep = self.descriptor.max_rep_level as u32;
+        let max_def = self.descriptor.max_def_level as u32;
+
+        match levels {
+            Levels::Required => {
+                if max_rep > 0 {
+                    return Err(Error::InvalidParameter(
+                        "Values of a repeated column require nested levels".to_string(),
+                    ));
+                }
+                Ok(None)
+            }
+            Levels::Optional(validity) => {
+                if max_rep > 0 || max_def != 1 {
+                    return Err(Error::InvalidParameter(
+                        "A validity can only be used on optional, non-nested columns".to_string(),
+                    ));
+                }
+                Ok(Some(validity.iter().filter(|x| **x).count()))
+            }
+            Levels::Nested { rep, def } => {
+                if (max_rep > 0 && rep.len() != def.len()) || (max_rep == 0 && !rep.is_empty()) {
+                    return Err(Error::InvalidParameter(
+                        "The repetition levels must have the same length as the definition levels, or be empty if the column is not repeated".to_string(),
+                    ));
+                }
+                if rep.first().is_some_and(|rep| *rep > 0) && self.num_values == 0 {
+                    return Err(Error::InvalidParameter(
+                        "The first level of a column chunk must start a row".to_string(),
+                    ));
+                }
+                let num_valid = def.iter().enumerate().try_fold(0, |num_valid, (i, def)| {
+                    let rep = rep.get(i).copied().unwrap_or(0);
+                    if rep > max_rep || *def > max_def {
+                        return Err(Error::InvalidParameter(format!(
+                            "The levels ({}, {}) are larger than the maximum levels of the column ({}, {})",
+                            rep, def, max_rep, max_def
+                        )));
+                    }
+                    Ok(num_valid + (*def == max_def) as usize)
+                })?;
+                Ok(Some(num_valid))
+            }
+        }
+    }
+
+    /// Declares that a new row starts, finishing the current page if it is full.
+    fn start_row<V: Values>(&mut self, buffer: &mut V) -> Result<()> {
+        let is_dictionary_full = buffer.dictionary().is_some_and(|x| x.is_full());
+        if buffer.size() >= self.column_options.max_page_size || is_dictionary_full {
+            self.finish_page(buffer)?;
+        }
+        self.num_rows += 1;
+        Ok(())
+    }
+
+    /// Encodes and compresses the current page, if any.
+    fn finish_page<V: Values>(&mut self, buffer: &mut V) -> Result<()> {
+        if self.num_values == 0 {
+            return Ok(());
+        }
+        let num_nulls = self.num_values - buffer.len();
+        let statistics = self
+            .options
+            .write_statistics
+            .then(|| buffer.statistics(&self.descriptor, num_nulls));
+        let is_dictionary_encoded = buffer.dictionary().is_some_and(|x| x.finish_page());
+        let encoding = if is_dictionary_encoded {
+            Encoding::RleDictionary
+        } else {
+            self.column_options.encoding
+        };
+
+        let mut data = vec![];
+        let rep_levels_byte_length = write_levels(
+            &mut data,
+            &self.rep_levels,
+            self.descriptor.max_rep_level,
+            self.options.version,
+        )?;
+        let def_levels_byte_length = write_levels(
+            &mut data,
+            &self.def_levels,
+            self.descriptor.max_def_level,
+            self.options.version,
+        )?;
+        buffer.encode(encoding, &mut data)?;
+
+        let header = match self.options.version {
+            Version::V1 => DataPageHeader::V1(DataPageHeaderV1 {
+                num_values: self.num_values as i32,
+                encoding: encoding.into(),
+                definition_level_encoding: Encoding::Rle.into(),
+                repetition_level_encoding: Encoding::Rle.into(),
+                statistics,
+            }),
+            Version::V2 => DataPageHeader::V2(DataPageHeaderV2 {
+                num_values: self.num_values as i32,
+                num_nulls: num_nulls as i32,
+                num_rows: self.num_rows as i32,
+                encoding: encoding.into(),
+                definition_levels_byte_length: def_levels_byte_length as i32,
+                repetition_levels_byte_length: rep_levels_byte_length as i32,
+                is_compressed: Some(
+                    self.column_options.compression != CompressionOptions::Uncompressed,
+                ),
+                statistics,
+            }),
+        };
+        let page = DataPage::new(header, data, self.descriptor.clone(), Some(self.num_rows));
+        self.pages.push(compress(
+            EncodedPage::Data(page),
+            vec![],
+            self.column_options.compression,
+        )?);
+
+        self.rep_levels.clear();
+        self.def_levels.clear();
+        self.num_values = 0;
+        self.num_rows = 0;
+        Ok(())
+    }
+
+    fn finish<V: Values>(mut self, buffer: &mut V) -> Result<Vec<CompressedPage>> {
+        self.finish_page(buffer)?;
+        if let Some(page) = buffer.dictionary().and_then(|x| x.dict_page()) {
+            // SPEC: the dictionary page is the first page of the column chunk
+            let page = compress(
+                EncodedPage::Dict(page),
+                vec![],
+                self.column_options.compression,
+            )?;
+            self.pages.insert(0, page);
+        }
+        Ok(self.pages)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::encoding::hybrid_rle::HybridRleDecoder;
+    use crate::page::split_buffer;
+    use crate::read::decompress;
+    use crate::schema::types::{PhysicalType, PrimitiveType};
+
+    fn descriptor(max_def_level: i16, max_rep_level: i16) -> Descriptor {
+        Descriptor {
+            primitive_type: PrimitiveType::from_physical("a".to_string(), PhysicalType::Int32),
+            max_def_level,
+            max_rep_level,
+        }
+    }
+
+    fn decode_levels(levels: &[u8], max_level: i16, length: usize) -> Vec<u32> {
+        HybridRleDecoder::new(levels, get_bit_width(max_level), length).collect()
+    }
+
+    fn data_pages(pages: Vec<CompressedPage>) -> Vec<DataPage> {
+        pages
+            .into_iter()
+            .map(|page| match decompress(page, &mut vec![]).unwrap() {
+                crate::page::Page::Data(page) => page,
+                _ => unreachable!(),
+            })
+            .collect()
+    }
+
+    fn write_nested(version: Version) -> Result<()> {
+        let options = WriteOptions {
+            write_statistics: true,
+            version,
+            write_page_crc: false,
+        };
+        let column_options = ColumnWriterOptions {
+            max_page_size: 8,
+            ..Default::default()
+        };
+        let mut writer = NativeColumnWriter::<i32>::new(descriptor(2, 1), options, column_options)?;
+
+        // [[1, 2], None, [], [3, None, 4], [5]]
+        let rep = [0, 1, 0, 0, 0, 1, 1, 0];
+        let def = [2, 2, 0, 1, 2, 1, 2, 2];
+        writer.write(
+            &[1, 2, 3, 4, 5],
+            Levels::Nested {
+                rep: &rep,
+                def: &def,
+            },
+        )?;
+
+        let pages = data_pages(writer.finish()?);
+        // a page ends on the first row boundary after 8 bytes of values
+        let num_values = pages.iter().map(|x| x.num_values()).collect::<Vec<_>>();
+        assert_eq!(num_values, [2, 5, 1]);
+
+        let (rep_levels, def_levels, values) = split_buffer(&pages[1])?;
+        assert_eq!(decode_levels(rep_levels, 1, 5), rep[2..7]);
+        assert_eq!(decode_levels(def_levels, 2, 5), def[2..7]);
+        let values = values
+            .chunks_exact(4)
+            .map(crate::types::decode::<i32>)
+            .collect::<Vec<_>>();
+        assert_eq!(values, [3, 4]);
+        Ok(())
+    }
+
+    #[test]
+    fn nested_v1() -> Result<()> {
+        write_nested(Version::V1)
+    }
+
+    #[test]
+    fn nested_v2() -> Result<()> {
+        write_nested(Version::V2)
+    }
+
+    #[test]
+    fn invalid_levels() -> Result<()> {
+        let options = WriteOptions {
+            write_statistics: false,
+            version: Version::V1,
+            write_page_crc: false,
+        };
+        let mut writer =
+            NativeColumnWriter::<i32>::new(descriptor(1, 0), options, Default::default())?;
+
+        // fewer values than valid items
+        assert!(writer.write(&[1], Levels::Optional(&[true, true])).is_err());
+
+        let mut writer =
+            NativeColumnWriter::<i32>::new(descriptor(1, 0), options, Default::default())?;
+        // more values than valid items
+        assert!(writer
+            .write(&[1, 2], Levels::Optional(&[true, false]))
+            .is_err());
+
+        let mut writer =
+            NativeColumnWriter::<i32>::new(descriptor(2, 1), options, Default::default())?;
+        // repeated columns need levels
+        assert!(writer.write(&[1], Levels::Required).is_err());
+        // a column chunk must start with a row
+        assert!(writer
+            .write(
+                &[1],
+                Levels::Nested {
+                    rep: &[1],
+                    def: &[2]
+                }
+            )
+            .is_err());
+        Ok(())
+    }
+    #[test]
+    fn failed_write_is_not_applied() -> Result<()> {
+        let options = WriteOptions {
+            write_statistics: false,
+            version: Version::V1,
+            write_page_crc: false,
+        };
+        let mut writer =
+            NativeColumnWriter::<i32>::new(descriptor(2, 1), options, Default::default())?;
+
+        writer.write(
+            &[1],
+            Levels::Nested {
+                rep: &[0],
+                def: &[2],
+            },
+        )?;
+        // the third level is larger than the maximum definition level
+        assert!(writer
+            .write(
+                &[2, 3],
+                Levels::Nested {
+                    rep: &[0, 1, 0],
+                    def: &[2, 2, 3]
+                }
+            )
+            .is_err());
+        // there are fewer values than non-null levels
+        assert!(writer
+            .write(
+                &[2],
+                Levels::Nested {
+                    rep: &[0, 1],
+                    def: &[2, 2]
+                }
+            )
+            .is_err());
+        writer.write(
+            &[2],
+            Levels::Nested {
+                rep: &[1],
+                def: &[2],
+            },
+        )?;
+
+        let pages = data_pages(writer.finish()?);
+        assert_eq!(pages.len(), 1);
+        assert_eq!(pages[0].num_values(), 2);
+        let (rep_levels, def_levels, values) = split_buffer(&pages[0])?;
+        assert_eq!(decode_levels(rep_levels, 1, 2), [0, 1]);
+        assert_eq!(decode_levels(def_levels, 2, 2), [2, 2]);
+        assert_eq!(values, [1, 0, 0, 0, 2, 0, 0, 0]);
+        Ok(())
+    }
+}
@@ -0,0 +1,404 @@
+use parquet_format_safe::Statistics as ParquetStatistics;
+
+use crate::encoding::delta_bitpacked::{self, BlockLayout};
+use crate::encoding::{byte_stream_split, Encoding};
+use crate::error::{Error, Result};
+use crate::metadata::Descriptor;
+use crate::page::CompressedPage;
+use crate::schema::types::PhysicalType;
+use crate::statistics::{serialize_statistics, PrimitiveStatistics};
+use crate::types::{self, NativeType};
+use crate::write::WriteOptions;
+
+use super::{
+    compare_native, sort_order, unsupported_encoding, ColumnWriterOptions, Dictionary, Levels,
+    PageBuilder, Push, Values,
+};
+
+#[derive(Debug)]
+struct NativeValues<T: NativeType> {
+    values: Vec<T>,
+    dictionary: Option<Dictionary>,
+    delta_block_layout: BlockLayout,
+}
+
+fn to_i64<T: NativeType>(value: &T) -> i64 {
+    let bytes = value.to_le_bytes();
+    match T::TYPE {
+        PhysicalType::Int32 => types::decode::<i32>(bytes.as_ref()) as i64,
+        _ => types::decode::<i64>(bytes.as_ref()),
+    }
+}
+
+fn is_nan<T: NativeType>(value: &T) -> bool {
+    let bytes = value.to_le_bytes();
+    match T::TYPE {
+        PhysicalType::Float => types::decode::<f32>(bytes.as_ref()).is_nan(),
+        PhysicalType::Double => types::decode::<f64>(bytes.as_ref()).is_nan(),
+        _ => false,
+    }
+}
+
+impl<T: NativeType> Values for NativeValues<T> {
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self.values.as_slice())
+    }
+
+    fn statistics(&self, descriptor: &Descriptor, null_count: usize) -> ParquetStatistics {
+        let sort_order = sort_order(descriptor);
+        let compare = |x: &T, y: &T| compare_native(sort_order, x, y);
+        // SPEC: NaN must not be written to the min nor the max
+        let values = self.values.iter().filter(|x| !is_nan(*x));
+
+        serialize_statistics(&PrimitiveStatistics::<T> {
+            primitive_type: descriptor.primitive_type.clone(),
+            null_count: Some(null_count as i64),
+            distinct_count: None,
+            max_value: values.clone().max_by(|x, y| compare(x, y)).copied(),
+            min_value: values.min_by(|x, y| compare(x, y)).copied(),
+        })
+    }
+
+    fn encode(&mut self, encoding: Encoding, buffer: &mut Vec<u8>) -> Result<()> {
+        match encoding {
+            Encoding::Plain => self
+                .values
+                .iter()
+                .for_each(|x| buffer.extend_from_slice(x.to_le_bytes().as_ref())),
+            Encoding::ByteStreamSplit => byte_stream_split::encode(&self.values, buffer),
+            Encoding::DeltaBinaryPacked if T::TYPE == PhysicalType::Int32 => {
+                delta_bitpacked::encode_i32_with_layout(
+                    self.values.iter().map(|x| to_i64(x) as i32),
+                    buffer,
+                    self.delta_block_layout,
+                )
+            }
+            Encoding::DeltaBinaryPacked => delta_bitpacked::encode_with_layout(
+                self.values.iter().map(to_i64),
+                buffer,
+                self.delta_block_layout,
+            ),
+            Encoding::RleDictionary => self.dictionary.as_mut().unwrap().encode(buffer)?,
+            _ => unreachable!(),
+        }
+        self.values.clear();
+        Ok(())
+    }
+
+    fn dictionary(&mut self) -> Option<&mut Dictionary> {
+        self.dictionary.as_mut()
+    }
+}
+
+impl<T: NativeType> Push<T> for NativeValues<T> {
+    fn push(&mut self, value: T) {
+        if let Some(dictionary) = self.dictionary.as_mut() {
+            dictionary.push(value.to_le_bytes().as_ref());
+        }
+        self.values.push(value);
+    }
+}
+
+/// Writes values of a [`NativeType`] into the pages of a column chunk.
+///
+/// Supported encodings are `Plain`, `ByteStreamSplit` and, for `i32` and `i64`,
+/// `DeltaBinaryPacked`. Values can also be dictionary-encoded
+/// (see [`ColumnWriterOptions::max_dictionary_size`]).
+#[derive(Debug)]
+pub struct NativeColumnWriter<T: NativeType> {
+    builder: PageBuilder,
+    values: NativeValues<T>,
+}
+
+impl<T: NativeType> NativeColumnWriter<T> {
+    /// Returns a new [`NativeColumnWriter`] of the column described by `descriptor`.
+    /// # Errors
+    /// Errors if the physical type of the column is not `T` or if the encoding is not
+    /// supported for `T`.
+    pub fn new(
+        descriptor: Descriptor,
+        options: WriteOptions,
+        column_options: ColumnWriterOptions,
+    ) -> Result<Self> {
+        if descriptor.primitive_type.physical_type != T::TYPE {
+            return Err(Error::InvalidParameter(format!(
+                "The column has physical type {:?} but the writer writes {:?}",
+                descriptor.primitive_type.physical_type,
+                T::TYPE
+            )));
+        }
+        let is_supported = match column_options.encoding {
+            Encoding::Plain => true,
+            Encoding::ByteStreamSplit => T::TYPE != PhysicalType::Int96,
+            Encoding::DeltaBinaryPacked => {
+                matches!(T::TYPE, PhysicalType::Int32 | PhysicalType::Int64)
+            }
+            _ => false,
+        };
+        if !is_supported {
+            return Err(unsupported_encoding(column_options.encoding, &descriptor));
+        }
+        Ok(Self {
+            builder: PageBuilder::new(descriptor, options, column_options),
+            values: NativeValues {
+                values: vec![],
+                dictionary: column_options
+                    .max_dictionary_size
+                    .map(|size| Dictionary::new(size, false)),
+                delta_block_layout: column_options.delta_block_layout,
+            },
+        })
+    }
+
+    /// Writes `values` with `levels` to the column chunk, finishing pages as they fill up.
+    /// # Errors
+    /// Errors if the number of values does not match `levels` or if `levels` is not valid
+    /// for the column.
+    pub fn write(&mut self, values: &[T], levels: Levels) -> Result<()> {
+        self.builder
+            .write(&mut self.values, values.iter().copied(), levels)
+    }
+
+    /// Finishes the last page and returns all pages of the column chunk.
+    /// The pages can be passed to [`FileWriter`](crate::write::FileWriter) via
+    /// [`convert`](crate::fallible_streaming_iterator::convert).
+    pub fn finish(mut self) -> Result<Vec<CompressedPage>> {
+        self.builder.finish(&mut self.values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::page::{split_buffer, Page};
+    use crate::read::decompress;
+    use crate::schema::types::{IntegerType, PrimitiveLogicalType, PrimitiveType};
+    use crate::write::Version;
+
+    fn descriptor(physical_type: PhysicalType) -> Descriptor {
+        Descriptor {
+            primitive_type: PrimitiveType::from_physical("a".to_string(), physical_type),
+            max_def_level: 1,
+            max_rep_level: 0,
+        }
+    }
+
+    #[test]
+    fn statistics() -> Result<()> {
+        let options = WriteOptions {
+            write_statistics: true,
+            version: Version::V2,
+            write_page_crc: false,
+        };
+        let descriptor = descriptor(PhysicalType::Int64);
+        let mut writer =
+            NativeColumnWriter::<i64>::new(descriptor.clone(), options, Default::default())?;
+        writer.write(&[3, -1, 2], Levels::Optional(&[true, false, true, true]))?;
+        let mut pages = writer.finish()?;
+        assert_eq!(pages.len(), 1);
+
+        let page = match decompress(pages.pop().unwrap(), &mut vec![])? {
+            Page::Data(page) => page,
+            _ => unreachable!(),
+        };
+        let statistics = page.statistics().unwrap()?;
+        let expected = PrimitiveStatistics::<i64> {
+            primitive_type: descriptor.primitive_type,
+            null_count: Some(1),
+            distinct_count: None,
+            max_value: Some(3),
+            min_value: Some(-1),
+        };
+        assert_eq!(
+            statistics
+                .as_any()
+                .downcast_ref::<PrimitiveStatistics<i64>>()
+                .unwrap(),
+            &expected
+        );
+        Ok(())
+    }
+
+    /// Returns the min and max of the statistics of the page of `values` written to `descriptor`
+    fn min_max<T: NativeType>(descriptor: Descriptor, values: &[T]) -> Result<(T, T)> {
+        let options = WriteOptions {
+            write_statistics: true,
+            version: Version::V1,
+            write_page_crc: false,
+        };
+        let mut writer = NativeColumnWriter::<T>::new(descriptor, options, Default::default())?;
+        writer.write(values, Levels::Required)?;
+        let page = match decompress(writer.finish()?.pop().unwrap(), &mut vec![])? {
+            Page::Data(page) => page,
+            _ => unreachable!(),
+        };
+        let statistics = page.statistics().unwrap()?;
+        let statistics = statistics
+            .as_any()
+            .downcast_ref::<PrimitiveStatistics<T>>()
+            .unwrap();
+        Ok((statistics.min_value.unwrap(), statistics.max_value.unwrap()))
+    }
+
+    #[test]
+    fn nan_statistics() -> Result<()> {
+        let descriptor = descriptor(PhysicalType::Double);
+        let (min, max) = min_max(descriptor.clone(), &[1.0f64, f64::NAN, 0.5])?;
+        assert_eq!((min, max), (0.5, 1.0));
+        let (min, max) = min_max(descriptor, &[f64::NAN, 2.0, -1.0, f64::NAN])?;
+        assert_eq!((min, max), (-1.0, 2.0));
+        Ok(())
+    }
+
+    #[test]
+    fn unsigned_statistics() -> Result<()> {
+        let mut descriptor = descriptor(PhysicalType::Int32);
+        descriptor.primitive_type.logical_type =
+            Some(PrimitiveLogicalType::Integer(IntegerType::UInt32));
+        // -1 is u32::MAX
+        let (min, max) = min_max(descriptor, &[1i32, -1, 2])?;
+        assert_eq!((min, max), (1, -1));
+        Ok(())
+    }
+
+    #[test]
+    fn delta_binary_packed() -> Result<()> {
+        let options = WriteOptions {
+            write_statistics: false,
+            version: Version::V1,
+            write_page_crc: false,
+        };
+        let column_options = ColumnWriterOptions {
+            encoding: Encoding::DeltaBinaryPacked,
+            ..Default::default()
+        };
+        let mut writer = NativeColumnWriter::<i32>::new(
+            descriptor(PhysicalType::Int32),
+            options,
+            column_options,
+        )?;
+        let values = (0..300).collect::<Vec<i32>>();
+        writer.write(&values, Levels::Required)?;
+        let mut pages = writer.finish()?;
+
+        let page = match decompress(pages.pop().unwrap(), &mut vec![])? {
+            Page::Data(page) => page,
+            _ => unreachable!(),
+        };
+        assert_eq!(page.encoding(), Encoding::DeltaBinaryPacked);
+        let (_, _, buffer) = split_buffer(&page)?;
+        let result = delta_bitpacked::Decoder::new(buffer)
+            .map(|x| x as i32)
+            .collect::<Vec<_>>();
+        assert_eq!(result, values);
+        Ok(())
+    }
+
+    #[test]
+    fn delta_binary_packed_i32_range() -> Result<()> {
+        let options = WriteOptions {
+            write_statistics: false,
+            version: Version::V1,
+            write_page_crc: false,
+        };
+        let column_options = ColumnWriterOptions {
+            encoding: Encoding::DeltaBinaryPacked,
+            ..Default::default()
+        };
+        let mut writer = NativeColumnWriter::<i32>::new(
+            descriptor(PhysicalType::Int32),
+            options,
+            column_options,
+        )?;
+        let values = (0..128)
+            .map(|x| [i32::MIN, i32::MAX, 0, -1][x % 4])
+            .collect::<Vec<_>>();
+        writer.write(&values, Levels::Required)?;
+        let page = match decompress(writer.finish()?.pop().unwrap(), &mut vec![])? {
+            Page::Data(page) => page,
+            _ => unreachable!(),
+        };
+        let (_, _, buffer) = split_buffer(&page)?;
+
+        // header: block size (2 bytes), mini-blocks, elements (2 bytes), first value (5 bytes)
+        // block1: min_delta (5 bytes), 4 bitwidths
+        let widths = &buffer[15..19];
+        assert!(widths.iter().all(|x| *x <= 32), "{:?}", widths);
+        let result = delta_bitpacked::Decoder::new(buffer)
+            .map(|x| x as i32)
+            .collect::<Vec<_>>();
+        assert_eq!(result, values);
+        Ok(())
+    }
+
+    #[test]
+    fn delta_block_layout() -> Result<()> {
+        let options = WriteOptions {
+            write_statistics: false,
+            version: Version::V1,
+            write_page_crc: false,
+        };
+        // timestamps increasing by about a second, with a gap every 100 values
+        let values = (0..1000i64)
+            .map(|x| x * 1_000_000_000 + x % 13 + (x / 100) * 3_600_000_000_000)
+            .collect::<Vec<_>>();
+
+        let mut sizes = vec![];
+        for layout in [BlockLayout::try_new(128, 1)?, BlockLayout::default()] {
+            let column_options = ColumnWriterOptions {
+                encoding: Encoding::DeltaBinaryPacked,
+                delta_block_layout: layout,
+                ..Default::default()
+            };
+            let mut writer = NativeColumnWriter::<i64>::new(
+                descriptor(PhysicalType::Int64),
+                options,
+                column_options,
+            )?;
+            writer.write(&values, Levels::Required)?;
+            let page = match decompress(writer.finish()?.pop().unwrap(), &mut vec![])? {
+                Page::Data(page) => page,
+                _ => unreachable!(),
+            };
+            let (_, _, buffer) = split_buffer(&page)?;
+            assert_eq!(
+                delta_bitpacked::Decoder::new(buffer).collect::<Vec<_>>(),
+                values
+            );
+            sizes.push(buffer.len());
+        }
+        // the gaps only widen the mini-blocks they are in
+        assert!(sizes[1] < sizes[0]);
+        Ok(())
+    }
+
+    #[test]
+    fn unsupported() {
+        let options = WriteOptions {
+            write_statistics: false,
+            version: Version::V1,
+            write_page_crc: false,
+        };
+        let column_options = ColumnWriterOptions {
+            encoding: Encoding::DeltaBinaryPacked,
+            ..Default::default()
+        };
+        assert!(NativeColumnWriter::<f64>::new(
+            descriptor(PhysicalType::Double),
+            options,
+            column_options
+        )
+        .is_err());
+        assert!(NativeColumnWriter::<f32>::new(
+            descriptor(PhysicalType::Double),
+            options,
+            Default::default()
+        )
+        .is_err());
+    }
+}
@@ -2,7 +2,7 @@ use std::io::Write;
 
 use parquet_format_safe::thrift::protocol::TCompactOutputProtocol;
 use parquet_format_safe::thrift::protocol::TOutputProtocol;
-use parquet_format_safe::RowGroup;
+use parquet_format_safe::{ColumnOrder, RowGroup, TypeDefinedOrder};
 
 use crate::metadata::ThriftFileMetaData;
 use crate::{
@@ -11,16 +11,63 @@ use crate::{
     FOOTER_SIZE, PARQUET_MAGIC,
 };
 
+use super::bloom_filter::write_bloom_filter;
 use super::indexes::{write_column_index, write_offset_index};
 use super::page::PageWriteSpec;
 use super::{row_group::write_row_group, RowGroupIter, WriteOptions};
+use super::{ColumnEncryptor, FileEncryptor};
 
 pub use crate::metadata::KeyValue;
 use crate::write::State;
 
-pub(super) fn start_file<W: Write>(writer: &mut W) -> Result<u64> {
-    writer.write_all(&PARQUET_MAGIC)?;
-    Ok(PARQUET_MAGIC.len() as u64)
+/// The [`ColumnOrder`] of every column of `schema`: the statistics written by this crate are
+/// in the order defined by the type of the column.
+pub(super) fn column_orders(schema: &SchemaDescriptor) -> Vec<ColumnOrder> {
+    schema
+        .columns()
+        .iter()
+        .map(|_| ColumnOrder::TYPEORDER(TypeDefinedOrder {}))
+        .collect()
+}
+
+pub(super) fn set_bloom_filter(
+    bloom_filters: &mut [Vec<Option<Vec<u8>>>],
+    row_group: usize,
+    column: usize,
+    bitset: Vec<u8>,
+) -> Result<()> {
+    let slot = bloom_filters
+        .get_mut(row_group)
+        .ok_or_else(|| {
+            Error::General(format!(
+                "Row group {} has not been written to the file",
+                row_group
+            ))
+        })?
+        .get_mut(column)
+        .ok_or_else(|| {
+            Error::General(format!("Row group {} has no column {}", row_group, column))
+        })?;
+    *slot = Some(bitset);
+    Ok(())
+}
+
+/// Returns the encryptor of the column chunk `column` of the row group `row_group`, if it is
+/// encrypted.
+pub(super) fn column_encryptor(
+    encryptor: Option<&FileEncryptor>,
+    row_group: usize,
+    column: usize,
+) -> Result<Option<ColumnEncryptor>> {
+    Ok(encryptor
+        .map(|encryptor| encryptor.column(row_group, column))
+        .transpose()?
+        .flatten())
+}
+
+pub(super) fn start_file<W: Write>(writer: &mut W, magic: &[u8; 4]) -> Result<u64> {
+    writer.write_all(magic)?;
+    Ok(magic.len() as u64)
 }
 
 pub(super) fn end_file<W: Write>(mut writer: &mut W, metadata: &ThriftFileMetaData) -> Result<u64> {
@@ -41,6 +88,21 @@ pub(super) fn end_file<W: Write>(mut writer: &mut W, metadata: &ThriftFileMetaDa
     Ok(metadata_len as u64 + FOOTER_SIZE)
 }
 
+/// Writes the footer of an encrypted file, whose column metadata and footer are encrypted
+/// (or signed) by `encryptor`.
+fn end_encrypted_file<W: Write>(
+    writer: &mut W,
+    metadata: &ThriftFileMetaData,
+    encryptor: &FileEncryptor,
+) -> Result<u64> {
+    let (footer, magic) = encryptor.footer(metadata)?;
+    let footer_len: i32 = footer.len().try_into()?;
+    writer.write_all(&footer)?;
+    writer.write_all(&footer_len.to_le_bytes())?;
+    writer.write_all(&magic)?;
+    Ok(footer.len() as u64 + FOOTER_SIZE)
+}
+
 /// An interface to write a parquet file.
 /// Use `start` to write the header, `write` to write a row group,
 /// and `end` to write the footer.
@@ -53,6 +115,8 @@ pub struct FileWriter<W: Write> {
     offset: u64,
     row_groups: Vec<RowGroup>,
     page_specs: Vec<Vec<Vec<PageWriteSpec>>>,
+    bloom_filters: Vec<Vec<Option<Vec<u8>>>>,
+    encryptor: Option<FileEncryptor>,
     /// Used to store the current state for writing the file
     state: State,
     // when the file is written, metadata becomes available
@@ -70,7 +134,7 @@ pub fn write_metadata_sidecar<W: Write>(
     writer: &mut W,
     metadata: &ThriftFileMetaData,
 ) -> Result<u64> {
-    let mut len = start_file(writer)?;
+    let mut len = start_file(writer, &PARQUET_MAGIC)?;
     len += end_file(writer, metadata)?;
     Ok(len)
 }
@@ -112,11 +176,32 @@ impl<W: Write> FileWriter<W> {
             offset: 0,
             row_groups: vec![],
             page_specs: vec![],
+            bloom_filters: vec![],
+            encryptor: None,
             state: State::Initialised,
             metadata: None,
         }
     }
 
+    /// Returns a new [`FileWriter`] that encrypts the file as declared by `properties`.
+    /// # Errors
+    /// Errors if the keys of the file can't be provided or are not AES keys.
+    #[cfg(feature = "encryption")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "encryption")))]
+    pub fn with_encryption(
+        writer: W,
+        schema: SchemaDescriptor,
+        options: WriteOptions,
+        created_by: Option<String>,
+        properties: &crate::encryption::FileEncryptionProperties,
+    ) -> Result<Self> {
+        let encryptor = FileEncryptor::try_new(properties, &schema)?;
+        Ok(Self {
+            encryptor: Some(encryptor),
+            ..Self::new(writer, schema, options, created_by)
+        })
+    }
+
     /// Writes the header of the file.
     ///
     /// This is automatically called by [`Self::write`] if not called following [`Self::new`].
@@ -125,7 +210,11 @@ impl<W: Write> FileWriter<W> {
     /// Returns an error if data has been written to the file.
     fn start(&mut self) -> Result<()> {
         if self.offset == 0 {
-            self.offset = start_file(&mut self.writer)? as u64;
+            let magic = self
+                .encryptor
+                .as_ref()
+                .map_or(PARQUET_MAGIC, FileEncryptor::magic);
+            self.offset = start_file(&mut self.writer, &magic)? as u64;
             self.state = State::Started;
             Ok(())
         } else {
@@ -151,13 +240,37 @@ impl<W: Write> FileWriter<W> {
             self.schema.columns(),
             row_group,
             ordinal,
+            self.options,
+            self.encryptor.as_ref(),
         )?;
         self.offset += size;
+        self.bloom_filters.push(vec![None; group.columns.len()]);
         self.row_groups.push(group);
         self.page_specs.push(specs);
         Ok(())
     }
 
+    /// Assigns a split-block bloom filter `bitset` to the column chunk `column` of the
+    /// (already written) row group `row_group`.
+    ///
+    /// The bloom filters are written by [`Self::end`]. See [`crate::bloom_filter`] on how
+    /// to size and populate `bitset`.
+    /// # Errors
+    /// Errors if the row group or column do not exist or if the file has already ended.
+    pub fn set_bloom_filter(
+        &mut self,
+        row_group: usize,
+        column: usize,
+        bitset: Vec<u8>,
+    ) -> Result<()> {
+        if self.state == State::Finished {
+            return Err(Error::General(
+                "Bloom filters cannot be set after the file has ended".to_string(),
+            ));
+        }
+        set_bloom_filter(&mut self.bloom_filters, row_group, column, bitset)
+    }
+
     /// Writes the footer of the parquet file. Returns the total size of the file and the
     /// underlying writer.
     pub fn end(&mut self, key_value_metadata: Option<Vec<KeyValue>>) -> Result<u64> {
@@ -171,22 +284,60 @@ impl<W: Write> FileWriter<W> {
         // compute file stats
         let num_rows = self.row_groups.iter().map(|group| group.num_rows).sum();
 
+        // write bloom filters
+        self.row_groups
+            .iter_mut()
+            .zip(self.bloom_filters.iter())
+            .enumerate()
+            .try_for_each(|(i, (group, bloom_filters))| {
+                group
+                    .columns
+                    .iter_mut()
+                    .zip(bloom_filters.iter())
+                    .enumerate()
+                    .filter_map(|(j, (column, bitset))| {
+                        bitset.as_ref().map(|bitset| (j, column, bitset))
+                    })
+                    .try_for_each(|(j, column, bitset)| {
+                        let encryptor = column_encryptor(self.encryptor.as_ref(), i, j)?;
+                        let offset = self.offset;
+                        self.offset +=
+                            write_bloom_filter(&mut self.writer, bitset, encryptor.as_ref())?;
+                        column.meta_data.as_mut().unwrap().bloom_filter_offset =
+                            Some(offset as i64);
+                        Result::Ok(())
+                    })?;
+                Result::Ok(())
+            })?;
+
         if self.options.write_statistics {
             // write column indexes (require page statistics)
             self.row_groups
                 .iter_mut()
                 .zip(self.page_specs.iter())
-                .try_for_each(|(group, pages)| {
-                    group.columns.iter_mut().zip(pages.iter()).try_for_each(
-                        |(column, pages)| {
+                .enumerate()
+                .try_for_each(|(i, (group, pages))| {
+                    group
+                        .columns
+                        .iter_mut()
+                        .zip(pages.iter())
+                        .enumerate()
+                        .try_for_each(|(j, (column, pages))| {
+                            let encryptor = column_encryptor(self.encryptor.as_ref(), i, j)?;
+                            let primitive_type =
+                                &self.schema.columns()[j].descriptor.primitive_type;
                             let offset = self.offset;
                             column.column_index_offset = Some(offset as i64);
-                            self.offset += write_column_index(&mut self.writer, pages)?;
+                            self.offset += write_column_index(
+                                &mut self.writer,
+                                pages,
+                                primitive_type,
+                                encryptor.as_ref(),
+                            )?;
                             let length = self.offset - offset;
                             column.column_index_length = Some(length as i32);
                             Result::Ok(())
-                        },
-                    )?;
+                        })?;
                     Result::Ok(())
                 })?;
         };
@@ -195,15 +346,19 @@ impl<W: Write> FileWriter<W> {
         self.row_groups
             .iter_mut()
             .zip(self.page_specs.iter())
-            .try_for_each(|(group, pages)| {
+            .enumerate()
+            .try_for_each(|(i, (group, pages))| {
                 group
                     .columns
                     .iter_mut()
                     .zip(pages.iter())
-                    .try_for_each(|(column, pages)| {
+                    .enumerate()
+                    .try_for_each(|(j, (column, pages))| {
+                        let encryptor = column_encryptor(self.encryptor.as_ref(), i, j)?;
                         let offset = self.offset;
                         column.offset_index_offset = Some(offset as i64);
-                        self.offset += write_offset_index(&mut self.writer, pages)?;
+                        self.offset +=
+                            write_offset_index(&mut self.writer, pages, encryptor.as_ref())?;
                         column.offset_index_length = Some((self.offset - offset) as i32);
                         Result::Ok(())
                     })?;
@@ -217,12 +372,15 @@ impl<W: Write> FileWriter<W> {
             self.row_groups.clone(),
             key_value_metadata,
             self.created_by.clone(),
-            None,
+            Some(column_orders(&self.schema)),
             None,
             None,
         );
 
-        let len = end_file(&mut self.writer, &metadata)?;
+        let len = match &self.encryptor {
+            Some(encryptor) => end_encrypted_file(&mut self.writer, &metadata, encryptor)?,
+            None => end_file(&mut self.writer, &metadata)?,
+        };
         self.state = State::Finished;
         self.metadata = Some(metadata);
         Ok(self.offset + len)
@@ -266,7 +424,7 @@ mod tests {
         let mut writer = Cursor::new(vec![]);
 
         // write the file
-        start_file(&mut writer)?;
+        start_file(&mut writer, &PARQUET_MAGIC)?;
         end_file(&mut writer, &metadata.into_thrift())?;
 
         let a = writer.into_inner();
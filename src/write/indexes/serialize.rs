@@ -1,3 +1,5 @@
+use std::cmp::Ordering;
+
 use parquet_format_safe::BoundaryOrder;
 use parquet_format_safe::ColumnIndex;
 use parquet_format_safe::OffsetIndex;
@@ -5,11 +7,17 @@ use parquet_format_safe::PageLocation;
 
 use crate::error::{Error, Result};
 pub use crate::metadata::KeyValue;
+use crate::metadata::{get_sort_order, SortOrder};
+use crate::schema::types::{PhysicalType, PrimitiveType};
 use crate::statistics::serialize_statistics;
+use crate::types::{ord_binary, ord_signed_binary, NativeType};
 
 use crate::write::page::{is_data_page, PageWriteSpec};
 
-pub fn serialize_column_index(pages: &[PageWriteSpec]) -> Result<ColumnIndex> {
+pub fn serialize_column_index(
+    pages: &[PageWriteSpec],
+    primitive_type: &PrimitiveType,
+) -> Result<ColumnIndex> {
     let mut null_pages = Vec::with_capacity(pages.len());
     let mut min_values = Vec::with_capacity(pages.len());
     let mut max_values = Vec::with_capacity(pages.len());
@@ -48,15 +56,107 @@ pub fn serialize_column_index(pages: &[PageWriteSpec]) -> Result<ColumnIndex> {
                 ))
             }
         })?;
+    let boundary_order = boundary_order(primitive_type, &null_pages, &min_values, &max_values);
     Ok(ColumnIndex {
         null_pages,
         min_values,
         max_values,
-        boundary_order: BoundaryOrder::UNORDERED,
+        boundary_order,
         null_counts: Some(null_counts),
     })
 }
 
+/// Compares two plain-encoded values of `physical_type` in `sort_order`, returning `None`
+/// when they are not comparable (e.g. `NaN` or an undefined order).
+fn compare(
+    physical_type: PhysicalType,
+    sort_order: SortOrder,
+    lhs: &[u8],
+    rhs: &[u8],
+) -> Option<Ordering> {
+    fn decode<T: NativeType>(lhs: &[u8], rhs: &[u8]) -> Option<(T, T)> {
+        Some((
+            T::from_le_bytes(lhs.try_into().ok()?),
+            T::from_le_bytes(rhs.try_into().ok()?),
+        ))
+    }
+
+    match (physical_type, sort_order) {
+        (_, SortOrder::Undefined) | (PhysicalType::Int96, _) => None,
+        (PhysicalType::Boolean, _) => Some(lhs.cmp(rhs)),
+        (PhysicalType::Int32, SortOrder::Signed) => {
+            decode::<i32>(lhs, rhs).map(|(lhs, rhs)| lhs.ord(&rhs))
+        }
+        (PhysicalType::Int32, SortOrder::Unsigned) => {
+            decode::<i32>(lhs, rhs).map(|(lhs, rhs)| (lhs as u32).cmp(&(rhs as u32)))
+        }
+        (PhysicalType::Int64, SortOrder::Signed) => {
+            decode::<i64>(lhs, rhs).map(|(lhs, rhs)| lhs.ord(&rhs))
+        }
+        (PhysicalType::Int64, SortOrder::Unsigned) => {
+            decode::<i64>(lhs, rhs).map(|(lhs, rhs)| (lhs as u64).cmp(&(rhs as u64)))
+        }
+        // `NativeType::ord` considers `NaN` equal to any value
+        (PhysicalType::Float, _) => decode::<f32>(lhs, rhs)
+            .filter(|(lhs, rhs)| !lhs.is_nan() && !rhs.is_nan())
+            .map(|(lhs, rhs)| lhs.ord(&rhs)),
+        (PhysicalType::Double, _) => decode::<f64>(lhs, rhs)
+            .filter(|(lhs, rhs)| !lhs.is_nan() && !rhs.is_nan())
+            .map(|(lhs, rhs)| lhs.ord(&rhs)),
+        (PhysicalType::ByteArray | PhysicalType::FixedLenByteArray(_), SortOrder::Signed) => {
+            Some(ord_signed_binary(lhs, rhs))
+        }
+        (PhysicalType::ByteArray | PhysicalType::FixedLenByteArray(_), SortOrder::Unsigned) => {
+            Some(ord_binary(lhs, rhs))
+        }
+    }
+}
+
+/// Returns whether the min and max values of the (non-null) pages are both ascending or both
+/// descending, in the order of the column's type. Pages whose values are all equal are
+/// ascending.
+fn boundary_order(
+    primitive_type: &PrimitiveType,
+    null_pages: &[bool],
+    min_values: &[Vec<u8>],
+    max_values: &[Vec<u8>],
+) -> BoundaryOrder {
+    let sort_order = get_sort_order(
+        &primitive_type.logical_type,
+        &primitive_type.converted_type,
+        &primitive_type.physical_type,
+    );
+    let compare =
+        |lhs: &[u8], rhs: &[u8]| compare(primitive_type.physical_type, sort_order, lhs, rhs);
+
+    let mut ascending = true;
+    let mut descending = true;
+    let mut previous: Option<(&[u8], &[u8])> = None;
+    for ((min, max), _) in min_values
+        .iter()
+        .zip(max_values.iter())
+        .zip(null_pages.iter())
+        .filter(|(_, is_null)| !**is_null)
+    {
+        if let Some((previous_min, previous_max)) = previous {
+            let (min, max) = match (compare(previous_min, min), compare(previous_max, max)) {
+                (Some(min), Some(max)) => (min, max),
+                _ => return BoundaryOrder::UNORDERED,
+            };
+            ascending &= min.is_le() && max.is_le();
+            descending &= min.is_ge() && max.is_ge();
+        }
+        previous = Some((min, max));
+    }
+    if ascending {
+        BoundaryOrder::ASCENDING
+    } else if descending {
+        BoundaryOrder::DESCENDING
+    } else {
+        BoundaryOrder::UNORDERED
+    }
+}
+
 pub fn serialize_offset_index(pages: &[PageWriteSpec]) -> Result<OffsetIndex> {
     let mut first_row_index = 0;
     let page_locations = pages
@@ -81,3 +181,100 @@ pub fn serialize_offset_index(pages: &[PageWriteSpec]) -> Result<OffsetIndex> {
 
     Ok(OffsetIndex { page_locations })
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::schema::types::PrimitiveLogicalType;
+
+    use super::*;
+
+    fn order(
+        primitive_type: &PrimitiveType,
+        pages: &[Option<(Vec<u8>, Vec<u8>)>],
+    ) -> BoundaryOrder {
+        let null_pages = pages.iter().map(|x| x.is_none()).collect::<Vec<_>>();
+        let (min_values, max_values) = pages
+            .iter()
+            .map(|x| x.clone().unwrap_or((vec![0], vec![0])))
+            .unzip::<_, _, Vec<_>, Vec<_>>();
+        boundary_order(primitive_type, &null_pages, &min_values, &max_values)
+    }
+
+    fn int32(pages: &[Option<(i32, i32)>]) -> Vec<Option<(Vec<u8>, Vec<u8>)>> {
+        pages
+            .iter()
+            .map(|x| x.map(|(min, max)| (min.to_le_bytes().to_vec(), max.to_le_bytes().to_vec())))
+            .collect()
+    }
+
+    #[test]
+    fn native() {
+        let signed = PrimitiveType::from_physical("a".to_string(), PhysicalType::Int32);
+        let mut unsigned = signed.clone();
+        unsigned.logical_type = Some(PrimitiveLogicalType::Integer(
+            crate::schema::types::IntegerType::UInt32,
+        ));
+
+        let ascending = int32(&[Some((-5, 0)), None, Some((0, 3)), Some((4, 4))]);
+        assert_eq!(order(&signed, &ascending), BoundaryOrder::ASCENDING);
+        // -5 is the largest unsigned value
+        assert_eq!(order(&unsigned, &ascending), BoundaryOrder::UNORDERED);
+
+        let descending = int32(&[Some((4, 9)), Some((4, 4)), None, Some((-1, 2))]);
+        assert_eq!(order(&signed, &descending), BoundaryOrder::DESCENDING);
+
+        let unordered = int32(&[Some((0, 9)), Some((1, 2))]);
+        assert_eq!(order(&signed, &unordered), BoundaryOrder::UNORDERED);
+
+        // equal (and no) pages are ascending
+        assert_eq!(
+            order(&signed, &int32(&[Some((1, 1)), Some((1, 1))])),
+            BoundaryOrder::ASCENDING
+        );
+        assert_eq!(order(&signed, &int32(&[None])), BoundaryOrder::ASCENDING);
+    }
+
+    #[test]
+    fn floats() {
+        let primitive_type = PrimitiveType::from_physical("a".to_string(), PhysicalType::Double);
+        let page =
+            |min: f64, max: f64| Some((min.to_le_bytes().to_vec(), max.to_le_bytes().to_vec()));
+        assert_eq!(
+            order(&primitive_type, &[page(-1.0, 0.5), page(0.5, 2.0)]),
+            BoundaryOrder::ASCENDING
+        );
+        assert_eq!(
+            order(&primitive_type, &[page(-1.0, 0.5), page(f64::NAN, 2.0)]),
+            BoundaryOrder::UNORDERED
+        );
+    }
+
+    #[test]
+    fn binary() {
+        let string = PrimitiveType::from_physical("a".to_string(), PhysicalType::ByteArray);
+        let page = |min: &[u8], max: &[u8]| Some((min.to_vec(), max.to_vec()));
+        // prefixes are smaller than the values they prefix
+        assert_eq!(
+            order(
+                &string,
+                &[page(b"a", b"ab"), page(b"ab", b"abc"), page(b"b", b"c")]
+            ),
+            BoundaryOrder::ASCENDING
+        );
+        assert_eq!(
+            order(&string, &[page(b"ab", b"abc"), page(b"a", b"ab")]),
+            BoundaryOrder::DESCENDING
+        );
+
+        // decimals are compared as signed integers
+        let mut decimal = string;
+        decimal.logical_type = Some(PrimitiveLogicalType::Decimal(10, 2));
+        assert_eq!(
+            order(
+                &decimal,
+                &[page(&[0x80], &[0xff]), page(&[0x00], &[0x01, 0x00])]
+            ),
+            BoundaryOrder::ASCENDING
+        );
+    }
+}
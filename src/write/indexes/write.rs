@@ -1,7 +1,7 @@
 use std::io::Write;
 
 #[cfg(feature = "async")]
-use futures::AsyncWrite;
+use futures::{AsyncWrite, AsyncWriteExt};
 #[cfg(feature = "async")]
 use parquet_format_safe::thrift::protocol::TCompactOutputStreamProtocol;
 
@@ -9,13 +9,27 @@ use parquet_format_safe::thrift::protocol::TCompactOutputProtocol;
 
 use crate::error::Result;
 pub use crate::metadata::KeyValue;
+use crate::schema::types::PrimitiveType;
 
 use crate::write::page::PageWriteSpec;
+use crate::write::ColumnEncryptor;
 
 use super::serialize::{serialize_column_index, serialize_offset_index};
 
-pub fn write_column_index<W: Write>(writer: &mut W, pages: &[PageWriteSpec]) -> Result<u64> {
-    let index = serialize_column_index(pages)?;
+pub fn write_column_index<W: Write>(
+    writer: &mut W,
+    pages: &[PageWriteSpec],
+    primitive_type: &PrimitiveType,
+    encryptor: Option<&ColumnEncryptor>,
+) -> Result<u64> {
+    let index = serialize_column_index(pages, primitive_type)?;
+    if let Some(encryptor) = encryptor {
+        let mut buffer = vec![];
+        index.write_to_out_protocol(&mut TCompactOutputProtocol::new(&mut buffer))?;
+        let module = encryptor.encrypt_column_index(&buffer)?;
+        writer.write_all(&module)?;
+        return Ok(module.len() as u64);
+    }
     let mut protocol = TCompactOutputProtocol::new(writer);
     Ok(index.write_to_out_protocol(&mut protocol)? as u64)
 }
@@ -25,14 +39,34 @@ pub fn write_column_index<W: Write>(writer: &mut W, pages: &[PageWriteSpec]) ->
 pub async fn write_column_index_async<W: AsyncWrite + Unpin + Send>(
     writer: &mut W,
     pages: &[PageWriteSpec],
+    primitive_type: &PrimitiveType,
+    encryptor: Option<&ColumnEncryptor>,
 ) -> Result<u64> {
-    let index = serialize_column_index(pages)?;
+    let index = serialize_column_index(pages, primitive_type)?;
+    if let Some(encryptor) = encryptor {
+        let mut buffer = vec![];
+        index.write_to_out_protocol(&mut TCompactOutputProtocol::new(&mut buffer))?;
+        let module = encryptor.encrypt_column_index(&buffer)?;
+        writer.write_all(&module).await?;
+        return Ok(module.len() as u64);
+    }
     let mut protocol = TCompactOutputStreamProtocol::new(writer);
     Ok(index.write_to_out_stream_protocol(&mut protocol).await? as u64)
 }
 
-pub fn write_offset_index<W: Write>(writer: &mut W, pages: &[PageWriteSpec]) -> Result<u64> {
+pub fn write_offset_index<W: Write>(
+    writer: &mut W,
+    pages: &[PageWriteSpec],
+    encryptor: Option<&ColumnEncryptor>,
+) -> Result<u64> {
     let index = serialize_offset_index(pages)?;
+    if let Some(encryptor) = encryptor {
+        let mut buffer = vec![];
+        index.write_to_out_protocol(&mut TCompactOutputProtocol::new(&mut buffer))?;
+        let module = encryptor.encrypt_offset_index(&buffer)?;
+        writer.write_all(&module)?;
+        return Ok(module.len() as u64);
+    }
     let mut protocol = TCompactOutputProtocol::new(&mut *writer);
     Ok(index.write_to_out_protocol(&mut protocol)? as u64)
 }
@@ -42,8 +76,16 @@ pub fn write_offset_index<W: Write>(writer: &mut W, pages: &[PageWriteSpec]) ->
 pub async fn write_offset_index_async<W: AsyncWrite + Unpin + Send>(
     writer: &mut W,
     pages: &[PageWriteSpec],
+    encryptor: Option<&ColumnEncryptor>,
 ) -> Result<u64> {
     let index = serialize_offset_index(pages)?;
+    if let Some(encryptor) = encryptor {
+        let mut buffer = vec![];
+        index.write_to_out_protocol(&mut TCompactOutputProtocol::new(&mut buffer))?;
+        let module = encryptor.encrypt_offset_index(&buffer)?;
+        writer.write_all(&module).await?;
+        return Ok(module.len() as u64);
+    }
     let mut protocol = TCompactOutputStreamProtocol::new(&mut *writer);
     Ok(index.write_to_out_stream_protocol(&mut protocol).await? as u64)
 }
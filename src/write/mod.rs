@@ -1,4 +1,6 @@
+mod bloom_filter;
 mod column_chunk;
+mod column_writer;
 mod compression;
 mod file;
 mod indexes;
@@ -6,6 +8,13 @@ pub(crate) mod page;
 mod row_group;
 pub(self) mod statistics;
 
+#[cfg(feature = "encryption")]
+use crate::encryption::{ColumnEncryptor, FileEncryptor, PageEncryptor};
+#[cfg(not(feature = "encryption"))]
+mod no_encryption;
+#[cfg(not(feature = "encryption"))]
+use no_encryption::{ColumnEncryptor, FileEncryptor, PageEncryptor};
+
 #[cfg(feature = "async")]
 mod stream;
 #[cfg(feature = "async")]
@@ -17,6 +26,11 @@ pub use dyn_iter::{DynIter, DynStreamingIterator};
 
 pub use compression::{compress, Compressor};
 
+pub use column_writer::{
+    BinaryColumnWriter, BooleanColumnWriter, ColumnWriterOptions, FixedLenColumnWriter, Levels,
+    NativeColumnWriter,
+};
+
 pub use file::{write_metadata_sidecar, FileWriter};
 
 pub use row_group::ColumnOffsetsMetadata;
@@ -27,12 +41,29 @@ pub type RowGroupIter<'a, E> =
     DynIter<'a, std::result::Result<DynStreamingIterator<'a, CompressedPage, E>, E>>;
 
 /// Write options of different interfaces on this crate
+///
+/// New options may be added to this struct; construct it with `..Default::default()` to
+/// remain compatible with them.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct WriteOptions {
     /// Whether to write statistics, including indexes
     pub write_statistics: bool,
     /// Which Parquet version to use
     pub version: Version,
+    /// Whether to write the CRC32 checksum of every page, computed over its data as written to
+    /// the file, i.e. compressed and, if the page is encrypted, encrypted
+    pub write_page_crc: bool,
+}
+
+impl Default for WriteOptions {
+    /// Writes statistics with [`Version::V1`] and no page checksums.
+    fn default() -> Self {
+        Self {
+            write_statistics: true,
+            version: Version::V1,
+            write_page_crc: false,
+        }
+    }
 }
 
 /// The parquet version to use
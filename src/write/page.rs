@@ -17,6 +17,8 @@ use crate::page::{
 };
 use crate::statistics::Statistics;
 
+use super::{PageEncryptor, WriteOptions};
+
 pub(crate) fn is_data_page(page: &PageWriteSpec) -> bool {
     page.header.type_ == PageType::DATA_PAGE || page.header.type_ == PageType::DATA_PAGE_V2
 }
@@ -39,6 +41,11 @@ fn maybe_bytes(uncompressed: usize, compressed: usize) -> Result<(i32, i32)> {
     Ok((uncompressed_page_size, compressed_page_size))
 }
 
+/// Returns the CRC32 checksum of the data of a page, as stored in its header
+fn crc(data: &[u8]) -> i32 {
+    crc32fast::hash(data) as i32
+}
+
 /// Contains page write metrics.
 pub struct PageWriteSpec {
     pub header: ParquetPageHeader,
@@ -55,27 +62,39 @@ pub fn write_page<W: Write>(
     writer: &mut W,
     offset: u64,
     compressed_page: &CompressedPage,
+    options: WriteOptions,
+    encryptor: Option<&mut PageEncryptor>,
 ) -> Result<PageWriteSpec> {
     let num_values = compressed_page.num_values();
     let selected_rows = compressed_page.selected_rows();
 
-    let header = match &compressed_page {
+    let mut header = match &compressed_page {
         CompressedPage::Data(compressed_page) => assemble_data_page_header(compressed_page),
         CompressedPage::Dict(compressed_page) => assemble_dict_page_header(compressed_page),
     }?;
+    let buffer = match &compressed_page {
+        CompressedPage::Data(compressed_page) => &compressed_page.buffer,
+        CompressedPage::Dict(compressed_page) => &compressed_page.buffer,
+    };
 
-    let header_size = write_page_header(writer, &header)?;
-    let mut bytes_written = header_size as u64;
-
-    bytes_written += match &compressed_page {
-        CompressedPage::Data(compressed_page) => {
-            writer.write_all(&compressed_page.buffer)?;
-            compressed_page.buffer.len() as u64
+    let (header_size, bytes_written) = if let Some(encryptor) = encryptor {
+        let page_module = encryptor.encrypt_page(&mut header, buffer)?;
+        // the checksum of encrypted pages is computed over the page as written, i.e. encrypted
+        if options.write_page_crc {
+            header.crc = Some(crc(&page_module));
         }
-        CompressedPage::Dict(compressed_page) => {
-            writer.write_all(&compressed_page.buffer)?;
-            compressed_page.buffer.len() as u64
+        let header_module = encryptor.encrypt_header(&header)?;
+        writer.write_all(&header_module)?;
+        writer.write_all(&page_module)?;
+        let header_size = header_module.len() as u64;
+        (header_size, header_size + page_module.len() as u64)
+    } else {
+        if options.write_page_crc {
+            header.crc = Some(crc(buffer));
         }
+        let header_size = write_page_header(writer, &header)?;
+        writer.write_all(buffer)?;
+        (header_size, header_size + buffer.len() as u64)
     };
 
     let statistics = match &compressed_page {
@@ -101,27 +120,39 @@ pub async fn write_page_async<W: AsyncWrite + Unpin + Send>(
     writer: &mut W,
     offset: u64,
     compressed_page: &CompressedPage,
+    options: WriteOptions,
+    encryptor: Option<&mut PageEncryptor>,
 ) -> Result<PageWriteSpec> {
     let num_values = compressed_page.num_values();
     let selected_rows = compressed_page.selected_rows();
 
-    let header = match &compressed_page {
+    let mut header = match &compressed_page {
         CompressedPage::Data(compressed_page) => assemble_data_page_header(compressed_page),
         CompressedPage::Dict(compressed_page) => assemble_dict_page_header(compressed_page),
     }?;
+    let buffer = match &compressed_page {
+        CompressedPage::Data(compressed_page) => &compressed_page.buffer,
+        CompressedPage::Dict(compressed_page) => &compressed_page.buffer,
+    };
 
-    let header_size = write_page_header_async(writer, &header).await?;
-    let mut bytes_written = header_size as u64;
-
-    bytes_written += match &compressed_page {
-        CompressedPage::Data(compressed_page) => {
-            writer.write_all(&compressed_page.buffer).await?;
-            compressed_page.buffer.len() as u64
+    let (header_size, bytes_written) = if let Some(encryptor) = encryptor {
+        let page_module = encryptor.encrypt_page(&mut header, buffer)?;
+        // the checksum of encrypted pages is computed over the page as written, i.e. encrypted
+        if options.write_page_crc {
+            header.crc = Some(crc(&page_module));
         }
-        CompressedPage::Dict(compressed_page) => {
-            writer.write_all(&compressed_page.buffer).await?;
-            compressed_page.buffer.len() as u64
+        let header_module = encryptor.encrypt_header(&header)?;
+        writer.write_all(&header_module).await?;
+        writer.write_all(&page_module).await?;
+        let header_size = header_module.len() as u64;
+        (header_size, header_size + page_module.len() as u64)
+    } else {
+        if options.write_page_crc {
+            header.crc = Some(crc(buffer));
         }
+        let header_size = write_page_header_async(writer, &header).await?;
+        writer.write_all(buffer).await?;
+        (header_size, header_size + buffer.len() as u64)
     };
 
     let statistics = match &compressed_page {
@@ -16,8 +16,9 @@ use super::column_chunk::write_column_chunk_async;
 
 use super::{
     column_chunk::write_column_chunk,
+    file::column_encryptor,
     page::{is_data_page, PageWriteSpec},
-    DynIter, DynStreamingIterator,
+    DynIter, DynStreamingIterator, FileEncryptor, PageEncryptor, WriteOptions,
 };
 
 pub struct ColumnOffsetsMetadata {
@@ -77,6 +78,16 @@ fn compute_num_rows(columns: &[(ColumnChunk, Vec<PageWriteSpec>)]) -> Result<i64
         .unwrap_or(Ok(0))
 }
 
+/// Returns the encryptor of the pages of the column chunk `column` of the row group `row_group`,
+/// if it is encrypted.
+fn page_encryptor(
+    encryptor: Option<&FileEncryptor>,
+    row_group: usize,
+    column: usize,
+) -> Result<Option<PageEncryptor>> {
+    Ok(column_encryptor(encryptor, row_group, column)?.map(PageEncryptor::new))
+}
+
 pub fn write_row_group<
     'a,
     W,
@@ -87,19 +98,22 @@ pub fn write_row_group<
     descriptors: &[ColumnDescriptor],
     columns: DynIter<'a, std::result::Result<DynStreamingIterator<'a, CompressedPage, E>, E>>,
     ordinal: usize,
+    options: WriteOptions,
+    encryptor: Option<&FileEncryptor>,
 ) -> Result<(RowGroup, Vec<Vec<PageWriteSpec>>, u64)>
 where
     W: Write,
     Error: From<E>,
     E: std::error::Error,
 {
-    let column_iter = descriptors.iter().zip(columns);
+    let column_iter = descriptors.iter().zip(columns).enumerate();
 
     let initial = offset;
     let columns = column_iter
-        .map(|(descriptor, page_iter)| {
+        .map(|(i, (descriptor, page_iter))| {
+            let encryptor = page_encryptor(encryptor, ordinal, i)?;
             let (column, page_specs, size) =
-                write_column_chunk(writer, offset, descriptor, page_iter?)?;
+                write_column_chunk(writer, offset, descriptor, page_iter?, options, encryptor)?;
             offset += size;
             Ok((column, page_specs))
         })
@@ -154,19 +168,23 @@ pub async fn write_row_group_async<
     descriptors: &[ColumnDescriptor],
     columns: DynIter<'a, std::result::Result<DynStreamingIterator<'a, CompressedPage, E>, E>>,
     ordinal: usize,
+    options: WriteOptions,
+    encryptor: Option<&FileEncryptor>,
 ) -> Result<(RowGroup, Vec<Vec<PageWriteSpec>>, u64)>
 where
     W: AsyncWrite + Unpin + Send,
     Error: From<E>,
     E: std::error::Error,
 {
-    let column_iter = descriptors.iter().zip(columns);
+    let column_iter = descriptors.iter().zip(columns).enumerate();
 
     let initial = offset;
     let mut columns = vec![];
-    for (descriptor, page_iter) in column_iter {
+    for (i, (descriptor, page_iter)) in column_iter {
+        let encryptor = page_encryptor(encryptor, ordinal, i)?;
         let (column, page_specs, size) =
-            write_column_chunk_async(writer, offset, descriptor, page_iter?).await?;
+            write_column_chunk_async(writer, offset, descriptor, page_iter?, options, encryptor)
+                .await?;
         offset += size;
         columns.push((column, page_specs));
     }
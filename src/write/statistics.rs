@@ -1,9 +1,37 @@
 use std::sync::Arc;
 
+use std::cmp::Ordering;
+
 use crate::error::{Error, Result};
+use crate::metadata::SortOrder;
 use crate::schema::types::PhysicalType;
 use crate::statistics::*;
-use crate::types::NativeType;
+use crate::types::{self, ord_binary, ord_signed_binary, NativeType};
+
+/// Compares two byte arrays in `sort_order`: decimals are signed, other byte arrays unsigned.
+pub(super) fn compare_binary(sort_order: SortOrder, lhs: &[u8], rhs: &[u8]) -> Ordering {
+    match sort_order {
+        SortOrder::Signed => ord_signed_binary(lhs, rhs),
+        _ => ord_binary(lhs, rhs),
+    }
+}
+
+/// Compares two native values in `sort_order`: unsigned integers are stored in signed
+/// physical types.
+pub(super) fn compare_native<T: NativeType>(sort_order: SortOrder, lhs: &T, rhs: &T) -> Ordering {
+    let (lhs_bytes, rhs_bytes) = (lhs.to_le_bytes(), rhs.to_le_bytes());
+    match (T::TYPE, sort_order) {
+        (PhysicalType::Int32, SortOrder::Unsigned) => {
+            let lhs = types::decode::<i32>(lhs_bytes.as_ref()) as u32;
+            lhs.cmp(&(types::decode::<i32>(rhs_bytes.as_ref()) as u32))
+        }
+        (PhysicalType::Int64, SortOrder::Unsigned) => {
+            let lhs = types::decode::<i64>(lhs_bytes.as_ref()) as u64;
+            lhs.cmp(&(types::decode::<i64>(rhs_bytes.as_ref()) as u64))
+        }
+        _ => lhs.ord(rhs),
+    }
+}
 
 #[inline]
 fn reduce_single<T, F: Fn(T, T) -> T>(lhs: Option<T>, rhs: Option<T>, op: F) -> Option<T> {
@@ -16,16 +44,34 @@ fn reduce_single<T, F: Fn(T, T) -> T>(lhs: Option<T>, rhs: Option<T>, op: F) ->
 }
 
 #[inline]
-fn reduce_vec8(lhs: Option<Vec<u8>>, rhs: &Option<Vec<u8>>, max: bool) -> Option<Vec<u8>> {
+fn reduce_vec8(
+    lhs: Option<Vec<u8>>,
+    rhs: &Option<Vec<u8>>,
+    sort_order: SortOrder,
+    max: bool,
+) -> Option<Vec<u8>> {
     match (lhs, rhs) {
         (None, None) => None,
         (Some(x), None) => Some(x),
         (None, Some(x)) => Some(x.clone()),
-        (Some(x), Some(y)) => Some(ord_binary(x, y.clone(), max)),
+        (Some(x), Some(y)) => {
+            let ordering = compare_binary(sort_order, &x, y);
+            let keep_lhs = if max {
+                ordering.is_ge()
+            } else {
+                ordering.is_le()
+            };
+            Some(if keep_lhs { x } else { y.clone() })
+        }
     }
 }
 
-pub fn reduce(stats: &[&Option<Arc<dyn Statistics>>]) -> Result<Option<Arc<dyn Statistics>>> {
+/// Reduces the statistics of the pages of a column chunk into the statistics of the chunk,
+/// comparing values in `sort_order`, the sort order of the column.
+pub fn reduce(
+    stats: &[&Option<Arc<dyn Statistics>>],
+    sort_order: SortOrder,
+) -> Result<Option<Arc<dyn Statistics>>> {
     if stats.is_empty() {
         return Ok(None);
     }
@@ -54,37 +100,40 @@ pub fn reduce(stats: &[&Option<Arc<dyn Statistics>>]) -> Result<Option<Arc<dyn S
         }
         PhysicalType::Int32 => {
             let stats = stats.iter().map(|x| x.as_any().downcast_ref().unwrap());
-            Some(Arc::new(reduce_primitive::<i32, _>(stats)))
+            Some(Arc::new(reduce_primitive::<i32, _>(stats, sort_order)))
         }
         PhysicalType::Int64 => {
             let stats = stats.iter().map(|x| x.as_any().downcast_ref().unwrap());
-            Some(Arc::new(reduce_primitive::<i64, _>(stats)))
+            Some(Arc::new(reduce_primitive::<i64, _>(stats, sort_order)))
         }
         PhysicalType::Float => {
             let stats = stats.iter().map(|x| x.as_any().downcast_ref().unwrap());
-            Some(Arc::new(reduce_primitive::<f32, _>(stats)))
+            Some(Arc::new(reduce_primitive::<f32, _>(stats, sort_order)))
         }
         PhysicalType::Double => {
             let stats = stats.iter().map(|x| x.as_any().downcast_ref().unwrap());
-            Some(Arc::new(reduce_primitive::<f64, _>(stats)))
+            Some(Arc::new(reduce_primitive::<f64, _>(stats, sort_order)))
         }
         PhysicalType::ByteArray => {
             let stats = stats.iter().map(|x| x.as_any().downcast_ref().unwrap());
-            Some(Arc::new(reduce_binary(stats)))
+            Some(Arc::new(reduce_binary(stats, sort_order)))
         }
         PhysicalType::FixedLenByteArray(_) => {
             let stats = stats.iter().map(|x| x.as_any().downcast_ref().unwrap());
-            Some(Arc::new(reduce_fix_len_binary(stats)))
+            Some(Arc::new(reduce_fix_len_binary(stats, sort_order)))
         }
         _ => todo!(),
     })
 }
 
-fn reduce_binary<'a, I: Iterator<Item = &'a BinaryStatistics>>(mut stats: I) -> BinaryStatistics {
+fn reduce_binary<'a, I: Iterator<Item = &'a BinaryStatistics>>(
+    mut stats: I,
+    sort_order: SortOrder,
+) -> BinaryStatistics {
     let initial = stats.next().unwrap().clone();
     stats.fold(initial, |mut acc, new| {
-        acc.min_value = reduce_vec8(acc.min_value, &new.min_value, false);
-        acc.max_value = reduce_vec8(acc.max_value, &new.max_value, true);
+        acc.min_value = reduce_vec8(acc.min_value, &new.min_value, sort_order, false);
+        acc.max_value = reduce_vec8(acc.max_value, &new.max_value, sort_order, true);
         acc.null_count = reduce_single(acc.null_count, new.null_count, |x, y| x + y);
         acc.distinct_count = None;
         acc
@@ -93,40 +142,18 @@ fn reduce_binary<'a, I: Iterator<Item = &'a BinaryStatistics>>(mut stats: I) ->
 
 fn reduce_fix_len_binary<'a, I: Iterator<Item = &'a FixedLenStatistics>>(
     mut stats: I,
+    sort_order: SortOrder,
 ) -> FixedLenStatistics {
     let initial = stats.next().unwrap().clone();
     stats.fold(initial, |mut acc, new| {
-        acc.min_value = reduce_vec8(acc.min_value, &new.min_value, false);
-        acc.max_value = reduce_vec8(acc.max_value, &new.max_value, true);
+        acc.min_value = reduce_vec8(acc.min_value, &new.min_value, sort_order, false);
+        acc.max_value = reduce_vec8(acc.max_value, &new.max_value, sort_order, true);
         acc.null_count = reduce_single(acc.null_count, new.null_count, |x, y| x + y);
         acc.distinct_count = None;
         acc
     })
 }
 
-fn ord_binary(a: Vec<u8>, b: Vec<u8>, max: bool) -> Vec<u8> {
-    for (v1, v2) in a.iter().zip(b.iter()) {
-        match v1.cmp(v2) {
-            std::cmp::Ordering::Greater => {
-                if max {
-                    return a;
-                } else {
-                    return b;
-                }
-            }
-            std::cmp::Ordering::Less => {
-                if max {
-                    return b;
-                } else {
-                    return a;
-                }
-            }
-            _ => {}
-        }
-    }
-    a
-}
-
 fn reduce_boolean<'a, I: Iterator<Item = &'a BooleanStatistics>>(
     mut stats: I,
 ) -> BooleanStatistics {
@@ -148,25 +175,26 @@ fn reduce_boolean<'a, I: Iterator<Item = &'a BooleanStatistics>>(
     })
 }
 
-fn reduce_primitive<
-    'a,
-    T: NativeType + std::cmp::PartialOrd,
-    I: Iterator<Item = &'a PrimitiveStatistics<T>>,
->(
+fn reduce_primitive<'a, T: NativeType, I: Iterator<Item = &'a PrimitiveStatistics<T>>>(
     mut stats: I,
+    sort_order: SortOrder,
 ) -> PrimitiveStatistics<T> {
     let initial = stats.next().unwrap().clone();
     stats.fold(initial, |mut acc, new| {
-        acc.min_value = reduce_single(
-            acc.min_value,
-            new.min_value,
-            |x, y| if x > y { y } else { x },
-        );
-        acc.max_value = reduce_single(
-            acc.max_value,
-            new.max_value,
-            |x, y| if x < y { x } else { y },
-        );
+        acc.min_value = reduce_single(acc.min_value, new.min_value, |x, y| {
+            if compare_native(sort_order, &x, &y).is_gt() {
+                y
+            } else {
+                x
+            }
+        });
+        acc.max_value = reduce_single(acc.max_value, new.max_value, |x, y| {
+            if compare_native(sort_order, &x, &y).is_gt() {
+                x
+            } else {
+                y
+            }
+        });
         acc.null_count = reduce_single(acc.null_count, new.null_count, |x, y| x + y);
         acc.distinct_count = None;
         acc
@@ -203,7 +231,7 @@ mod tests {
                 max_value: None,
             },
         ];
-        let a = reduce_binary(iter.iter());
+        let a = reduce_binary(iter.iter(), SortOrder::Unsigned);
 
         assert_eq!(
             a,
@@ -246,7 +274,7 @@ mod tests {
                 max_value: None,
             },
         ];
-        let a = reduce_fix_len_binary(iter.iter());
+        let a = reduce_fix_len_binary(iter.iter(), SortOrder::Unsigned);
 
         assert_eq!(
             a,
@@ -295,4 +323,39 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn primitive() -> Result<()> {
+        let primitive_type = PrimitiveType::from_physical("bla".to_string(), PhysicalType::Int32);
+        let iter = [
+            PrimitiveStatistics::<i32> {
+                primitive_type: primitive_type.clone(),
+                null_count: Some(1),
+                distinct_count: None,
+                min_value: Some(1),
+                max_value: Some(10),
+            },
+            PrimitiveStatistics::<i32> {
+                primitive_type: primitive_type.clone(),
+                null_count: Some(2),
+                distinct_count: None,
+                min_value: Some(-2),
+                max_value: Some(5),
+            },
+        ];
+        let a = reduce_primitive(iter.iter(), SortOrder::Signed);
+
+        assert_eq!(
+            a,
+            PrimitiveStatistics::<i32> {
+                primitive_type,
+                null_count: Some(3),
+                distinct_count: None,
+                min_value: Some(-2),
+                max_value: Some(10),
+            },
+        );
+
+        Ok(())
+    }
 }
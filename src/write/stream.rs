@@ -7,9 +7,11 @@ use parquet_format_safe::{
     FileMetaData, RowGroup,
 };
 
+use crate::write::bloom_filter::write_bloom_filter_async;
+use crate::write::file::{column_encryptor, column_orders, set_bloom_filter};
 use crate::write::indexes::{write_column_index_async, write_offset_index_async};
 use crate::write::page::PageWriteSpec;
-use crate::write::State;
+use crate::write::{FileEncryptor, State};
 use crate::{
     error::{Error, Result},
     metadata::{KeyValue, SchemaDescriptor},
@@ -18,9 +20,9 @@ use crate::{
 
 use super::{row_group::write_row_group_async, RowGroupIter, WriteOptions};
 
-async fn start_file<W: AsyncWrite + Unpin>(writer: &mut W) -> Result<u64> {
-    writer.write_all(&PARQUET_MAGIC).await?;
-    Ok(PARQUET_MAGIC.len() as u64)
+async fn start_file<W: AsyncWrite + Unpin>(writer: &mut W, magic: &[u8; 4]) -> Result<u64> {
+    writer.write_all(magic).await?;
+    Ok(magic.len() as u64)
 }
 
 async fn end_file<W: AsyncWrite + Unpin + Send>(
@@ -45,6 +47,22 @@ async fn end_file<W: AsyncWrite + Unpin + Send>(
     Ok(metadata_len as u64 + FOOTER_SIZE)
 }
 
+/// Writes the footer of an encrypted file, whose column metadata and footer are encrypted
+/// (or signed) by `encryptor`.
+async fn end_encrypted_file<W: AsyncWrite + Unpin + Send>(
+    writer: &mut W,
+    metadata: &FileMetaData,
+    encryptor: &FileEncryptor,
+) -> Result<u64> {
+    let (footer, magic) = encryptor.footer(metadata)?;
+    let footer_len: i32 = footer.len().try_into()?;
+    writer.write_all(&footer).await?;
+    writer.write_all(&footer_len.to_le_bytes()).await?;
+    writer.write_all(&magic).await?;
+    writer.flush().await?;
+    Ok(footer.len() as u64 + FOOTER_SIZE)
+}
+
 /// An interface to write a parquet file asynchronously.
 /// Use `start` to write the header, `write` to write a row group,
 /// and `end` to write the footer.
@@ -57,6 +75,8 @@ pub struct FileStreamer<W: AsyncWrite + Unpin + Send> {
     offset: u64,
     row_groups: Vec<RowGroup>,
     page_specs: Vec<Vec<Vec<PageWriteSpec>>>,
+    bloom_filters: Vec<Vec<Option<Vec<u8>>>>,
+    encryptor: Option<FileEncryptor>,
     /// Used to store the current state for writing the file
     state: State,
 }
@@ -90,10 +110,31 @@ impl<W: AsyncWrite + Unpin + Send> FileStreamer<W> {
             offset: 0,
             row_groups: vec![],
             page_specs: vec![],
+            bloom_filters: vec![],
+            encryptor: None,
             state: State::Initialised,
         }
     }
 
+    /// Returns a new [`FileStreamer`] that encrypts the file as declared by `properties`.
+    /// # Errors
+    /// Errors if the keys of the file can't be provided or are not AES keys.
+    #[cfg(feature = "encryption")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "encryption")))]
+    pub fn with_encryption(
+        writer: W,
+        schema: SchemaDescriptor,
+        options: WriteOptions,
+        created_by: Option<String>,
+        properties: &crate::encryption::FileEncryptionProperties,
+    ) -> Result<Self> {
+        let encryptor = FileEncryptor::try_new(properties, &schema)?;
+        Ok(Self {
+            encryptor: Some(encryptor),
+            ..Self::new(writer, schema, options, created_by)
+        })
+    }
+
     /// Writes the header of the file.
     ///
     /// This is automatically called by [`Self::write`] if not called following [`Self::new`].
@@ -102,7 +143,11 @@ impl<W: AsyncWrite + Unpin + Send> FileStreamer<W> {
     /// Returns an error if data has been written to the file.
     async fn start(&mut self) -> Result<()> {
         if self.offset == 0 {
-            self.offset = start_file(&mut self.writer).await? as u64;
+            let magic = self
+                .encryptor
+                .as_ref()
+                .map_or(PARQUET_MAGIC, FileEncryptor::magic);
+            self.offset = start_file(&mut self.writer, &magic).await? as u64;
             self.state = State::Started;
             Ok(())
         } else {
@@ -127,14 +172,38 @@ impl<W: AsyncWrite + Unpin + Send> FileStreamer<W> {
             self.schema.columns(),
             row_group,
             ordinal,
+            self.options,
+            self.encryptor.as_ref(),
         )
         .await?;
         self.offset += size;
+        self.bloom_filters.push(vec![None; group.columns.len()]);
         self.row_groups.push(group);
         self.page_specs.push(specs);
         Ok(())
     }
 
+    /// Assigns a split-block bloom filter `bitset` to the column chunk `column` of the
+    /// (already written) row group `row_group`.
+    ///
+    /// The bloom filters are written by [`Self::end`]. See [`crate::bloom_filter`] on how
+    /// to size and populate `bitset`.
+    /// # Errors
+    /// Errors if the row group or column do not exist or if the file has already ended.
+    pub fn set_bloom_filter(
+        &mut self,
+        row_group: usize,
+        column: usize,
+        bitset: Vec<u8>,
+    ) -> Result<()> {
+        if self.state == State::Finished {
+            return Err(Error::General(
+                "Bloom filters cannot be set after the file has ended".to_string(),
+            ));
+        }
+        set_bloom_filter(&mut self.bloom_filters, row_group, column, bitset)
+    }
+
     /// Writes the footer of the parquet file. Returns the total size of the file and the
     /// underlying writer.
     pub async fn end(&mut self, key_value_metadata: Option<Vec<KeyValue>>) -> Result<u64> {
@@ -148,13 +217,50 @@ impl<W: AsyncWrite + Unpin + Send> FileStreamer<W> {
         // compute file stats
         let num_rows = self.row_groups.iter().map(|group| group.num_rows).sum();
 
+        // write bloom filters
+        for (i, (group, bloom_filters)) in self
+            .row_groups
+            .iter_mut()
+            .zip(self.bloom_filters.iter())
+            .enumerate()
+        {
+            for (j, (column, bitset)) in group
+                .columns
+                .iter_mut()
+                .zip(bloom_filters.iter())
+                .enumerate()
+            {
+                if let Some(bitset) = bitset {
+                    let encryptor = column_encryptor(self.encryptor.as_ref(), i, j)?;
+                    let offset = self.offset;
+                    self.offset +=
+                        write_bloom_filter_async(&mut self.writer, bitset, encryptor.as_ref())
+                            .await?;
+                    column.meta_data.as_mut().unwrap().bloom_filter_offset = Some(offset as i64);
+                }
+            }
+        }
+
         if self.options.write_statistics {
             // write column indexes (require page statistics)
-            for (group, pages) in self.row_groups.iter_mut().zip(self.page_specs.iter()) {
-                for (column, pages) in group.columns.iter_mut().zip(pages.iter()) {
+            for (i, (group, pages)) in self
+                .row_groups
+                .iter_mut()
+                .zip(self.page_specs.iter())
+                .enumerate()
+            {
+                for (j, (column, pages)) in group.columns.iter_mut().zip(pages.iter()).enumerate() {
+                    let encryptor = column_encryptor(self.encryptor.as_ref(), i, j)?;
+                    let primitive_type = &self.schema.columns()[j].descriptor.primitive_type;
                     let offset = self.offset;
                     column.column_index_offset = Some(offset as i64);
-                    self.offset += write_column_index_async(&mut self.writer, pages).await?;
+                    self.offset += write_column_index_async(
+                        &mut self.writer,
+                        pages,
+                        primitive_type,
+                        encryptor.as_ref(),
+                    )
+                    .await?;
                     let length = self.offset - offset;
                     column.column_index_length = Some(length as i32);
                 }
@@ -162,11 +268,18 @@ impl<W: AsyncWrite + Unpin + Send> FileStreamer<W> {
         };
 
         // write offset index
-        for (group, pages) in self.row_groups.iter_mut().zip(self.page_specs.iter()) {
-            for (column, pages) in group.columns.iter_mut().zip(pages.iter()) {
+        for (i, (group, pages)) in self
+            .row_groups
+            .iter_mut()
+            .zip(self.page_specs.iter())
+            .enumerate()
+        {
+            for (j, (column, pages)) in group.columns.iter_mut().zip(pages.iter()).enumerate() {
+                let encryptor = column_encryptor(self.encryptor.as_ref(), i, j)?;
                 let offset = self.offset;
                 column.offset_index_offset = Some(offset as i64);
-                self.offset += write_offset_index_async(&mut self.writer, pages).await?;
+                self.offset +=
+                    write_offset_index_async(&mut self.writer, pages, encryptor.as_ref()).await?;
                 column.offset_index_length = Some((self.offset - offset) as i32);
             }
         }
@@ -178,12 +291,16 @@ impl<W: AsyncWrite + Unpin + Send> FileStreamer<W> {
             self.row_groups.clone(),
             key_value_metadata,
             self.created_by.clone(),
-            None,
+            Some(column_orders(&self.schema)),
             None,
             None,
         );
 
-        let len = end_file(&mut self.writer, metadata).await?;
+        let len = match &self.encryptor {
+            Some(encryptor) => end_encrypted_file(&mut self.writer, &metadata, encryptor).await?,
+            None => end_file(&mut self.writer, metadata).await?,
+        };
+        self.state = State::Finished;
         Ok(self.offset + len)
     }
 
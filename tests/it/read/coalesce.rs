@@ -0,0 +1,170 @@
+use std::io::Cursor;
+
+use parquet2::error::Result;
+use parquet2::fallible_streaming_iterator;
+use parquet2::indexes::{select_pages, Interval};
+use parquet2::metadata::{ColumnChunkMetaData, SchemaDescriptor};
+use parquet2::read::{
+    get_page_iterator, read_metadata, read_pages_locations, BasicDecompressor, ChunkRead,
+    CoalesceOptions, FetchedRanges, ReadPlan,
+};
+use parquet2::write::{
+    ColumnWriterOptions, DynIter, DynStreamingIterator, FileWriter, Levels, NativeColumnWriter,
+    Version, WriteOptions,
+};
+
+use super::collect;
+use crate::Array;
+
+/// Writes a file with two row groups of two columns of 100 rows, in pages of 10 rows
+fn write_file() -> Result<Vec<u8>> {
+    let schema = SchemaDescriptor::try_from_message(
+        "message schema { required int32 a; required int32 b; }",
+    )?;
+    let options = WriteOptions {
+        write_statistics: true,
+        version: Version::V1,
+        write_page_crc: false,
+    };
+    let column_options = ColumnWriterOptions {
+        max_page_size: 10 * 4,
+        ..Default::default()
+    };
+
+    let mut writer = FileWriter::new(Cursor::new(vec![]), schema.clone(), options, None);
+    for row_group in 0..2 {
+        let pages = schema
+            .columns()
+            .iter()
+            .enumerate()
+            .map(|(column, descriptor)| {
+                let mut writer = NativeColumnWriter::<i32>::new(
+                    descriptor.descriptor.clone(),
+                    options,
+                    column_options,
+                )?;
+                let offset = (row_group * 2 + column as i32) * 1000;
+                writer.write(
+                    &(offset..offset + 100).collect::<Vec<_>>(),
+                    Levels::Required,
+                )?;
+                writer.finish()
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let columns = pages.iter().map(|pages| {
+            Ok(DynStreamingIterator::new(
+                fallible_streaming_iterator::convert(pages.iter().map(Result::Ok)),
+            ))
+        });
+        writer.write(DynIter::new(columns))?;
+    }
+    writer.end(None)?;
+    Ok(writer.into_inner().into_inner())
+}
+
+fn read_column(data: &[u8], column: &ColumnChunkMetaData) -> Result<Vec<Array>> {
+    let pages = get_page_iterator(column, Cursor::new(data), None, vec![], usize::MAX)?;
+    collect(
+        BasicDecompressor::new(pages, vec![]),
+        column.physical_type(),
+    )
+}
+
+fn read_planned(plan: &ReadPlan, fetched: &FetchedRanges) -> Result<Vec<Vec<Array>>> {
+    plan.chunks()
+        .iter()
+        .map(|chunk| {
+            let pages = fetched.pages(chunk, None, vec![], usize::MAX)?;
+            collect(
+                BasicDecompressor::new(pages, vec![]),
+                chunk.column.physical_type(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn coalesced() -> Result<()> {
+    let data = write_file()?;
+    let metadata = read_metadata(&mut Cursor::new(&data))?;
+
+    // the column `b` of both row groups is separated by the column `a` of the second
+    let plan = ReadPlan::from_metadata(&metadata, &[0, 1], &[1], CoalesceOptions::default());
+    assert_eq!(plan.ranges().len(), 1);
+    let options = CoalesceOptions {
+        max_gap: 0,
+        ..Default::default()
+    };
+    let separate = ReadPlan::from_metadata(&metadata, &[0, 1], &[1], options);
+    assert_eq!(separate.ranges().len(), 2);
+
+    let expected = [0, 1]
+        .iter()
+        .map(|row_group| read_column(&data, &metadata.row_groups[*row_group].columns()[1]))
+        .collect::<Result<Vec<_>>>()?;
+    for plan in [plan, separate] {
+        let fetched = plan.read(&mut Cursor::new(&data))?;
+        assert_eq!(read_planned(&plan, &fetched)?, expected);
+    }
+    Ok(())
+}
+
+#[test]
+fn selected_pages() -> Result<()> {
+    let data = write_file()?;
+    let metadata = read_metadata(&mut Cursor::new(&data))?;
+    let row_group = &metadata.row_groups[0];
+    let locations = read_pages_locations(&mut Cursor::new(&data), row_group.columns())?;
+
+    let intervals = &[Interval::new(15, 10), Interval::new(80, 5)];
+    let chunks = row_group
+        .columns()
+        .iter()
+        .zip(locations.iter())
+        .map(|(column, locations)| {
+            Ok(ChunkRead {
+                column: column.clone(),
+                pages: Some(select_pages(intervals, locations, row_group.num_rows())?),
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let options = CoalesceOptions {
+        max_gap: 0,
+        ..Default::default()
+    };
+    let plan = ReadPlan::new(chunks, options);
+    // pages [10, 30) and [80, 90) of each column
+    assert_eq!(plan.ranges().len(), 4);
+
+    let fetched = plan.read(&mut Cursor::new(&data))?;
+    let expected = |offset: i32| {
+        vec![
+            Array::Int32((offset + 15..offset + 20).map(Some).collect()),
+            Array::Int32((offset + 20..offset + 25).map(Some).collect()),
+            Array::Int32((offset + 80..offset + 85).map(Some).collect()),
+        ]
+    };
+    assert_eq!(
+        read_planned(&plan, &fetched)?,
+        vec![expected(0), expected(1000)]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn coalesced_async() -> Result<()> {
+    let data = write_file()?;
+    let metadata = read_metadata(&mut Cursor::new(&data))?;
+
+    let plan = ReadPlan::from_metadata(&metadata, &[1], &[0, 1], CoalesceOptions::default());
+    let fetched = plan
+        .read_async(&mut futures::io::Cursor::new(&data))
+        .await?;
+    let expected = metadata.row_groups[1]
+        .columns()
+        .iter()
+        .map(|column| read_column(&data, column))
+        .collect::<Result<Vec<_>>>()?;
+    assert_eq!(read_planned(&plan, &fetched)?, expected);
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use std::fs::File;
+
+use parquet2::encoding::{delta_bitpacked, Encoding};
+use parquet2::error::Result;
+use parquet2::page::{split_buffer, Page};
+use parquet2::read::{get_page_iterator, read_metadata, BasicDecompressor};
+use parquet2::FallibleStreamingIterator;
+
+fn test_pyarrow_integration(version: usize) -> Result<()> {
+    if std::env::var("PARQUET2_IGNORE_PYARROW_TESTS").is_ok() {
+        return Ok(());
+    }
+    let path = format!(
+        "fixtures/pyarrow3/v{}/delta_binary_packed/encoded_required_10.parquet",
+        version
+    );
+    let mut file = File::open(path).unwrap();
+    let metadata = read_metadata(&mut file)?;
+    let column = &metadata.row_groups[0].columns()[1];
+
+    let pages = get_page_iterator(column, &mut file, None, vec![], usize::MAX)?;
+    let mut pages = BasicDecompressor::new(pages, vec![]);
+
+    let expected = vec![
+        0,
+        i64::MAX,
+        i64::MIN,
+        1,
+        -1,
+        1_700_000_000_000_000_000,
+        1_700_000_000_123_456_789,
+        -4_611_686_018_427_387_904,
+        42,
+        i64::MAX - 1,
+    ];
+
+    let page = match pages.next()?.unwrap() {
+        Page::Data(page) => page,
+        Page::Dict(_) => panic!("pyarrow was asked to not use dictionary encoding"),
+    };
+    assert_eq!(page.encoding(), Encoding::DeltaBinaryPacked);
+
+    // decode what pyarrow wrote
+    let (_, _, values) = split_buffer(page)?;
+    let mut decoder = delta_bitpacked::Decoder::new(values);
+    let result = decoder.by_ref().collect::<Vec<_>>();
+    assert_eq!(result, expected);
+    assert_eq!(decoder.consumed_bytes(), values.len());
+
+    // and round-trip it through our encoder
+    let mut buffer = vec![];
+    delta_bitpacked::encode(result.into_iter(), &mut buffer);
+    assert_eq!(
+        delta_bitpacked::Decoder::new(&buffer).collect::<Vec<_>>(),
+        expected
+    );
+    Ok(())
+}
+
+#[test]
+fn pyarrow_v1_required() -> Result<()> {
+    test_pyarrow_integration(1)
+}
+
+#[test]
+fn pyarrow_v2_required() -> Result<()> {
+    test_pyarrow_integration(2)
+}
@@ -0,0 +1,75 @@
+use std::fs::File;
+
+use parquet2::encoding::{delta_byte_array, Encoding};
+use parquet2::error::Result;
+use parquet2::page::{split_buffer, Page};
+use parquet2::read::{get_page_iterator, read_metadata, BasicDecompressor};
+use parquet2::FallibleStreamingIterator;
+
+/// Returns the prefix lengths, suffix lengths and suffixes of a `DELTA_BYTE_ARRAY`-encoded buffer
+fn parts(values: &[u8]) -> (Vec<u32>, Vec<i32>, Vec<u8>) {
+    let mut decoder = delta_byte_array::Decoder::new(values);
+    let prefixes = decoder.by_ref().collect::<Vec<_>>();
+
+    let mut decoder = decoder.into_lengths();
+    let lengths = decoder.by_ref().collect::<Vec<_>>();
+    let total = lengths.iter().sum::<i32>() as usize;
+    (prefixes, lengths, decoder.values()[..total].to_vec())
+}
+
+fn test_pyarrow_integration(version: usize) -> Result<()> {
+    if std::env::var("PARQUET2_IGNORE_PYARROW_TESTS").is_ok() {
+        return Ok(());
+    }
+    let path = format!(
+        "fixtures/pyarrow3/v{}/delta_byte_array/encoded_required_10.parquet",
+        version
+    );
+    let mut file = File::open(path).unwrap();
+    let metadata = read_metadata(&mut file)?;
+    let column = &metadata.row_groups[0].columns()[0];
+
+    let pages = get_page_iterator(column, &mut file, None, vec![], usize::MAX)?;
+    let mut pages = BasicDecompressor::new(pages, vec![]);
+
+    let expected = [
+        "https://a.com",
+        "https://a.com/a",
+        "https://a.com/ab",
+        "https://a.com/b",
+        "https://b.com",
+        "https://b.com/",
+        "https://b.com/index.html",
+        "https://bb.com",
+        "https://c.com",
+        "https://c.com/c",
+    ]
+    .iter()
+    .map(|x| x.as_bytes().to_vec())
+    .collect::<Vec<_>>();
+
+    let page = match pages.next()?.unwrap() {
+        Page::Data(page) => page,
+        Page::Dict(_) => panic!("pyarrow was asked to not use dictionary encoding"),
+    };
+    assert_eq!(page.encoding(), Encoding::DeltaByteArray);
+
+    // what pyarrow wrote must match what we write for the same values
+    let (_, _, values) = split_buffer(page)?;
+    let mut buffer = vec![];
+    delta_byte_array::encode(expected.iter(), &mut buffer);
+    let result = parts(values);
+    assert_eq!(result.0, vec![0, 13, 15, 14, 8, 13, 14, 9, 8, 13]);
+    assert_eq!(result, parts(&buffer));
+    Ok(())
+}
+
+#[test]
+fn pyarrow_v1_required() -> Result<()> {
+    test_pyarrow_integration(1)
+}
+
+#[test]
+fn pyarrow_v2_required() -> Result<()> {
+    test_pyarrow_integration(2)
+}
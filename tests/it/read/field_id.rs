@@ -0,0 +1,90 @@
+use std::io::Cursor;
+
+use parquet2::error::Result;
+use parquet2::fallible_streaming_iterator;
+use parquet2::metadata::SchemaDescriptor;
+use parquet2::read::{
+    get_field_columns_by_id, get_page_iterator, read_metadata, BasicDecompressor,
+};
+use parquet2::write::{
+    ColumnWriterOptions, DynIter, DynStreamingIterator, FileWriter, Levels, NativeColumnWriter,
+    Version, WriteOptions,
+};
+
+use super::collect;
+use crate::Array;
+
+fn write_file() -> Result<Vec<u8>> {
+    let schema = SchemaDescriptor::try_from_message(
+        "message schema { required int32 a = 1; required int64 b = 2; }",
+    )?;
+    let options = WriteOptions {
+        write_statistics: true,
+        version: Version::V1,
+        write_page_crc: false,
+    };
+
+    let mut a = NativeColumnWriter::<i32>::new(
+        schema.columns()[0].descriptor.clone(),
+        options,
+        ColumnWriterOptions::default(),
+    )?;
+    a.write(&[1, 2, 3], Levels::Required)?;
+    let mut b = NativeColumnWriter::<i64>::new(
+        schema.columns()[1].descriptor.clone(),
+        options,
+        ColumnWriterOptions::default(),
+    )?;
+    b.write(&[4, 5, 6], Levels::Required)?;
+    let pages = [a.finish()?, b.finish()?];
+
+    let mut writer = FileWriter::new(Cursor::new(vec![]), schema, options, None);
+    let columns = pages.iter().map(|pages| {
+        Ok(DynStreamingIterator::new(
+            fallible_streaming_iterator::convert(pages.iter().map(Result::Ok)),
+        ))
+    });
+    writer.write(DynIter::new(columns))?;
+    writer.end(None)?;
+    Ok(writer.into_inner().into_inner())
+}
+
+#[test]
+fn project() -> Result<()> {
+    let data = write_file()?;
+    let metadata = read_metadata(&mut Cursor::new(&data))?;
+
+    // the columns were renamed, reordered and one was added since the file was written
+    let requested = SchemaDescriptor::try_from_message(
+        "message schema { required int64 x = 2; optional int32 y = 3; required int32 z = 1; }",
+    )?;
+    let projection = metadata.project_by_id(&requested)?;
+    assert_eq!(projection.len(), 1);
+    let columns = &projection[0];
+    assert!(columns[1].is_none());
+
+    let read = |column: &parquet2::metadata::ColumnChunkMetaData| -> Result<Vec<Array>> {
+        let pages = get_page_iterator(column, Cursor::new(&data), None, vec![], usize::MAX)?;
+        collect(
+            BasicDecompressor::new(pages, vec![]),
+            column.physical_type(),
+        )
+    };
+    assert_eq!(
+        read(columns[0].unwrap())?,
+        vec![Array::Int64(vec![Some(4), Some(5), Some(6)])]
+    );
+    assert_eq!(
+        read(columns[2].unwrap())?,
+        vec![Array::Int32(vec![Some(1), Some(2), Some(3)])]
+    );
+
+    let columns = get_field_columns_by_id(&metadata, 0, 2).collect::<Vec<_>>();
+    assert_eq!(columns.len(), 1);
+    assert_eq!(
+        columns[0].descriptor().path_in_schema,
+        vec!["b".to_string()]
+    );
+    assert_eq!(get_field_columns_by_id(&metadata, 0, 3).count(), 0);
+    Ok(())
+}
@@ -0,0 +1,180 @@
+use std::io::Cursor;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use parquet2::error::Result;
+use parquet2::fallible_streaming_iterator;
+use parquet2::metadata::SchemaDescriptor;
+use parquet2::read::{
+    read_columns_indexes, read_metadata, read_metadata_with_options, read_pages_locations,
+    MetadataCache, MetadataOptions, RangeCursor, RangeReader,
+};
+use parquet2::write::{
+    ColumnWriterOptions, DynIter, DynStreamingIterator, FileWriter, Levels, NativeColumnWriter,
+    Version, WriteOptions,
+};
+
+/// An in-memory reader counting its requests
+struct Counting {
+    data: Vec<u8>,
+    requests: AtomicUsize,
+}
+
+impl RangeReader for Counting {
+    fn get_range(&self, start: u64, length: usize) -> Result<Vec<u8>> {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        self.data.get_range(start, length)
+    }
+
+    fn size(&self) -> Result<u64> {
+        self.data.size()
+    }
+}
+
+/// Writes a file with a column of `rows` rows in pages of 10 rows
+fn write_file(rows: i32) -> Result<Vec<u8>> {
+    let schema = SchemaDescriptor::try_from_message("message schema { required int32 a; }")?;
+    let options = WriteOptions {
+        write_statistics: true,
+        version: Version::V1,
+        write_page_crc: false,
+    };
+
+    let mut column = NativeColumnWriter::<i32>::new(
+        schema.columns()[0].descriptor.clone(),
+        options,
+        ColumnWriterOptions {
+            max_page_size: 10 * 4,
+            ..Default::default()
+        },
+    )?;
+    column.write(&(0..rows).collect::<Vec<_>>(), Levels::Required)?;
+    let pages = column.finish()?;
+
+    let mut writer = FileWriter::new(Cursor::new(vec![]), schema, options, None);
+    let columns = std::iter::once(Ok(DynStreamingIterator::new(
+        fallible_streaming_iterator::convert(pages.iter().map(Result::Ok)),
+    )));
+    writer.write(DynIter::new(columns))?;
+    writer.end(None)?;
+    Ok(writer.into_inner().into_inner())
+}
+
+#[test]
+fn footer_read_size() -> Result<()> {
+    let reader = Counting {
+        data: write_file(100)?,
+        requests: AtomicUsize::new(0),
+    };
+    let requests = || reader.requests.swap(0, Ordering::Relaxed);
+    let expected = read_metadata(&mut Cursor::new(&reader.data))?;
+
+    // the whole footer (and page index) is within the default 64 KiB
+    let options = MetadataOptions {
+        prefetch_page_index: true,
+        ..Default::default()
+    };
+    let footer = read_metadata_with_options(&mut RangeCursor::new(&reader), &options)?;
+    assert_eq!(requests(), 1);
+    assert_eq!(footer.metadata.num_rows, expected.num_rows);
+    assert!(footer.page_index.is_some());
+
+    // the footer is read again when it is larger than the bytes read
+    let options = MetadataOptions {
+        footer_read_size: 8,
+        prefetch_page_index: false,
+    };
+    let footer = read_metadata_with_options(&mut RangeCursor::new(&reader), &options)?;
+    assert_eq!(requests(), 2);
+    assert!(footer.page_index.is_none());
+
+    // and the page index with a third read
+    let options = MetadataOptions {
+        footer_read_size: 8,
+        prefetch_page_index: true,
+    };
+    let footer = read_metadata_with_options(&mut RangeCursor::new(&reader), &options)?;
+    assert_eq!(requests(), 3);
+    assert_eq!(footer.metadata.row_groups.len(), expected.row_groups.len());
+    Ok(())
+}
+
+#[test]
+fn prefetched_page_index() -> Result<()> {
+    let data = write_file(100)?;
+    let columns = read_metadata(&mut Cursor::new(&data))?.row_groups[0]
+        .columns()
+        .to_vec();
+    let expected_indexes = read_columns_indexes(&mut Cursor::new(&data), &columns)?;
+    let expected_locations = read_pages_locations(&mut Cursor::new(&data), &columns)?;
+    assert_eq!(expected_locations[0].len(), 10);
+
+    // both when the page index is within the bytes read for the footer, and when it isn't
+    for footer_read_size in [64 * 1024, 100, 8] {
+        let options = MetadataOptions {
+            footer_read_size,
+            prefetch_page_index: true,
+        };
+        let footer = read_metadata_with_options(&mut Cursor::new(&data), &options)?;
+        let mut page_index = footer.page_index.unwrap();
+        let columns = footer.metadata.row_groups[0].columns();
+
+        let indexes = read_columns_indexes(&mut page_index, columns)?;
+        assert_eq!(format!("{:?}", indexes), format!("{:?}", expected_indexes));
+        let locations = read_pages_locations(&mut page_index, columns)?;
+        assert_eq!(locations, expected_locations);
+    }
+    Ok(())
+}
+
+#[test]
+fn cache() -> Result<()> {
+    let dir = std::env::temp_dir().join(format!("parquet2-metadata-cache-{}", std::process::id()));
+    std::fs::create_dir_all(&dir)?;
+    let a = dir.join("a.parquet");
+    let b = dir.join("b.parquet");
+    std::fs::write(&a, write_file(100)?)?;
+    std::fs::write(&b, write_file(10)?)?;
+
+    let cache = MetadataCache::new(1, MetadataOptions::default());
+    let read = |path: &Path| cache.read(path);
+
+    let first = read(&a)?;
+    assert!(Arc::ptr_eq(&first, &read(&a)?));
+    assert_eq!(first.metadata.num_rows, 100);
+
+    // a file that changed is read again
+    std::fs::write(&a, write_file(20)?)?;
+    let changed = read(&a)?;
+    assert!(!Arc::ptr_eq(&first, &changed));
+    assert_eq!(changed.metadata.num_rows, 20);
+    assert_eq!(cache.len(), 1);
+
+    // the least recently used file is evicted
+    assert_eq!(read(&b)?.metadata.num_rows, 10);
+    assert_eq!(cache.len(), 1);
+    let size = std::fs::metadata(&a)?.len();
+    let modified = std::fs::metadata(&a)?.modified().ok();
+    assert!(cache.get(&a, size, modified).is_none());
+
+    // footers of files that are not local are cached by the given size and modification time
+    let data = write_file(30)?;
+    let footer = cache.get_or_read(
+        &mut Cursor::new(&data),
+        Path::new("s3://bucket/c.parquet"),
+        data.len() as u64,
+        None,
+    )?;
+    assert!(Arc::ptr_eq(
+        &footer,
+        &cache
+            .get(Path::new("s3://bucket/c.parquet"), data.len() as u64, None)
+            .unwrap()
+    ));
+
+    cache.clear();
+    assert!(cache.is_empty());
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
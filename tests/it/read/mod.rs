@@ -3,12 +3,19 @@
 /// but OTOH it has no external dependencies and is very familiar to Rust developers.
 mod binary;
 mod boolean;
+mod coalesce;
+mod delta_bitpacked;
+mod delta_byte_array;
 mod deserialize;
 mod dictionary;
+mod field_id;
 mod fixed_binary;
 mod indexes;
+mod metadata;
+mod predicate;
 mod primitive;
 mod primitive_nested;
+mod range;
 mod struct_;
 mod utils;
 
@@ -450,7 +457,32 @@ fn test_pyarrow_integration(
         version, use_dictionary_s, compression, file, required_s
     );
 
-    let (array, statistics) = get_column(&path, column)?;
+    test_pyarrow_file(&path, file, column, required)
+}
+
+/// Tests a pyarrow file whose columns were written with a specific encoding
+fn test_pyarrow_integration_encoded(
+    file: &str,
+    column: &str,
+    version: usize,
+    required: bool,
+    encoding: &str,
+) -> Result<()> {
+    if std::env::var("PARQUET2_IGNORE_PYARROW_TESTS").is_ok() {
+        return Ok(());
+    }
+    let required_s = if required { "required" } else { "nullable" };
+
+    let path = format!(
+        "fixtures/pyarrow3/v{}/{}/{}_{}_10.parquet",
+        version, encoding, file, required_s
+    );
+
+    test_pyarrow_file(&path, file, column, required)
+}
+
+fn test_pyarrow_file(path: &str, file: &str, column: &str, required: bool) -> Result<()> {
+    let (array, statistics) = get_column(path, column)?;
 
     let expected = match (file, required) {
         ("basic", true) => pyarrow_required(column),
@@ -595,6 +627,26 @@ fn pyarrow_v1_non_dict_fixed_binary_optional() -> Result<()> {
     test_pyarrow_integration("basic", "fixed_binary", 1, false, false, "")
 }
 
+#[test]
+fn pyarrow_v1_byte_stream_split_float64_required() -> Result<()> {
+    test_pyarrow_integration_encoded("basic", "float64", 1, true, "byte_stream_split")
+}
+
+#[test]
+fn pyarrow_v1_byte_stream_split_float64_optional() -> Result<()> {
+    test_pyarrow_integration_encoded("basic", "float64", 1, false, "byte_stream_split")
+}
+
+#[test]
+fn pyarrow_v2_byte_stream_split_float64_required() -> Result<()> {
+    test_pyarrow_integration_encoded("basic", "float64", 2, true, "byte_stream_split")
+}
+
+#[test]
+fn pyarrow_v2_byte_stream_split_float64_optional() -> Result<()> {
+    test_pyarrow_integration_encoded("basic", "float64", 2, false, "byte_stream_split")
+}
+
 #[test]
 fn pyarrow_v1_dict_list_optional() -> Result<()> {
     test_pyarrow_integration("nested", "list_int64", 1, false, true, "")
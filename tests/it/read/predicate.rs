@@ -0,0 +1,414 @@
+use std::io::Cursor;
+
+use parquet2::error::{Error, Result};
+use parquet2::fallible_streaming_iterator;
+use parquet2::indexes::{BoundaryOrder, ByteIndex, Index, Interval, NativeIndex};
+use parquet2::metadata::{ColumnOrder, SchemaDescriptor, SortOrder};
+use parquet2::read::{
+    prune_pages, prune_row_groups, read_columns_indexes, read_filtered_pages, read_metadata,
+    read_pages_locations, BasicDecompressor, IndexedPageReader, Predicate, Value,
+};
+use parquet2::write::{
+    BinaryColumnWriter, ColumnWriterOptions, DynIter, DynStreamingIterator, FileWriter,
+    FixedLenColumnWriter, Levels, NativeColumnWriter, Version, WriteOptions,
+};
+
+use super::collect;
+use crate::Array;
+
+/// Writes a file with one row group per item of `row_groups`
+fn write_file(row_groups: &[(&[i32], &[Option<&str>])]) -> Result<Vec<u8>> {
+    let schema = SchemaDescriptor::try_from_message(
+        "message schema { required int32 a; optional binary b (STRING); }",
+    )?;
+    let options = WriteOptions {
+        write_statistics: true,
+        version: Version::V1,
+        write_page_crc: false,
+    };
+
+    let mut writer = FileWriter::new(Cursor::new(vec![]), schema.clone(), options, None);
+    for (a_values, b_values) in row_groups {
+        let mut a = NativeColumnWriter::<i32>::new(
+            schema.columns()[0].descriptor.clone(),
+            options,
+            ColumnWriterOptions::default(),
+        )?;
+        a.write(a_values, Levels::Required)?;
+
+        let mut b = BinaryColumnWriter::new(
+            schema.columns()[1].descriptor.clone(),
+            options,
+            ColumnWriterOptions::default(),
+        )?;
+        let validity = b_values.iter().map(|x| x.is_some()).collect::<Vec<_>>();
+        b.write(b_values.iter().flatten(), Levels::Optional(&validity))?;
+
+        let pages = [a.finish()?, b.finish()?];
+        let columns = pages.iter().map(|pages| {
+            Ok(DynStreamingIterator::new(
+                fallible_streaming_iterator::convert(pages.iter().map(Result::Ok)),
+            ))
+        });
+        writer.write(DynIter::new(columns))?;
+    }
+    writer.end(None)?;
+    Ok(writer.into_inner().into_inner())
+}
+
+#[test]
+fn prune() -> Result<()> {
+    let data = write_file(&[
+        (&[1, 2, 3], &[Some("a"), Some("b"), None]),
+        (&[4, 5, 6], &[None, None, None]),
+        (&[7, 8, 9], &[Some("x"), Some("é"), Some("y")]),
+    ])?;
+    let mut metadata = read_metadata(&mut Cursor::new(&data))?;
+
+    let a = || vec!["a".to_string()];
+    let b = || vec!["b".to_string()];
+
+    let cases = [
+        (Predicate::Eq(a(), Value::Int32(5)), vec![1]),
+        (Predicate::GtEq(a(), Value::Int32(3)), vec![0, 1, 2]),
+        (
+            Predicate::Between(a(), Value::Int32(4), Value::Int32(6)),
+            vec![1],
+        ),
+        (
+            Predicate::In(a(), vec![Value::Int32(0), Value::Int32(9)]),
+            vec![2],
+        ),
+        (Predicate::IsNull(b()), vec![0, 1]),
+        (!Predicate::IsNull(b()), vec![0, 2]),
+        (
+            Predicate::Lt(a(), Value::Int32(4)).or(Predicate::Eq(b(), Value::Bytes(b"x".to_vec()))),
+            vec![0, 2],
+        ),
+        (
+            Predicate::Gt(a(), Value::Int32(3)).and(!Predicate::IsNull(b())),
+            vec![2],
+        ),
+    ];
+    for (predicate, expected) in cases {
+        assert_eq!(
+            prune_row_groups(&metadata, &predicate)?,
+            expected,
+            "{:?}",
+            predicate
+        );
+    }
+
+    // the writer writes the column orders, so strings are compared as unsigned bytes and
+    // "é" is larger than "z"
+    assert_eq!(
+        metadata.column_orders,
+        Some(vec![
+            ColumnOrder::TypeDefinedOrder(SortOrder::Signed),
+            ColumnOrder::TypeDefinedOrder(SortOrder::Unsigned),
+        ])
+    );
+    let gt_z = Predicate::Gt(b(), Value::Bytes("z".as_bytes().to_vec()));
+    assert_eq!(prune_row_groups(&metadata, &gt_z)?, vec![2]);
+    // without column orders, the statistics of strings follow the legacy signed order and
+    // can't be used
+    metadata.column_orders = None;
+    assert_eq!(prune_row_groups(&metadata, &gt_z)?, vec![0, 2]);
+
+    assert!(prune_row_groups(&metadata, &Predicate::IsNull(vec!["c".to_string()])).is_err());
+    assert!(prune_row_groups(&metadata, &Predicate::Eq(a(), Value::Int64(1))).is_err());
+    Ok(())
+}
+
+#[test]
+fn prune_decimals() -> Result<()> {
+    let schema = SchemaDescriptor::try_from_message(
+        "message schema { required fixed_len_byte_array(2) d (DECIMAL(4,2)); }",
+    )?;
+    let options = WriteOptions {
+        write_statistics: true,
+        version: Version::V1,
+        write_page_crc: false,
+    };
+
+    // a page per value: the statistics of the column chunk are merged from those of its pages
+    let mut d = FixedLenColumnWriter::new(
+        schema.columns()[0].descriptor.clone(),
+        options,
+        ColumnWriterOptions {
+            max_page_size: 1,
+            ..Default::default()
+        },
+    )?;
+    // 1 and -2 as big-endian two's complement
+    d.write([[0u8, 1], [0xFF, 0xFE]], Levels::Required)?;
+    let pages = d.finish()?;
+    assert_eq!(pages.len(), 2);
+
+    let mut writer = FileWriter::new(Cursor::new(vec![]), schema, options, None);
+    let columns = std::iter::once(Ok(DynStreamingIterator::new(
+        fallible_streaming_iterator::convert(pages.iter().map(Result::Ok)),
+    )));
+    writer.write(DynIter::new(columns))?;
+    writer.end(None)?;
+    let metadata = read_metadata(&mut Cursor::new(writer.into_inner().into_inner()))?;
+
+    let d = || vec!["d".to_string()];
+    let cases = [
+        (Predicate::Eq(d(), Value::Bytes(vec![0, 1])), vec![0]),
+        (Predicate::Eq(d(), Value::Bytes(vec![0xFF, 0xFE])), vec![0]),
+        (Predicate::Eq(d(), Value::Bytes(vec![0, 0])), vec![0]),
+        (Predicate::Eq(d(), Value::Bytes(vec![0, 2])), vec![]),
+        (Predicate::Lt(d(), Value::Bytes(vec![0xFF, 0xFE])), vec![]),
+        (Predicate::Gt(d(), Value::Bytes(vec![0, 1])), vec![]),
+    ];
+    for (predicate, expected) in cases {
+        assert_eq!(
+            prune_row_groups(&metadata, &predicate)?,
+            expected,
+            "{:?}",
+            predicate
+        );
+    }
+    Ok(())
+}
+
+/// Writes a file with a row group of 100 rows, where `a` is the row number in pages of 10
+/// rows and `b` is `99 - a` in pages of 25 rows.
+fn write_paged_file(write_statistics: bool) -> Result<Vec<u8>> {
+    let schema = SchemaDescriptor::try_from_message(
+        "message schema { required int32 a; required int64 b; }",
+    )?;
+    let options = WriteOptions {
+        write_statistics,
+        version: Version::V1,
+        write_page_crc: false,
+    };
+
+    let mut a = NativeColumnWriter::<i32>::new(
+        schema.columns()[0].descriptor.clone(),
+        options,
+        ColumnWriterOptions {
+            max_page_size: 10 * 4,
+            ..Default::default()
+        },
+    )?;
+    a.write(&(0..100).collect::<Vec<_>>(), Levels::Required)?;
+    let mut b = NativeColumnWriter::<i64>::new(
+        schema.columns()[1].descriptor.clone(),
+        options,
+        ColumnWriterOptions {
+            max_page_size: 25 * 8,
+            ..Default::default()
+        },
+    )?;
+    b.write(&(0..100).rev().collect::<Vec<_>>(), Levels::Required)?;
+    let pages = [a.finish()?, b.finish()?];
+
+    let mut writer = FileWriter::new(Cursor::new(vec![]), schema, options, None);
+    let columns = pages.iter().map(|pages| {
+        Ok(DynStreamingIterator::new(
+            fallible_streaming_iterator::convert(pages.iter().map(Result::Ok)),
+        ))
+    });
+    writer.write(DynIter::new(columns))?;
+    writer.end(None)?;
+    Ok(writer.into_inner().into_inner())
+}
+
+#[test]
+fn prune_rows() -> Result<()> {
+    let data = write_paged_file(true)?;
+    let mut reader = Cursor::new(data);
+    let metadata = read_metadata(&mut reader)?;
+    let row_group = &metadata.row_groups[0];
+    let indexes = read_columns_indexes(&mut reader, row_group.columns())?;
+    let locations = read_pages_locations(&mut reader, row_group.columns())?;
+
+    let a = || vec!["a".to_string()];
+    let b = || vec!["b".to_string()];
+
+    let cases = [
+        (
+            Predicate::Between(a(), Value::Int32(15), Value::Int32(24)),
+            vec![Interval::new(10, 20)],
+        ),
+        // intersected across columns with different page boundaries
+        (
+            Predicate::GtEq(a(), Value::Int32(20)).and(Predicate::GtEq(b(), Value::Int64(60))),
+            vec![Interval::new(20, 30)],
+        ),
+        (
+            Predicate::Lt(a(), Value::Int32(5)).or(Predicate::Lt(b(), Value::Int64(10))),
+            vec![Interval::new(0, 10), Interval::new(75, 25)],
+        ),
+        (
+            !Predicate::GtEq(a(), Value::Int32(10)),
+            vec![Interval::new(0, 10)],
+        ),
+        (Predicate::IsNull(a()), vec![]),
+    ];
+    for (predicate, expected) in cases {
+        assert_eq!(
+            prune_pages(&metadata, row_group, &predicate, &indexes, &locations)?,
+            expected,
+            "{:?}",
+            predicate
+        );
+    }
+
+    // without page indexes, all rows may match
+    assert_eq!(
+        prune_pages(&metadata, row_group, &Predicate::IsNull(a()), &[], &[])?,
+        vec![Interval::new(0, 100)]
+    );
+    Ok(())
+}
+
+#[test]
+fn ordered_string_pages() -> Result<()> {
+    let schema =
+        SchemaDescriptor::try_from_message("message schema { required binary s (STRING); }")?;
+    let options = WriteOptions {
+        write_statistics: true,
+        version: Version::V1,
+        write_page_crc: false,
+    };
+    // pages of 10 values of 6 bytes each (length and value)
+    let mut s = BinaryColumnWriter::new(
+        schema.columns()[0].descriptor.clone(),
+        options,
+        ColumnWriterOptions {
+            max_page_size: 10 * 6,
+            ..Default::default()
+        },
+    )?;
+    let values = (0..100).map(|x| format!("{:02}", x)).collect::<Vec<_>>();
+    s.write(&values, Levels::Required)?;
+    let pages = s.finish()?;
+
+    let mut writer = FileWriter::new(Cursor::new(vec![]), schema, options, None);
+    let columns = std::iter::once(Ok(DynStreamingIterator::new(
+        fallible_streaming_iterator::convert(pages.iter().map(Result::Ok)),
+    )));
+    writer.write(DynIter::new(columns))?;
+    writer.end(None)?;
+
+    let mut reader = Cursor::new(writer.into_inner().into_inner());
+    let metadata = read_metadata(&mut reader)?;
+    let row_group = &metadata.row_groups[0];
+    let indexes = read_columns_indexes(&mut reader, row_group.columns())?;
+    let locations = read_pages_locations(&mut reader, row_group.columns())?;
+    let index = indexes[0].as_any().downcast_ref::<ByteIndex>().unwrap();
+    assert_eq!(index.boundary_order, BoundaryOrder::Ascending);
+
+    let eq = Predicate::Eq(vec!["s".to_string()], Value::Bytes(b"42".to_vec()));
+    assert_eq!(
+        prune_pages(&metadata, row_group, &eq, &indexes, &locations)?,
+        vec![Interval::new(40, 10)]
+    );
+
+    // the column orders are written, so row groups are also pruned by strings
+    let gt = Predicate::Gt(vec!["s".to_string()], Value::Bytes(b"99".to_vec()));
+    assert!(prune_row_groups(&metadata, &gt)?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn ordered_pages() -> Result<()> {
+    let data = write_paged_file(true)?;
+    let mut reader = Cursor::new(data);
+    let metadata = read_metadata(&mut reader)?;
+    let row_group = &metadata.row_groups[0];
+    let indexes = read_columns_indexes(&mut reader, row_group.columns())?;
+    let locations = read_pages_locations(&mut reader, row_group.columns())?;
+
+    // `a` is ascending and `b` descending
+    let a = indexes[0]
+        .as_any()
+        .downcast_ref::<NativeIndex<i32>>()
+        .unwrap();
+    assert_eq!(a.boundary_order, BoundaryOrder::Ascending);
+    let b = indexes[1]
+        .as_any()
+        .downcast_ref::<NativeIndex<i64>>()
+        .unwrap();
+    assert_eq!(b.boundary_order, BoundaryOrder::Descending);
+
+    // the same indexes without order, pruned without binary search
+    let mut a = a.clone();
+    a.boundary_order = BoundaryOrder::Unordered;
+    let mut b = b.clone();
+    b.boundary_order = BoundaryOrder::Unordered;
+    let unordered: Vec<Box<dyn Index>> = vec![Box::new(a), Box::new(b)];
+
+    let a = || vec!["a".to_string()];
+    let b = || vec!["b".to_string()];
+    let mut predicates = vec![];
+    for value in [-1, 0, 9, 10, 15, 49, 50, 99, 100] {
+        predicates.extend([
+            Predicate::Eq(a(), Value::Int32(value)),
+            Predicate::Lt(a(), Value::Int32(value)),
+            Predicate::LtEq(a(), Value::Int32(value)),
+            Predicate::Gt(a(), Value::Int32(value)),
+            Predicate::GtEq(a(), Value::Int32(value)),
+            Predicate::Eq(b(), Value::Int64(value as i64)),
+            Predicate::Lt(b(), Value::Int64(value as i64)),
+            Predicate::LtEq(b(), Value::Int64(value as i64)),
+            Predicate::Gt(b(), Value::Int64(value as i64)),
+            Predicate::GtEq(b(), Value::Int64(value as i64)),
+            Predicate::Between(a(), Value::Int32(value), Value::Int32(value + 12)),
+            Predicate::Between(
+                b(),
+                Value::Int64(value as i64),
+                Value::Int64(value as i64 + 30),
+            ),
+            Predicate::Lt(a(), Value::Int32(value)).or(Predicate::Gt(b(), Value::Int64(80))),
+            Predicate::Gt(a(), Value::Int32(value)).and(Predicate::Gt(b(), Value::Int64(30))),
+        ]);
+    }
+    for predicate in predicates {
+        assert_eq!(
+            prune_pages(&metadata, row_group, &predicate, &indexes, &locations)?,
+            prune_pages(&metadata, row_group, &predicate, &unordered, &locations)?,
+            "{:?}",
+            predicate
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn read_filtered() -> Result<()> {
+    let data = write_paged_file(true)?;
+    let mut reader = Cursor::new(data);
+    let metadata = read_metadata(&mut reader)?;
+
+    let predicate = Predicate::Between(vec!["a".to_string()], Value::Int32(15), Value::Int32(24))
+        .and(Predicate::Gt(vec!["b".to_string()], Value::Int64(77)));
+    let mut pages = read_filtered_pages(&mut reader, &metadata, 0, &predicate)?;
+
+    // only the rows [10, 25) are read
+    let column = &metadata.row_groups[0].columns()[0];
+    let pages = IndexedPageReader::new(reader, column, pages.remove(0), vec![], vec![]);
+    let arrays = collect(
+        BasicDecompressor::new(pages, vec![]),
+        column.physical_type(),
+    )?;
+    assert_eq!(
+        arrays,
+        vec![
+            Array::Int32((10..20).map(Some).collect()),
+            Array::Int32((20..25).map(Some).collect()),
+        ]
+    );
+
+    // pages can't be filtered without a page index
+    let mut reader = Cursor::new(write_paged_file(false)?);
+    let metadata = read_metadata(&mut reader)?;
+    assert!(matches!(
+        read_filtered_pages(&mut reader, &metadata, 0, &predicate),
+        Err(Error::InvalidParameter(_))
+    ));
+    Ok(())
+}
@@ -3,11 +3,15 @@ use std::convert::TryInto;
 use super::{dictionary::PrimitivePageDict, Array};
 
 use parquet2::{
+    deserialize::{Nested, NestedDecoder, OptionalValues},
     encoding::{bitpacking, hybrid_rle::HybridRleDecoder, uleb128, Encoding},
     error::{Error, Result},
-    page::{split_buffer, DataPage},
-    read::levels::get_bit_width,
+    metadata::SchemaDescriptor,
+    page::{split_buffer, DataPage, Page},
+    read::{decompress, levels::get_bit_width},
+    schema::Repetition,
     types::NativeType,
+    write::{ColumnWriterOptions, Levels, NativeColumnWriter, Version, WriteOptions},
 };
 
 fn read_buffer<T: NativeType>(values: &[u8]) -> impl Iterator<Item = T> + '_ {
@@ -223,3 +227,120 @@ pub fn page_dict_to_array<T: NativeType>(
         _ => todo!(),
     }
 }
+
+/// Assembles the values of a page of a column of a list of optional values into a list with
+/// [`NestedDecoder`]
+fn decode_nested<I: Iterator<Item = i64>>(page: &DataPage, values: I) -> Result<Array> {
+    // optional group (LIST) { repeated group list { optional int64 element; } }
+    let mut decoder = NestedDecoder::try_new(
+        &page.descriptor,
+        &[
+            Repetition::Optional,
+            Repetition::Repeated,
+            Repetition::Optional,
+        ],
+    )?;
+    decoder.push_page(page)?;
+
+    let (validity, offsets, elements) = match decoder.finish().as_slice() {
+        [Nested::Field { validity, .. }, Nested::Repeated { offsets }, Nested::Field {
+            validity: elements, ..
+        }] => (validity.clone(), offsets.clone(), elements.clone()),
+        _ => unreachable!(),
+    };
+    let elements = OptionalValues::new(elements.into_iter(), values).collect::<Vec<_>>();
+
+    Ok(Array::List(
+        validity
+            .into_iter()
+            .zip(offsets.windows(2))
+            .map(|(is_valid, offsets)| {
+                is_valid.then(|| Array::Int64(elements[offsets[0]..offsets[1]].to_vec()))
+            })
+            .collect(),
+    ))
+}
+
+#[test]
+fn nested_decoder() -> Result<()> {
+    let schema = SchemaDescriptor::try_from_message(
+        "message schema { optional group list_int64 (LIST) { repeated group list { optional int64 element; } } }",
+    )?;
+    let column = &schema.columns()[0];
+    assert_eq!(
+        column.repetitions(),
+        vec![
+            Repetition::Optional,
+            Repetition::Repeated,
+            Repetition::Optional
+        ]
+    );
+
+    // the levels of `pyarrow_nested_optional`
+    let rep = [0, 1, 0, 0, 1, 1, 0, 1, 1, 0, 0, 1, 1, 0, 0];
+    let def = [3, 3, 0, 3, 2, 3, 3, 3, 3, 1, 3, 3, 3, 0, 3];
+    let values = (0..=10).collect::<Vec<i64>>();
+
+    let options = WriteOptions {
+        write_statistics: false,
+        version: Version::V1,
+        write_page_crc: false,
+    };
+    let mut writer = NativeColumnWriter::<i64>::new(
+        column.descriptor.clone(),
+        options,
+        ColumnWriterOptions {
+            max_page_size: 3 * 8,
+            ..Default::default()
+        },
+    )?;
+    writer.write(
+        &values,
+        Levels::Nested {
+            rep: &rep,
+            def: &def,
+        },
+    )?;
+    let pages = writer.finish()?;
+    assert!(pages.len() > 1);
+
+    // every page assembles into the same rows as the reference implementation
+    let mut decoder = NestedDecoder::try_new(&column.descriptor, &column.repetitions())?;
+    let mut arrays = vec![];
+    for page in pages {
+        let page = match decompress(page, &mut vec![])? {
+            Page::Data(page) => page,
+            _ => unreachable!(),
+        };
+        decoder.push_page(&page)?;
+
+        let (_, _, values) = split_buffer(&page)?;
+        let array = decode_nested(&page, read_buffer::<i64>(values))?;
+        assert_eq!(array, page_to_array::<i64>(&page, None)?);
+        arrays.push(array);
+    }
+
+    // the levels of all pages assemble into the rows of the column chunk
+    assert_eq!(decoder.num_rows(), 8);
+    assert_eq!(decoder.num_values(), values.len());
+    let nested = decoder.finish();
+    assert_eq!(
+        nested[1],
+        Nested::Repeated {
+            offsets: vec![0, 2, 2, 5, 8, 8, 11, 11, 12]
+        }
+    );
+
+    let result = arrays
+        .into_iter()
+        .flat_map(|array| match array {
+            Array::List(array) => array,
+            _ => unreachable!(),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        Array::List(result),
+        crate::pyarrow_nested_optional("list_int64")
+    );
+    Ok(())
+}
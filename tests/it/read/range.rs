@@ -0,0 +1,140 @@
+use std::io::Cursor;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use parquet2::bloom_filter;
+use parquet2::error::Result;
+use parquet2::fallible_streaming_iterator;
+use parquet2::metadata::SchemaDescriptor;
+use parquet2::read::{
+    get_page_iterator, read_columns_indexes, read_metadata, read_pages_locations,
+    BasicDecompressor, CachingRangeReader, CoalesceOptions, RangeCursor, RangeReader, ReadPlan,
+};
+use parquet2::write::{
+    ColumnWriterOptions, DynIter, DynStreamingIterator, FileWriter, Levels, NativeColumnWriter,
+    Version, WriteOptions,
+};
+
+use super::collect;
+use crate::Array;
+
+/// An in-memory fake of an object in object storage, counting the requests made to it
+struct ObjectStore {
+    data: Vec<u8>,
+    requests: AtomicUsize,
+}
+
+impl RangeReader for ObjectStore {
+    fn get_range(&self, start: u64, length: usize) -> Result<Vec<u8>> {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        self.data.get_range(start, length)
+    }
+
+    fn size(&self) -> Result<u64> {
+        self.data.size()
+    }
+}
+
+/// Writes a file with a column of 1000 rows in pages of 100 rows and a bloom filter
+fn write_file() -> Result<Vec<u8>> {
+    let schema = SchemaDescriptor::try_from_message("message schema { required int64 a; }")?;
+    let options = WriteOptions {
+        write_statistics: true,
+        version: Version::V1,
+        write_page_crc: false,
+    };
+
+    let values = (0..1000).collect::<Vec<i64>>();
+    let mut column = NativeColumnWriter::<i64>::new(
+        schema.columns()[0].descriptor.clone(),
+        options,
+        ColumnWriterOptions {
+            max_page_size: 100 * 8,
+            ..Default::default()
+        },
+    )?;
+    column.write(&values, Levels::Required)?;
+    let pages = column.finish()?;
+
+    let mut writer = FileWriter::new(Cursor::new(vec![]), schema, options, None);
+    let columns = std::iter::once(Ok(DynStreamingIterator::new(
+        fallible_streaming_iterator::convert(pages.iter().map(Result::Ok)),
+    )));
+    writer.write(DynIter::new(columns))?;
+
+    let mut bitset = vec![0; bloom_filter::optimal_num_of_bytes(values.len(), 0.01)?];
+    for value in &values {
+        bloom_filter::insert(&mut bitset, bloom_filter::hash_native(*value));
+    }
+    writer.set_bloom_filter(0, 0, bitset)?;
+    writer.end(None)?;
+    Ok(writer.into_inner().into_inner())
+}
+
+#[test]
+fn object_store() -> Result<()> {
+    let data = write_file()?;
+    let expected = (0..10)
+        .map(|page| Array::Int64((page * 100..(page + 1) * 100).map(Some).collect()))
+        .collect::<Vec<_>>();
+
+    let store = ObjectStore {
+        data,
+        requests: AtomicUsize::new(0),
+    };
+    let reader = CachingRangeReader::new(&store, 64 * 1024);
+
+    let metadata = read_metadata(&mut RangeCursor::new(&reader))?;
+    let column = &metadata.row_groups[0].columns()[0];
+
+    let indexes =
+        read_columns_indexes(&mut RangeCursor::new(&reader), std::slice::from_ref(column))?;
+    assert_eq!(indexes.len(), 1);
+    let locations =
+        read_pages_locations(&mut RangeCursor::new(&reader), std::slice::from_ref(column))?;
+    assert_eq!(locations[0].len(), 10);
+
+    let mut bitset = vec![];
+    bloom_filter::read(column, &mut RangeCursor::new(&reader), &mut bitset)?;
+    assert!(bloom_filter::is_in_set(
+        &bitset,
+        bloom_filter::hash_native(999i64)
+    ));
+
+    let pages = get_page_iterator(column, RangeCursor::new(&reader), None, vec![], 1024)?;
+    let arrays = collect(
+        BasicDecompressor::new(pages, vec![]),
+        column.physical_type(),
+    )?;
+    assert_eq!(arrays, expected);
+
+    // the whole (small) file fits in the first block
+    assert_eq!(store.requests.load(Ordering::Relaxed), 1);
+
+    // read plans fetch each coalesced range with a single request
+    let plan = ReadPlan::from_metadata(&metadata, &[0], &[0], CoalesceOptions::default());
+    let fetched = plan.read_ranges(&store)?;
+    assert_eq!(store.requests.load(Ordering::Relaxed), 2);
+    let pages = fetched.pages(&plan.chunks()[0], None, vec![], 1024)?;
+    let arrays = collect(
+        BasicDecompressor::new(pages, vec![]),
+        column.physical_type(),
+    )?;
+    assert_eq!(arrays, expected);
+    Ok(())
+}
+
+#[test]
+fn file() -> Result<()> {
+    let data = write_file()?;
+    let path = std::env::temp_dir().join(format!("parquet2-range-{}.parquet", std::process::id()));
+    std::fs::write(&path, &data)?;
+    let file = std::fs::File::open(&path)?;
+
+    assert_eq!(file.size()?, data.len() as u64);
+    assert_eq!(file.get_range(0, 4)?, b"PAR1");
+    let metadata = read_metadata(&mut RangeCursor::new(&file))?;
+    assert_eq!(metadata.num_rows, 1000);
+
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
@@ -0,0 +1,234 @@
+use std::io::Cursor;
+
+use parquet2::bloom_filter;
+use parquet2::compression::CompressionOptions;
+use parquet2::error::Result;
+use parquet2::metadata::SchemaDescriptor;
+use parquet2::read::{read_metadata, Value};
+use parquet2::schema::types::{ParquetType, PhysicalType};
+use parquet2::write::WriteOptions;
+use parquet2::write::{
+    Compressor, DynIter, DynStreamingIterator, FileStreamer, FileWriter, Version,
+};
+
+use super::primitive::array_to_page_v1;
+
+fn array() -> Vec<Option<i64>> {
+    vec![
+        Some(0i64),
+        Some(1),
+        None,
+        Some(3),
+        Some(4),
+        Some(5),
+        Some(6),
+    ]
+}
+
+fn schema() -> SchemaDescriptor {
+    SchemaDescriptor::new(
+        "schema".to_string(),
+        vec![ParquetType::from_physical(
+            "col1".to_string(),
+            PhysicalType::Int64,
+        )],
+    )
+}
+
+fn bitset(array: &[Option<i64>]) -> Result<Vec<u8>> {
+    let mut bitset = vec![0; bloom_filter::optimal_num_of_bytes(array.len(), 0.01)?];
+    array
+        .iter()
+        .flatten()
+        .for_each(|x| bloom_filter::insert(&mut bitset, bloom_filter::hash_native(*x)));
+    Ok(bitset)
+}
+
+fn write_file(with_bloom_filter: bool) -> Result<Vec<u8>> {
+    let array = array();
+
+    let options = WriteOptions {
+        write_statistics: true,
+        version: Version::V1,
+        write_page_crc: false,
+    };
+
+    let schema = schema();
+
+    let pages = vec![array_to_page_v1::<i64>(
+        &array,
+        &options,
+        &schema.columns()[0].descriptor,
+    )];
+
+    let pages = DynStreamingIterator::new(Compressor::new(
+        DynIter::new(pages.into_iter()),
+        CompressionOptions::Uncompressed,
+        vec![],
+    ));
+    let columns = std::iter::once(Ok(pages));
+
+    let writer = Cursor::new(vec![]);
+    let mut writer = FileWriter::new(writer, schema, options, None);
+
+    writer.write(DynIter::new(columns))?;
+
+    if with_bloom_filter {
+        writer.set_bloom_filter(0, 0, bitset(&array)?)?;
+        assert!(writer.set_bloom_filter(1, 0, vec![0; 32]).is_err());
+    }
+
+    writer.end(None)?;
+
+    Ok(writer.into_inner().into_inner())
+}
+
+#[test]
+fn write_and_read() -> Result<()> {
+    let data = write_file(true)?;
+    let mut reader = Cursor::new(data);
+
+    let metadata = read_metadata(&mut reader)?;
+    let column = &metadata.row_groups[0].columns()[0];
+    assert!(column.metadata().bloom_filter_offset.is_some());
+
+    let mut bitset = vec![];
+    bloom_filter::read(column, &mut reader, &mut bitset)?;
+    assert_eq!(bitset.len(), 32);
+
+    for value in [0i64, 1, 3, 4, 5, 6] {
+        assert!(bloom_filter::is_in_set(
+            &bitset,
+            bloom_filter::hash_native(value)
+        ));
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn write_and_read_async() -> Result<()> {
+    let data = write_file(true)?;
+    let metadata = read_metadata(&mut Cursor::new(&data))?;
+    let column = &metadata.row_groups[0].columns()[0];
+
+    let mut bitset = vec![];
+    bloom_filter::read_async(column, &mut futures::io::Cursor::new(data), &mut bitset).await?;
+    assert_eq!(bitset.len(), 32);
+
+    for value in [0i64, 1, 3, 4, 5, 6] {
+        assert!(bloom_filter::is_in_set(
+            &bitset,
+            bloom_filter::hash_native(value)
+        ));
+    }
+    Ok(())
+}
+
+async fn write_file_async() -> Result<Vec<u8>> {
+    let array = array();
+
+    let options = WriteOptions {
+        write_statistics: true,
+        version: Version::V1,
+        ..Default::default()
+    };
+
+    let schema = schema();
+
+    let pages = vec![array_to_page_v1::<i64>(
+        &array,
+        &options,
+        &schema.columns()[0].descriptor,
+    )];
+
+    let pages = DynStreamingIterator::new(Compressor::new(
+        DynIter::new(pages.into_iter()),
+        CompressionOptions::Uncompressed,
+        vec![],
+    ));
+    let columns = std::iter::once(Ok(pages));
+
+    let writer = futures::io::Cursor::new(vec![]);
+    let mut writer = FileStreamer::new(writer, schema, options, None);
+
+    writer.write(DynIter::new(columns)).await?;
+
+    writer.set_bloom_filter(0, 0, bitset(&array)?)?;
+    assert!(writer.set_bloom_filter(1, 0, vec![0; 32]).is_err());
+
+    writer.end(None).await?;
+
+    Ok(writer.into_inner().into_inner())
+}
+
+#[tokio::test]
+async fn write_async_and_read() -> Result<()> {
+    let data = write_file_async().await?;
+    let mut reader = Cursor::new(data);
+
+    let metadata = read_metadata(&mut reader)?;
+    let column = &metadata.row_groups[0].columns()[0];
+    assert!(column.metadata().bloom_filter_offset.is_some());
+
+    let mut bitset = vec![];
+    bloom_filter::read(column, &mut reader, &mut bitset)?;
+    assert_eq!(bitset.len(), 32);
+
+    for value in [0i64, 1, 3, 4, 5, 6] {
+        assert!(bloom_filter::is_in_set(
+            &bitset,
+            bloom_filter::hash_native(value)
+        ));
+    }
+    Ok(())
+}
+
+#[test]
+fn without_bloom_filter() -> Result<()> {
+    let data = write_file(false)?;
+    let mut reader = Cursor::new(data);
+
+    let metadata = read_metadata(&mut reader)?;
+    let column = &metadata.row_groups[0].columns()[0];
+    assert!(column.metadata().bloom_filter_offset.is_none());
+
+    let mut bitset = vec![1];
+    bloom_filter::read(column, &mut reader, &mut bitset)?;
+    assert!(bitset.is_empty());
+    Ok(())
+}
+
+#[test]
+fn prune() -> Result<()> {
+    let data = write_file(true)?;
+    let mut reader = Cursor::new(data);
+    let metadata = read_metadata(&mut reader)?;
+    let path = &["col1".to_string()];
+
+    let mut filters = bloom_filter::BloomFilters::new(&metadata);
+    let skipped = filters.prune_row_groups(&mut reader, path, &[Value::Int64(100)])?;
+    assert_eq!(skipped, vec![0]);
+    let values = &[Value::Int64(100), Value::Int64(3)];
+    let skipped = filters.prune_row_groups(&mut reader, path, values)?;
+    assert!(skipped.is_empty());
+    assert_eq!(filters.get(&mut reader, 0, 0)?.map(|x| x.len()), Some(32));
+
+    assert!(filters
+        .prune_row_groups(&mut reader, path, &[Value::Int32(100)])
+        .is_err());
+    Ok(())
+}
+
+#[test]
+fn prune_without_bloom_filter() -> Result<()> {
+    let data = write_file(false)?;
+    let mut reader = Cursor::new(data);
+    let metadata = read_metadata(&mut reader)?;
+
+    let mut filters = bloom_filter::BloomFilters::new(&metadata);
+    let path = &["col1".to_string()];
+    let skipped = filters.prune_row_groups(&mut reader, path, &[Value::Int64(100)])?;
+    assert!(skipped.is_empty());
+    assert!(filters.get(&mut reader, 0, 0)?.is_none());
+    Ok(())
+}
@@ -0,0 +1,235 @@
+use std::io::Cursor;
+use std::sync::Arc;
+
+use futures::TryStreamExt;
+
+use parquet2::compression::CompressionOptions;
+use parquet2::error::{Error, Result};
+use parquet2::indexes::{select_pages, Interval};
+use parquet2::metadata::{FileMetaData, SchemaDescriptor};
+use parquet2::read::{
+    get_page_iterator, get_page_stream_with_page_meta, read_metadata, read_pages_locations,
+    BasicDecompressor, IndexedPageReader, PageMetaData,
+};
+use parquet2::schema::types::{ParquetType, PhysicalType};
+use parquet2::write::WriteOptions;
+use parquet2::write::{Compressor, DynIter, DynStreamingIterator, FileWriter, Version};
+
+use crate::read::collect;
+use crate::Array;
+
+use super::primitive::array_to_page_v1;
+
+fn schema() -> SchemaDescriptor {
+    SchemaDescriptor::new(
+        "schema".to_string(),
+        vec![ParquetType::from_physical(
+            "col1".to_string(),
+            PhysicalType::Int32,
+        )],
+    )
+}
+
+fn options(write_page_crc: bool) -> WriteOptions {
+    WriteOptions {
+        write_statistics: true,
+        version: Version::V1,
+        write_page_crc,
+    }
+}
+
+fn write_file(write_page_crc: bool) -> Result<Vec<u8>> {
+    let writer = FileWriter::new(Cursor::new(vec![]), schema(), options(write_page_crc), None);
+    write(writer)
+}
+
+/// Writes two pages of a single column with `writer`
+fn write(mut writer: FileWriter<Cursor<Vec<u8>>>) -> Result<Vec<u8>> {
+    let page1 = vec![Some(0), Some(1), None, Some(3), Some(4), Some(5), Some(6)];
+    let page2 = vec![Some(10), Some(11)];
+
+    let options = *writer.options();
+    let descriptor = &writer.schema().columns()[0].descriptor;
+    let pages = vec![
+        array_to_page_v1::<i32>(&page1, &options, descriptor)?,
+        array_to_page_v1::<i32>(&page2, &options, descriptor)?,
+    ];
+
+    let pages = DynStreamingIterator::new(Compressor::new(
+        DynIter::new(pages.into_iter().map(Ok)),
+        CompressionOptions::Snappy,
+        vec![],
+    ));
+    let columns = std::iter::once(Ok(pages));
+
+    writer.write(DynIter::new(columns))?;
+    writer.end(None)?;
+
+    Ok(writer.into_inner().into_inner())
+}
+
+fn read_column(data: &[u8], metadata: &FileMetaData) -> Result<Vec<Array>> {
+    let column = &metadata.row_groups[0].columns()[0];
+    let pages = get_page_iterator(column, Cursor::new(data), None, vec![], usize::MAX)?
+        .with_crc_verification(true);
+    collect(
+        BasicDecompressor::new(pages, vec![]),
+        column.physical_type(),
+    )
+}
+
+fn read_indexed(data: &[u8], metadata: &FileMetaData) -> Result<Vec<Array>> {
+    let columns = metadata.row_groups[0].columns();
+    let locations = read_pages_locations(&mut Cursor::new(data), columns)?;
+    let pages = select_pages(&[Interval::new(0, 9)], &locations[0], 9)?;
+    let pages = IndexedPageReader::new(Cursor::new(data), &columns[0], pages, vec![], vec![])
+        .with_crc_verification(true);
+    collect(
+        BasicDecompressor::new(pages, vec![]),
+        columns[0].physical_type(),
+    )
+}
+
+async fn read_stream(data: &[u8], metadata: &FileMetaData) -> Result<usize> {
+    let column = &metadata.row_groups[0].columns()[0];
+    let mut reader = futures::io::Cursor::new(data);
+    let page_metadata = PageMetaData::from(column).with_crc_verification(true);
+    let pages = get_page_stream_with_page_meta(
+        page_metadata,
+        &mut reader,
+        vec![],
+        Arc::new(|_, _| true),
+        usize::MAX,
+    )
+    .await?;
+    let pages = pages.try_collect::<Vec<_>>().await?;
+    Ok(pages.len())
+}
+
+#[test]
+fn verify() -> Result<()> {
+    let data = write_file(true)?;
+    let metadata = read_metadata(&mut Cursor::new(&data))?;
+
+    let expected = vec![
+        Array::Int32(vec![
+            Some(0),
+            Some(1),
+            None,
+            Some(3),
+            Some(4),
+            Some(5),
+            Some(6),
+        ]),
+        Array::Int32(vec![Some(10), Some(11)]),
+    ];
+    assert_eq!(read_column(&data, &metadata)?, expected);
+    assert_eq!(read_indexed(&data, &metadata)?, expected);
+    Ok(())
+}
+
+#[test]
+fn corrupted() -> Result<()> {
+    let mut data = write_file(true)?;
+    let metadata = read_metadata(&mut Cursor::new(&data))?;
+
+    // corrupt the last byte of the first page
+    let locations =
+        read_pages_locations(&mut Cursor::new(&data), metadata.row_groups[0].columns())?;
+    let end = locations[0][0].offset as usize + locations[0][0].compressed_page_size as usize;
+    data[end - 1] ^= 1;
+
+    assert!(matches!(
+        read_column(&data, &metadata),
+        Err(Error::ChecksumMismatch { .. })
+    ));
+    assert!(matches!(
+        read_indexed(&data, &metadata),
+        Err(Error::ChecksumMismatch { .. })
+    ));
+    Ok(())
+}
+
+#[tokio::test]
+async fn stream() -> Result<()> {
+    let mut data = write_file(true)?;
+    let metadata = read_metadata(&mut Cursor::new(&data))?;
+    assert_eq!(read_stream(&data, &metadata).await?, 2);
+
+    let locations =
+        read_pages_locations(&mut Cursor::new(&data), metadata.row_groups[0].columns())?;
+    let end = locations[0][0].offset as usize + locations[0][0].compressed_page_size as usize;
+    data[end - 1] ^= 1;
+    assert!(matches!(
+        read_stream(&data, &metadata).await,
+        Err(Error::ChecksumMismatch { .. })
+    ));
+    Ok(())
+}
+
+#[test]
+fn without_crc() -> Result<()> {
+    // pages without a checksum are not verified
+    let mut data = write_file(false)?;
+    let metadata = read_metadata(&mut Cursor::new(&data))?;
+    assert_eq!(read_column(&data, &metadata)?.len(), 2);
+
+    let locations =
+        read_pages_locations(&mut Cursor::new(&data), metadata.row_groups[0].columns())?;
+    let end = locations[0][0].offset as usize + locations[0][0].compressed_page_size as usize;
+    data[end - 1] ^= 1;
+    assert!(!matches!(
+        read_column(&data, &metadata),
+        Err(Error::ChecksumMismatch { .. })
+    ));
+    Ok(())
+}
+
+#[cfg(feature = "encryption")]
+#[tokio::test]
+async fn encrypted() -> Result<()> {
+    use parquet2::encryption::{
+        Algorithm, EncryptionKey, FileDecryptionProperties, FileEncryptionProperties,
+    };
+    use parquet2::read::read_metadata_with_decryption;
+
+    // the checksum is computed over the pages as written, i.e. encrypted, and verified before
+    // they are decrypted: a corrupted page is a checksum mismatch, also with AES-GCM, whose
+    // decryption would fail on it
+    for algorithm in [Algorithm::AesGcm, Algorithm::AesGcmCtr] {
+        let mut properties =
+            FileEncryptionProperties::new(Arc::new(EncryptionKey::new(vec![1; 16])));
+        properties.algorithm = algorithm;
+        let writer = FileWriter::with_encryption(
+            Cursor::new(vec![]),
+            schema(),
+            options(true),
+            None,
+            &properties,
+        )?;
+        let mut data = write(writer)?;
+        let properties = FileDecryptionProperties::new(vec![1; 16]);
+        let metadata = read_metadata_with_decryption(&mut Cursor::new(&data), &properties)?;
+        assert_eq!(read_column(&data, &metadata)?.len(), 2);
+        assert_eq!(read_indexed(&data, &metadata)?.len(), 2);
+        assert_eq!(read_stream(&data, &metadata).await?, 2);
+
+        let locations =
+            read_pages_locations(&mut Cursor::new(&data), metadata.row_groups[0].columns())?;
+        let end = locations[0][0].offset as usize + locations[0][0].compressed_page_size as usize;
+        data[end - 1] ^= 1;
+        assert!(matches!(
+            read_column(&data, &metadata),
+            Err(Error::ChecksumMismatch { .. })
+        ));
+        assert!(matches!(
+            read_indexed(&data, &metadata),
+            Err(Error::ChecksumMismatch { .. })
+        ));
+        assert!(matches!(
+            read_stream(&data, &metadata).await,
+            Err(Error::ChecksumMismatch { .. })
+        ));
+    }
+    Ok(())
+}
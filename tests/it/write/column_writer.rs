@@ -0,0 +1,489 @@
+use std::io::Cursor;
+
+use parquet2::compression::CompressionOptions;
+use parquet2::encoding::Encoding;
+use parquet2::error::Result;
+use parquet2::fallible_streaming_iterator;
+use parquet2::metadata::SchemaDescriptor;
+use parquet2::page::{CompressedPage, DataPageHeader, DataPageHeaderExt};
+use parquet2::read::{
+    get_page_iterator, prune_row_groups, read_metadata, BasicDecompressor, Predicate, Value,
+};
+use parquet2::schema::types::{ParquetType, PhysicalType};
+use parquet2::statistics::{BinaryStatistics, FixedLenStatistics, PrimitiveStatistics};
+use parquet2::write::{
+    BinaryColumnWriter, ColumnWriterOptions, DynIter, DynStreamingIterator, FileWriter,
+    FixedLenColumnWriter, Levels, NativeColumnWriter, Version, WriteOptions,
+};
+
+use crate::read::collect;
+use crate::Array;
+
+fn write_column(
+    pages: Vec<CompressedPage>,
+    schema: SchemaDescriptor,
+    options: WriteOptions,
+) -> Result<Vec<u8>> {
+    let pages = DynStreamingIterator::new(fallible_streaming_iterator::convert(
+        pages.iter().map(Result::Ok),
+    ));
+    let columns = std::iter::once(Ok(pages));
+
+    let mut writer = FileWriter::new(Cursor::new(vec![]), schema, options, None);
+    writer.write(DynIter::new(columns))?;
+    writer.end(None)?;
+    Ok(writer.into_inner().into_inner())
+}
+
+fn read_pages(data: Vec<u8>) -> Result<(Vec<Array>, parquet2::metadata::FileMetaData)> {
+    let mut reader = Cursor::new(data);
+    let metadata = read_metadata(&mut reader)?;
+    let column = &metadata.row_groups[0].columns()[0];
+    let pages = get_page_iterator(column, &mut reader, None, vec![], usize::MAX)?;
+    let arrays = collect(
+        BasicDecompressor::new(pages, vec![]),
+        column.physical_type(),
+    )?;
+    Ok((arrays, metadata))
+}
+
+#[test]
+fn native_optional() -> Result<()> {
+    let array = (0..100i64)
+        .map(|x| if x % 3 == 0 { None } else { Some(x - 50) })
+        .collect::<Vec<_>>();
+    let validity = array.iter().map(|x| x.is_some()).collect::<Vec<_>>();
+    let values = array.iter().flatten().copied().collect::<Vec<_>>();
+
+    let options = WriteOptions {
+        write_statistics: true,
+        version: Version::V2,
+        write_page_crc: false,
+    };
+    let schema = SchemaDescriptor::new(
+        "schema".to_string(),
+        vec![ParquetType::from_physical(
+            "col".to_string(),
+            PhysicalType::Int64,
+        )],
+    );
+    let column_options = ColumnWriterOptions {
+        encoding: Encoding::Plain,
+        compression: CompressionOptions::Snappy,
+        max_page_size: 128,
+        max_dictionary_size: None,
+        delta_block_layout: Default::default(),
+        max_statistics_size: None,
+    };
+
+    let mut writer = NativeColumnWriter::<i64>::new(
+        schema.columns()[0].descriptor.clone(),
+        options,
+        column_options,
+    )?;
+    // values can be written in multiple batches
+    let (first, second) = validity.split_at(40);
+    let valid = first.iter().filter(|x| **x).count();
+    writer.write(&values[..valid], Levels::Optional(first))?;
+    writer.write(&values[valid..], Levels::Optional(second))?;
+    let pages = writer.finish()?;
+    assert!(pages.len() > 1);
+
+    let data = write_column(pages, schema, options)?;
+    let (arrays, metadata) = read_pages(data)?;
+
+    let result = arrays
+        .into_iter()
+        .flat_map(|array| match array {
+            Array::Int64(array) => array,
+            _ => unreachable!(),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(result, array);
+
+    let statistics = metadata.row_groups[0].columns()[0].statistics().unwrap()?;
+    let statistics = statistics
+        .as_any()
+        .downcast_ref::<PrimitiveStatistics<i64>>()
+        .unwrap();
+    assert_eq!(statistics.null_count, Some(34));
+    assert_eq!(statistics.min_value, Some(-49));
+    assert_eq!(statistics.max_value, Some(48));
+    Ok(())
+}
+
+/// Options of a column writer that writes a page per value
+fn page_per_value() -> ColumnWriterOptions {
+    ColumnWriterOptions {
+        max_page_size: 1,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn native_unsigned_statistics() -> Result<()> {
+    let options = WriteOptions {
+        write_statistics: true,
+        version: Version::V1,
+        write_page_crc: false,
+    };
+    let schema = SchemaDescriptor::try_from_message(
+        "message schema { required int32 col (INTEGER(32,false)); }",
+    )?;
+
+    let mut writer = NativeColumnWriter::<i32>::new(
+        schema.columns()[0].descriptor.clone(),
+        options,
+        page_per_value(),
+    )?;
+    // -1 is u32::MAX
+    writer.write(&[2, -1, 1], Levels::Required)?;
+    let pages = writer.finish()?;
+    assert_eq!(pages.len(), 3);
+
+    let data = write_column(pages, schema, options)?;
+    let (_, metadata) = read_pages(data)?;
+
+    let statistics = metadata.row_groups[0].columns()[0].statistics().unwrap()?;
+    let statistics = statistics
+        .as_any()
+        .downcast_ref::<PrimitiveStatistics<i32>>()
+        .unwrap();
+    assert_eq!(statistics.min_value, Some(1));
+    assert_eq!(statistics.max_value, Some(-1));
+    Ok(())
+}
+
+#[test]
+fn decimal_statistics() -> Result<()> {
+    let options = WriteOptions {
+        write_statistics: true,
+        version: Version::V1,
+        write_page_crc: false,
+    };
+    let schema = SchemaDescriptor::try_from_message(
+        "message schema { required fixed_len_byte_array(2) col (DECIMAL(4,2)); }",
+    )?;
+
+    let mut writer = FixedLenColumnWriter::new(
+        schema.columns()[0].descriptor.clone(),
+        options,
+        page_per_value(),
+    )?;
+    // 1 and -2 as big-endian two's complement
+    writer.write([[0u8, 1], [0xFF, 0xFE]], Levels::Required)?;
+    let pages = writer.finish()?;
+    assert_eq!(pages.len(), 2);
+
+    let data = write_column(pages, schema, options)?;
+    let (_, metadata) = read_pages(data)?;
+
+    let statistics = metadata.row_groups[0].columns()[0].statistics().unwrap()?;
+    let statistics = statistics
+        .as_any()
+        .downcast_ref::<FixedLenStatistics>()
+        .unwrap();
+    assert_eq!(statistics.min_value, Some(vec![0xFF, 0xFE]));
+    assert_eq!(statistics.max_value, Some(vec![0, 1]));
+    Ok(())
+}
+
+#[test]
+fn binary_prefix_statistics() -> Result<()> {
+    let options = WriteOptions {
+        write_statistics: true,
+        version: Version::V1,
+        write_page_crc: false,
+    };
+    let schema = SchemaDescriptor::try_from_message("message schema { required binary col; }")?;
+
+    let mut writer = BinaryColumnWriter::new(
+        schema.columns()[0].descriptor.clone(),
+        options,
+        page_per_value(),
+    )?;
+    writer.write([&b"ab"[..], b"a", b"abc"], Levels::Required)?;
+    let pages = writer.finish()?;
+    assert_eq!(pages.len(), 3);
+
+    let data = write_column(pages, schema, options)?;
+    let (_, metadata) = read_pages(data)?;
+
+    let statistics = metadata.row_groups[0].columns()[0].statistics().unwrap()?;
+    let statistics = statistics
+        .as_any()
+        .downcast_ref::<BinaryStatistics>()
+        .unwrap();
+    assert_eq!(statistics.min_value, Some(b"a".to_vec()));
+    assert_eq!(statistics.max_value, Some(b"abc".to_vec()));
+    Ok(())
+}
+
+#[test]
+fn binary_optional() -> Result<()> {
+    let array = vec![
+        Some(b"hello".to_vec()),
+        None,
+        Some(b"".to_vec()),
+        Some(b"world".to_vec()),
+        None,
+    ];
+    let validity = array.iter().map(|x| x.is_some()).collect::<Vec<_>>();
+
+    let options = WriteOptions {
+        write_statistics: true,
+        version: Version::V1,
+        write_page_crc: false,
+    };
+    let schema = SchemaDescriptor::new(
+        "schema".to_string(),
+        vec![ParquetType::from_physical(
+            "col".to_string(),
+            PhysicalType::ByteArray,
+        )],
+    );
+
+    let mut writer = BinaryColumnWriter::new(
+        schema.columns()[0].descriptor.clone(),
+        options,
+        Default::default(),
+    )?;
+    writer.write(array.iter().flatten(), Levels::Optional(&validity))?;
+    let pages = writer.finish()?;
+
+    let data = write_column(pages, schema, options)?;
+    let (mut arrays, metadata) = read_pages(data)?;
+    assert_eq!(arrays.pop(), Some(Array::Binary(array)));
+
+    let statistics = metadata.row_groups[0].columns()[0].statistics().unwrap()?;
+    let statistics = statistics
+        .as_any()
+        .downcast_ref::<BinaryStatistics>()
+        .unwrap();
+    assert_eq!(statistics.null_count, Some(2));
+    assert_eq!(statistics.min_value, Some(vec![]));
+    assert_eq!(statistics.max_value, Some(b"world".to_vec()));
+    Ok(())
+}
+
+#[test]
+fn binary_truncated_statistics() -> Result<()> {
+    let array = vec![b"abcdef".to_vec(), b"uvwxyz".to_vec(), b"uv".to_vec()];
+
+    let options = WriteOptions {
+        write_statistics: true,
+        version: Version::V1,
+        write_page_crc: false,
+    };
+    let schema = SchemaDescriptor::try_from_message("message schema { required binary col; }")?;
+
+    let mut writer = BinaryColumnWriter::new(
+        schema.columns()[0].descriptor.clone(),
+        options,
+        ColumnWriterOptions {
+            max_statistics_size: Some(3),
+            ..Default::default()
+        },
+    )?;
+    writer.write(&array, Levels::Required)?;
+    let pages = writer.finish()?;
+
+    let data = write_column(pages, schema, options)?;
+    let (mut arrays, metadata) = read_pages(data)?;
+    assert_eq!(
+        arrays.pop(),
+        Some(Array::Binary(array.into_iter().map(Some).collect()))
+    );
+
+    let statistics = metadata.row_groups[0].columns()[0].statistics().unwrap()?;
+    let statistics = statistics
+        .as_any()
+        .downcast_ref::<BinaryStatistics>()
+        .unwrap();
+    assert_eq!(statistics.min_value, Some(b"abc".to_vec()));
+    assert_eq!(statistics.max_value, Some(b"uvx".to_vec()));
+
+    // the truncated values are bounds: the row group is not pruned for its min and max
+    let path = vec!["col".to_string()];
+    for value in [&b"abcdef"[..], b"uvwxyz"] {
+        let predicate = Predicate::Eq(path.clone(), Value::Bytes(value.to_vec()));
+        assert_eq!(prune_row_groups(&metadata, &predicate)?, vec![0]);
+    }
+    let predicate = Predicate::Gt(path, Value::Bytes(b"uvx".to_vec()));
+    assert!(prune_row_groups(&metadata, &predicate)?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn binary_truncated_statistics_pages() -> Result<()> {
+    let options = WriteOptions {
+        write_statistics: true,
+        version: Version::V1,
+        write_page_crc: false,
+    };
+    let schema = SchemaDescriptor::try_from_message("message schema { required binary col; }")?;
+
+    let mut writer = BinaryColumnWriter::new(
+        schema.columns()[0].descriptor.clone(),
+        options,
+        ColumnWriterOptions {
+            max_statistics_size: Some(2),
+            ..page_per_value()
+        },
+    )?;
+    // the truncated min of the pages are "u", "uv" and "ab" and their max "u", "uw" and "ac":
+    // "u" is a prefix and thus smaller than "uw"
+    writer.write([&b"u"[..], b"uvwxyz", b"abc"], Levels::Required)?;
+    let pages = writer.finish()?;
+    assert_eq!(pages.len(), 3);
+
+    let data = write_column(pages, schema, options)?;
+    let (_, metadata) = read_pages(data)?;
+
+    let statistics = metadata.row_groups[0].columns()[0].statistics().unwrap()?;
+    let statistics = statistics
+        .as_any()
+        .downcast_ref::<BinaryStatistics>()
+        .unwrap();
+    assert_eq!(statistics.min_value, Some(b"ab".to_vec()));
+    assert_eq!(statistics.max_value, Some(b"uw".to_vec()));
+
+    let path = vec!["col".to_string()];
+    for value in [&b"u"[..], b"uvwxyz", b"abc"] {
+        let predicate = Predicate::Eq(path.clone(), Value::Bytes(value.to_vec()));
+        assert_eq!(prune_row_groups(&metadata, &predicate)?, vec![0]);
+    }
+    Ok(())
+}
+
+/// Writes a categorical column with dictionary encoding, returning the encodings of its data pages
+fn write_dictionary(max_dictionary_size: usize) -> Result<Vec<Encoding>> {
+    let array = (0..1000)
+        .map(|x| match x % 7 {
+            0 => None,
+            _ => Some(format!("{:04}", x / 4).into_bytes()),
+        })
+        .collect::<Vec<_>>();
+    let validity = array.iter().map(|x| x.is_some()).collect::<Vec<_>>();
+
+    let options = WriteOptions {
+        write_statistics: true,
+        version: Version::V1,
+        write_page_crc: false,
+    };
+    let schema = SchemaDescriptor::new(
+        "schema".to_string(),
+        vec![ParquetType::from_physical(
+            "col".to_string(),
+            PhysicalType::ByteArray,
+        )],
+    );
+    let column_options = ColumnWriterOptions {
+        max_page_size: 1024,
+        max_dictionary_size: Some(max_dictionary_size),
+        ..Default::default()
+    };
+
+    let mut writer = BinaryColumnWriter::new(
+        schema.columns()[0].descriptor.clone(),
+        options,
+        column_options,
+    )?;
+    writer.write(array.iter().flatten(), Levels::Optional(&validity))?;
+    let pages = writer.finish()?;
+    assert!(matches!(pages[0], CompressedPage::Dict(_)));
+    let encodings = pages
+        .iter()
+        .filter_map(|page| match page {
+            CompressedPage::Data(page) => match page.header() {
+                DataPageHeader::V1(header) => Some(header.encoding()),
+                DataPageHeader::V2(header) => Some(header.encoding()),
+            },
+            CompressedPage::Dict(_) => None,
+        })
+        .collect();
+
+    let data = write_column(pages, schema, options)?;
+    let (arrays, metadata) = read_pages(data)?;
+    let column = &metadata.row_groups[0].columns()[0];
+    let dictionary_page_offset = column.dictionary_page_offset().unwrap();
+    assert!(column.data_page_offset() > dictionary_page_offset);
+
+    let result = arrays
+        .into_iter()
+        .flat_map(|array| match array {
+            Array::Binary(array) => array,
+            _ => unreachable!(),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(result, array);
+    Ok(encodings)
+}
+
+#[test]
+fn dictionary() -> Result<()> {
+    let encodings = write_dictionary(1024 * 1024)?;
+    assert!(encodings
+        .iter()
+        .all(|encoding| *encoding == Encoding::RleDictionary));
+    Ok(())
+}
+
+#[test]
+fn dictionary_all_null() -> Result<()> {
+    let options = WriteOptions {
+        write_statistics: true,
+        version: Version::V1,
+        write_page_crc: false,
+    };
+    let schema = SchemaDescriptor::new(
+        "schema".to_string(),
+        vec![ParquetType::from_physical(
+            "col".to_string(),
+            PhysicalType::ByteArray,
+        )],
+    );
+    let column_options = ColumnWriterOptions {
+        max_dictionary_size: Some(1024),
+        ..Default::default()
+    };
+
+    let mut writer = BinaryColumnWriter::new(
+        schema.columns()[0].descriptor.clone(),
+        options,
+        column_options,
+    )?;
+    writer.write(Vec::<&[u8]>::new(), Levels::Optional(&[false; 10]))?;
+    let pages = writer.finish()?;
+    // without values there is no dictionary and thus no page is dictionary-encoded
+    assert_eq!(pages.len(), 1);
+    let encoding = match &pages[0] {
+        CompressedPage::Data(page) => match page.header() {
+            DataPageHeader::V1(header) => header.encoding(),
+            DataPageHeader::V2(header) => header.encoding(),
+        },
+        CompressedPage::Dict(_) => unreachable!(),
+    };
+    assert_eq!(encoding, Encoding::Plain);
+
+    let data = write_column(pages, schema, options)?;
+    let (arrays, metadata) = read_pages(data)?;
+    assert!(metadata.row_groups[0].columns()[0]
+        .dictionary_page_offset()
+        .is_none());
+    assert_eq!(arrays, vec![Array::Binary(vec![None; 10])]);
+    Ok(())
+}
+
+#[test]
+fn dictionary_fallback() -> Result<()> {
+    // a page has 128 values (8 bytes each) and thus adds ~37 entries (8 bytes each) to the
+    // dictionary: the dictionary of the first page fits, the one of the second does not
+    let encodings = write_dictionary(400)?;
+    assert!(encodings.len() > 2);
+    assert_eq!(encodings[0], Encoding::RleDictionary);
+    assert!(encodings[1..]
+        .iter()
+        .all(|encoding| *encoding == Encoding::Plain));
+    Ok(())
+}
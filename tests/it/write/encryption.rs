@@ -0,0 +1,469 @@
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::sync::Arc;
+
+use parquet2::bloom_filter;
+use parquet2::encryption::{
+    Algorithm, ColumnEncryption, EncryptionKey, FileDecryptionProperties, FileEncryptionProperties,
+    KeyProvider, KeyRetriever,
+};
+use parquet2::error::Result;
+use parquet2::fallible_streaming_iterator;
+use parquet2::indexes::{select_pages, Interval};
+use parquet2::metadata::{FileMetaData, SchemaDescriptor};
+use parquet2::page::CompressedPage;
+use parquet2::read::{
+    get_page_iterator, read_columns_indexes, read_columns_indexes_async, read_metadata,
+    read_metadata_with_decryption, read_pages_locations, read_pages_locations_async,
+    BasicDecompressor, IndexedPageReader,
+};
+use parquet2::schema::types::{ParquetType, PhysicalType};
+use parquet2::write::{
+    ColumnWriterOptions, DynIter, DynStreamingIterator, FileStreamer, FileWriter, Levels,
+    NativeColumnWriter, Version, WriteOptions,
+};
+use parquet2::FallibleStreamingIterator;
+
+use crate::read::collect;
+use crate::{get_path, Array};
+
+/// Provides and retrieves the keys of the columns "a" (own key), "b" (footer key) and "c"
+/// (plaintext) by their name.
+struct Keys;
+
+impl KeyProvider for Keys {
+    fn footer_key(&self) -> Result<EncryptionKey> {
+        Ok(EncryptionKey::with_metadata(
+            vec![1; 16],
+            b"footer".to_vec(),
+        ))
+    }
+
+    fn column_encryption(&self, path: &[String]) -> Result<ColumnEncryption> {
+        Ok(match path[0].as_str() {
+            "a" => ColumnEncryption::ColumnKey(EncryptionKey::with_metadata(
+                vec![2; 32],
+                b"a".to_vec(),
+            )),
+            "b" => ColumnEncryption::FooterKey,
+            _ => ColumnEncryption::Plaintext,
+        })
+    }
+}
+
+impl KeyRetriever for Keys {
+    fn retrieve_key(&self, key_metadata: &[u8]) -> Result<Vec<u8>> {
+        Ok(match key_metadata {
+            b"footer" => vec![1; 16],
+            _ => vec![2; 32],
+        })
+    }
+}
+
+fn schema() -> SchemaDescriptor {
+    SchemaDescriptor::new(
+        "schema".to_string(),
+        ["a", "b", "c"]
+            .iter()
+            .map(|name| ParquetType::from_physical(name.to_string(), PhysicalType::Int64))
+            .collect(),
+    )
+}
+
+fn values() -> Vec<i64> {
+    (0..200).map(|x| x % 30).collect()
+}
+
+/// Returns the (dictionary-encoded) pages of every column of [`schema`]
+fn pages(schema: &SchemaDescriptor, options: WriteOptions) -> Result<Vec<Vec<CompressedPage>>> {
+    let column_options = ColumnWriterOptions {
+        max_page_size: 256,
+        max_dictionary_size: Some(1024),
+        ..Default::default()
+    };
+    schema
+        .columns()
+        .iter()
+        .map(|column| {
+            let mut writer =
+                NativeColumnWriter::<i64>::new(column.descriptor.clone(), options, column_options)?;
+            writer.write(&values(), Levels::Required)?;
+            writer.finish()
+        })
+        .collect()
+}
+
+fn write_file(properties: &FileEncryptionProperties) -> Result<Vec<u8>> {
+    let options = WriteOptions {
+        write_statistics: true,
+        version: Version::V2,
+        write_page_crc: false,
+    };
+    let schema = schema();
+    let pages = pages(&schema, options)?;
+    assert!(pages[0].len() > 2);
+
+    let mut writer =
+        FileWriter::with_encryption(Cursor::new(vec![]), schema, options, None, properties)?;
+    let columns = pages.iter().map(|pages| {
+        Ok(DynStreamingIterator::new(
+            fallible_streaming_iterator::convert(pages.iter().map(Result::Ok)),
+        ))
+    });
+    writer.write(DynIter::new(columns))?;
+
+    let mut bitset = vec![0; 64];
+    values()
+        .into_iter()
+        .for_each(|x| bloom_filter::insert(&mut bitset, bloom_filter::hash_native(x)));
+    for column in 0..3 {
+        writer.set_bloom_filter(0, column, bitset.clone())?;
+    }
+    writer.end(None)?;
+    Ok(writer.into_inner().into_inner())
+}
+
+fn read_values(column: usize, metadata: &FileMetaData, data: &[u8]) -> Result<Vec<i64>> {
+    let column = &metadata.row_groups[0].columns()[column];
+    let pages = get_page_iterator(column, Cursor::new(data), None, vec![], usize::MAX)?;
+    let arrays = collect(
+        BasicDecompressor::new(pages, vec![]),
+        column.physical_type(),
+    )?;
+    Ok(arrays
+        .into_iter()
+        .flat_map(|array| match array {
+            Array::Int64(array) => array,
+            _ => unreachable!(),
+        })
+        .flatten()
+        .collect())
+}
+
+/// Reads every column, index and bloom filter of an encrypted file with `properties`
+fn read_file(data: &[u8], properties: &FileDecryptionProperties) -> Result<()> {
+    let metadata = read_metadata_with_decryption(&mut Cursor::new(data), properties)?;
+    let columns = metadata.row_groups[0].columns();
+    assert!(columns[0].decryptor().is_some());
+    assert!(columns[1].decryptor().is_some());
+    assert!(columns[2].decryptor().is_none());
+
+    for (i, column) in columns.iter().enumerate() {
+        assert_eq!(read_values(i, &metadata, data)?, values());
+
+        let mut bitset = vec![];
+        bloom_filter::read(column, &mut Cursor::new(data), &mut bitset)?;
+        assert!(bloom_filter::is_in_set(
+            &bitset,
+            bloom_filter::hash_native(29i64)
+        ));
+        assert!(!bloom_filter::is_in_set(
+            &bitset,
+            bloom_filter::hash_native(30i64)
+        ));
+
+        let statistics = column.statistics().unwrap()?;
+        assert_eq!(statistics.null_count(), Some(0));
+    }
+
+    let indexes = read_columns_indexes(&mut Cursor::new(data), columns)?;
+    assert_eq!(indexes.len(), 3);
+    let locations = read_pages_locations(&mut Cursor::new(data), columns)?;
+    for (column, locations) in columns.iter().zip(locations) {
+        assert_eq!(
+            locations[0].offset,
+            column.data_page_offset(),
+            "the dictionary page is not part of the offset index"
+        );
+
+        // read the last rows: the dictionary page and the last two data pages are decrypted
+        let intervals = &[Interval::new(190, 10)];
+        let pages = select_pages(intervals, &locations, 200)?;
+        let pages = IndexedPageReader::new(Cursor::new(data), column, pages, vec![], vec![]);
+        let mut pages = BasicDecompressor::new(pages, vec![]);
+        let mut num_pages = 0;
+        while pages.next()?.is_some() {
+            num_pages += 1;
+        }
+        assert_eq!(num_pages, 3);
+    }
+    Ok(())
+}
+
+#[test]
+fn uniform() -> Result<()> {
+    let key = EncryptionKey::new(vec![3; 24]);
+    let data = write_file(&FileEncryptionProperties::new(Arc::new(key)))?;
+    assert_eq!(&data[..4], b"PARE");
+    assert_eq!(&data[data.len() - 4..], b"PARE");
+
+    // the footer can't be read without the key
+    assert!(read_metadata(&mut Cursor::new(&data)).is_err());
+    let wrong = FileDecryptionProperties::new(vec![4; 24]);
+    assert!(read_metadata_with_decryption(&mut Cursor::new(&data), &wrong).is_err());
+
+    let properties = FileDecryptionProperties::new(vec![3; 24]);
+    let metadata = read_metadata_with_decryption(&mut Cursor::new(&data), &properties)?;
+    for column in 0..3 {
+        assert_eq!(read_values(column, &metadata, &data)?, values());
+    }
+    Ok(())
+}
+
+#[test]
+fn column_keys() -> Result<()> {
+    for algorithm in [Algorithm::AesGcm, Algorithm::AesGcmCtr] {
+        let mut properties = FileEncryptionProperties::new(Arc::new(Keys));
+        properties.algorithm = algorithm;
+        let data = write_file(&properties)?;
+        assert_eq!(&data[..4], b"PARE");
+        assert_eq!(&data[data.len() - 4..], b"PARE");
+
+        read_file(
+            &data,
+            &FileDecryptionProperties::with_key_retriever(Arc::new(Keys)),
+        )?;
+
+        // a column can't be read without its key, but the other columns can
+        let properties = FileDecryptionProperties::new(vec![1; 16]);
+        let metadata = read_metadata_with_decryption(&mut Cursor::new(&data), &properties)?;
+        assert!(read_values(0, &metadata, &data).is_err());
+        let column = &metadata.row_groups[0].columns()[0];
+        assert!(bloom_filter::read(column, &mut Cursor::new(&data), &mut vec![]).is_err());
+        assert_eq!(read_values(1, &metadata, &data)?, values());
+        assert_eq!(read_values(2, &metadata, &data)?, values());
+        let properties = FileDecryptionProperties {
+            column_keys: HashMap::from([(vec!["a".to_string()], vec![2; 32])]),
+            ..FileDecryptionProperties::new(vec![1; 16])
+        };
+        read_file(&data, &properties)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn plaintext_footer() -> Result<()> {
+    let mut properties = FileEncryptionProperties::new(Arc::new(Keys));
+    properties.encrypted_footer = false;
+    let data = write_file(&properties)?;
+    assert_eq!(&data[..4], b"PAR1");
+    assert_eq!(&data[data.len() - 4..], b"PAR1");
+
+    read_file(
+        &data,
+        &FileDecryptionProperties::with_key_retriever(Arc::new(Keys)),
+    )?;
+
+    // the metadata is read without decryption, but only the plaintext column can be read
+    let metadata = read_metadata(&mut Cursor::new(&data))?;
+    let columns = metadata.row_groups[0].columns();
+    assert!(columns[0].is_encrypted());
+    assert!(columns[1].is_encrypted());
+    assert!(!columns[2].is_encrypted());
+    assert_eq!(read_values(2, &metadata, &data)?, values());
+    assert!(bloom_filter::read(&columns[2], &mut Cursor::new(&data), &mut vec![]).is_ok());
+    assert_eq!(
+        read_columns_indexes(&mut Cursor::new(&data), &columns[2..])?.len(),
+        1
+    );
+    for column in 0..2 {
+        assert!(read_values(column, &metadata, &data).is_err());
+        let mut bitset = vec![];
+        assert!(
+            bloom_filter::read(&columns[column], &mut Cursor::new(&data), &mut bitset).is_err()
+        );
+        assert!(read_columns_indexes(&mut Cursor::new(&data), &columns[column..=column]).is_err());
+        assert!(read_pages_locations(&mut Cursor::new(&data), &columns[column..=column]).is_err());
+    }
+
+    // readers without the keys can read the plaintext column, but not the statistics nor the
+    // values of the encrypted columns
+    let properties = FileDecryptionProperties {
+        verify_plaintext_footer: false,
+        ..Default::default()
+    };
+    let metadata = read_metadata_with_decryption(&mut Cursor::new(&data), &properties)?;
+    assert_eq!(read_values(2, &metadata, &data)?, values());
+    assert!(metadata.row_groups[0].columns()[0].statistics().is_none());
+    assert!(metadata.row_groups[0].columns()[1].statistics().is_none());
+    assert!(metadata.row_groups[0].columns()[0]
+        .check_decryption()
+        .is_err());
+    assert!(metadata.row_groups[0].columns()[2]
+        .check_decryption()
+        .is_ok());
+    assert!(read_values(0, &metadata, &data).is_err());
+    assert!(read_values(1, &metadata, &data).is_err());
+
+    // the footer is signed
+    let mut corrupted = data.clone();
+    let position = corrupted.len() - 100;
+    corrupted[position] ^= 1;
+    let properties = FileDecryptionProperties::with_key_retriever(Arc::new(Keys));
+    assert!(read_metadata_with_decryption(&mut Cursor::new(&corrupted), &properties).is_err());
+    Ok(())
+}
+
+#[test]
+fn aad_prefix() -> Result<()> {
+    let mut properties = FileEncryptionProperties::new(Arc::new(Keys));
+    properties.aad_prefix = Some(b"file.parquet".to_vec());
+    properties.store_aad_prefix = false;
+    let data = write_file(&properties)?;
+
+    let mut properties = FileDecryptionProperties::with_key_retriever(Arc::new(Keys));
+    assert!(read_metadata_with_decryption(&mut Cursor::new(&data), &properties).is_err());
+    properties.aad_prefix = Some(b"other.parquet".to_vec());
+    assert!(read_metadata_with_decryption(&mut Cursor::new(&data), &properties).is_err());
+    properties.aad_prefix = Some(b"file.parquet".to_vec());
+    read_file(&data, &properties)
+}
+
+#[test]
+fn invalid_key() {
+    let key = EncryptionKey::new(vec![1; 10]);
+    let properties = FileEncryptionProperties::new(Arc::new(key));
+    let result = FileWriter::with_encryption(
+        Cursor::new(vec![]),
+        schema(),
+        WriteOptions {
+            write_statistics: true,
+            version: Version::V1,
+            write_page_crc: false,
+        },
+        None,
+        &properties,
+    );
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn streamer() -> Result<()> {
+    let options = WriteOptions {
+        write_statistics: true,
+        version: Version::V1,
+        write_page_crc: false,
+    };
+    let schema = schema();
+    let pages = pages(&schema, options)?;
+
+    let properties = FileEncryptionProperties::new(Arc::new(Keys));
+    let mut writer = FileStreamer::with_encryption(
+        futures::io::Cursor::new(vec![]),
+        schema,
+        options,
+        None,
+        &properties,
+    )?;
+    let columns = pages.iter().map(|pages| {
+        Ok(DynStreamingIterator::new(
+            fallible_streaming_iterator::convert(pages.iter().map(Result::Ok)),
+        ))
+    });
+    writer.write(DynIter::new(columns)).await?;
+    let mut bitset = vec![0; 64];
+    values()
+        .into_iter()
+        .for_each(|x| bloom_filter::insert(&mut bitset, bloom_filter::hash_native(x)));
+    for column in 0..3 {
+        writer.set_bloom_filter(0, column, bitset.clone())?;
+    }
+    writer.end(None).await?;
+    let data = writer.into_inner().into_inner();
+    assert_eq!(&data[..4], b"PARE");
+    assert_eq!(&data[data.len() - 4..], b"PARE");
+
+    let properties = FileDecryptionProperties::with_key_retriever(Arc::new(Keys));
+    let metadata = read_metadata_with_decryption(&mut Cursor::new(&data), &properties)?;
+    for column in 0..3 {
+        assert_eq!(read_values(column, &metadata, &data)?, values());
+    }
+
+    // the bloom filters and indexes of encrypted columns are also read asynchronously
+    let columns = metadata.row_groups[0].columns();
+    let mut reader = futures::io::Cursor::new(data.clone());
+    for column in columns {
+        let mut bitset = vec![];
+        bloom_filter::read_async(column, &mut reader, &mut bitset).await?;
+        assert!(bloom_filter::is_in_set(
+            &bitset,
+            bloom_filter::hash_native(29i64)
+        ));
+    }
+    assert_eq!(
+        read_columns_indexes_async(&mut reader, columns).await?,
+        read_columns_indexes(&mut Cursor::new(&data), columns)?
+    );
+    assert_eq!(
+        read_pages_locations_async(&mut reader, columns).await?,
+        read_pages_locations(&mut Cursor::new(&data), columns)?
+    );
+    Ok(())
+}
+
+/// Retrieves the keys of the encrypted files of parquet-testing by their metadata.
+struct TestingKeys;
+
+impl KeyRetriever for TestingKeys {
+    fn retrieve_key(&self, key_metadata: &[u8]) -> Result<Vec<u8>> {
+        Ok(match key_metadata {
+            b"kf" => b"0123456789012345".to_vec(),
+            b"kc1" => b"1234567890123450".to_vec(),
+            b"kc2" => b"1234567890123451".to_vec(),
+            _ => unreachable!(),
+        })
+    }
+}
+
+#[test]
+fn parquet_testing() -> Result<()> {
+    // files written by parquet-mr with the columns "double_field" and "float_field" encrypted
+    // with their own keys and the footer with the footer key
+    let files = [
+        "encrypt_columns_and_footer.parquet.encrypted",
+        "encrypt_columns_and_footer_aad.parquet.encrypted",
+        "encrypt_columns_and_footer_ctr.parquet.encrypted",
+    ];
+    let properties = FileDecryptionProperties::with_key_retriever(Arc::new(TestingKeys));
+    for file in files {
+        let mut path = get_path();
+        path.push(file);
+        let data = std::fs::read(path)?;
+        assert_eq!(&data[..4], b"PARE", "of file {file}");
+        assert_eq!(&data[data.len() - 4..], b"PARE", "of file {file}");
+
+        let metadata = read_metadata_with_decryption(&mut Cursor::new(&data), &properties)?;
+        let (mut doubles, mut floats) = (vec![], vec![]);
+        for row_group in &metadata.row_groups {
+            for column in row_group.columns() {
+                let pages =
+                    get_page_iterator(column, Cursor::new(&data), None, vec![], usize::MAX)?;
+                let arrays = collect(
+                    BasicDecompressor::new(pages, vec![]),
+                    column.physical_type(),
+                )?;
+                match column.descriptor().path_in_schema[0].as_str() {
+                    "double_field" => doubles.extend(arrays),
+                    "float_field" => floats.extend(arrays),
+                    _ => {}
+                }
+            }
+        }
+        let doubles = doubles.into_iter().flat_map(|array| match array {
+            Array::Double(array) => array,
+            _ => unreachable!(),
+        });
+        let floats = floats.into_iter().flat_map(|array| match array {
+            Array::Float(array) => array,
+            _ => unreachable!(),
+        });
+        let mut num_rows = 0;
+        for (i, (double, float)) in doubles.zip(floats).enumerate() {
+            assert_eq!(double, Some(i as f64 * 1.1111111), "of file {file}");
+            assert_eq!(float, Some(i as f32 * 1.1), "of file {file}");
+            num_rows += 1;
+        }
+        assert_eq!(num_rows, metadata.num_rows, "of file {file}");
+    }
+    Ok(())
+}
@@ -7,7 +7,8 @@ use parquet2::indexes::{
 };
 use parquet2::metadata::SchemaDescriptor;
 use parquet2::read::{
-    read_columns_indexes, read_metadata, read_pages_locations, BasicDecompressor, IndexedPageReader,
+    read_columns_indexes, read_columns_indexes_async, read_metadata, read_pages_locations,
+    read_pages_locations_async, BasicDecompressor, IndexedPageReader,
 };
 use parquet2::schema::types::{ParquetType, PhysicalType, PrimitiveType};
 use parquet2::write::WriteOptions;
@@ -25,6 +26,7 @@ fn write_file() -> Result<Vec<u8>> {
     let options = WriteOptions {
         write_statistics: true,
         version: Version::V1,
+        write_page_crc: false,
     };
 
     let schema = SchemaDescriptor::new(
@@ -120,7 +122,7 @@ fn read_indexes_and_locations() -> Result<()> {
                 null_count: Some(0),
             },
         ],
-        boundary_order: BoundaryOrder::Unordered,
+        boundary_order: BoundaryOrder::Ascending,
     }) as Box<dyn Index>];
 
     let indexes = read_columns_indexes(&mut reader, columns)?;
@@ -131,3 +133,49 @@ fn read_indexes_and_locations() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn read_indexes_and_locations_async() -> Result<()> {
+    let data = write_file()?;
+    let metadata = read_metadata(&mut Cursor::new(&data))?;
+    let columns = &metadata.row_groups[0].columns();
+
+    let mut reader = Cursor::new(data.clone());
+    let expected_index = read_columns_indexes(&mut reader, columns)?;
+    let expected_page_locations = read_pages_locations(&mut reader, columns)?;
+
+    let mut reader = futures::io::Cursor::new(data);
+    let indexes = read_columns_indexes_async(&mut reader, columns).await?;
+    assert_eq!(&indexes, &expected_index);
+
+    let pages = read_pages_locations_async(&mut reader, columns).await?;
+    assert_eq!(pages, expected_page_locations);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn read_truncated_indexes() -> Result<()> {
+    let data = write_file()?;
+    let metadata = read_metadata(&mut Cursor::new(&data))?;
+    let columns = &metadata.row_groups[0].columns();
+
+    // files truncated in the middle of their indexes
+    let column_chunk = columns[0].column_chunk();
+    let end = column_chunk.column_index_offset.unwrap() + 1;
+    let truncated = &data[..end as usize];
+    assert!(read_columns_indexes(&mut Cursor::new(truncated), columns).is_err());
+    let mut reader = futures::io::Cursor::new(truncated);
+    assert!(read_columns_indexes_async(&mut reader, columns)
+        .await
+        .is_err());
+
+    let end = column_chunk.offset_index_offset.unwrap() + 1;
+    let truncated = &data[..end as usize];
+    assert!(read_pages_locations(&mut Cursor::new(truncated), columns).is_err());
+    let mut reader = futures::io::Cursor::new(truncated);
+    assert!(read_pages_locations_async(&mut reader, columns)
+        .await
+        .is_err());
+    Ok(())
+}
@@ -1,4 +1,9 @@
 mod binary;
+mod bloom_filter;
+mod checksum;
+mod column_writer;
+#[cfg(feature = "encryption")]
+mod encryption;
 mod indexes;
 mod primitive;
 mod sidecar;
@@ -14,11 +19,11 @@ use parquet2::schema::types::{ParquetType, PhysicalType};
 use parquet2::statistics::Statistics;
 use parquet2::write::FileStreamer;
 use parquet2::write::{Compressor, DynIter, DynStreamingIterator, FileWriter, Version};
-use parquet2::{metadata::Descriptor, page::EncodedPage, write::WriteOptions};
+use parquet2::{encoding::Encoding, metadata::Descriptor, page::EncodedPage, write::WriteOptions};
 
 use super::Array;
 use super::{alltypes_plain, alltypes_statistics};
-use primitive::array_to_page_v1;
+use primitive::{array_to_page_v1, array_to_page_v1_encoded};
 
 pub fn array_to_page(
     array: &Array,
@@ -57,6 +62,7 @@ fn test_column(column: &str, compression: CompressionOptions) -> Result<()> {
     let options = WriteOptions {
         write_statistics: true,
         version: Version::V1,
+        write_page_crc: false,
     };
 
     // prepare schema
@@ -126,6 +132,12 @@ fn int32_lz4_short_i32_array() -> Result<()> {
     test_column("id-short-array", CompressionOptions::Lz4Raw)
 }
 
+#[cfg(feature = "lzo")]
+#[test]
+fn int32_lzo() -> Result<()> {
+    test_column("id", CompressionOptions::Lzo)
+}
+
 #[test]
 fn int32_brotli() -> Result<()> {
     test_column(
@@ -190,6 +202,7 @@ fn basic() -> Result<()> {
     let options = WriteOptions {
         write_statistics: false,
         version: Version::V1,
+        write_page_crc: false,
     };
 
     let schema = SchemaDescriptor::new(
@@ -232,12 +245,56 @@ fn basic() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn byte_stream_split() -> Result<()> {
+    let array = vec![Some(0.1f64), None, Some(-2.5), Some(1e10), None, Some(3.0)];
+
+    let options = WriteOptions {
+        write_statistics: true,
+        version: Version::V1,
+        write_page_crc: false,
+    };
+
+    let schema = SchemaDescriptor::new(
+        "schema".to_string(),
+        vec![ParquetType::from_physical(
+            "col".to_string(),
+            PhysicalType::Double,
+        )],
+    );
+
+    let pages = DynStreamingIterator::new(Compressor::new_from_vec(
+        DynIter::new(std::iter::once(array_to_page_v1_encoded(
+            &array,
+            &options,
+            &schema.columns()[0].descriptor,
+            Encoding::ByteStreamSplit,
+        ))),
+        CompressionOptions::Uncompressed,
+        vec![],
+    ));
+    let columns = std::iter::once(Ok(pages));
+
+    let writer = Cursor::new(vec![]);
+    let mut writer = FileWriter::new(writer, schema, options, None);
+
+    writer.write(DynIter::new(columns))?;
+    writer.end(None)?;
+
+    let data = writer.into_inner().into_inner();
+
+    let (result, _) = read_column(&mut Cursor::new(data))?;
+    assert_eq!(result, Array::Double(array));
+    Ok(())
+}
+
 async fn test_column_async(column: &str, compression: CompressionOptions) -> Result<()> {
     let array = alltypes_plain(column);
 
     let options = WriteOptions {
         write_statistics: true,
         version: Version::V1,
+        write_page_crc: false,
     };
 
     // prepare schema
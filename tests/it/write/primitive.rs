@@ -1,5 +1,5 @@
 use parquet2::{
-    encoding::Encoding,
+    encoding::{byte_stream_split, Encoding},
     metadata::Descriptor,
     page::{DataPage, DataPageHeader, DataPageHeaderV1, EncodedPage},
     statistics::{serialize_statistics, PrimitiveStatistics, Statistics},
@@ -44,10 +44,28 @@ pub fn array_to_page_v1<T: NativeType>(
     array: &[Option<T>],
     options: &WriteOptions,
     descriptor: &Descriptor,
+) -> Result<EncodedPage> {
+    array_to_page_v1_encoded(array, options, descriptor, Encoding::Plain)
+}
+
+pub fn array_to_page_v1_encoded<T: NativeType>(
+    array: &[Option<T>],
+    options: &WriteOptions,
+    descriptor: &Descriptor,
+    encoding: Encoding,
 ) -> Result<EncodedPage> {
     let (values, mut buffer) = unzip_option(array)?;
 
-    buffer.extend_from_slice(&values);
+    match encoding {
+        Encoding::Plain => buffer.extend_from_slice(&values),
+        Encoding::ByteStreamSplit => {
+            byte_stream_split::encode_fixed_len(&values, std::mem::size_of::<T>(), &mut buffer)?
+        }
+        other => unreachable!(
+            "the tests do not write primitive pages encoded with {:?}",
+            other
+        ),
+    }
 
     let statistics = if options.write_statistics {
         let statistics = &PrimitiveStatistics {
@@ -64,7 +82,7 @@ pub fn array_to_page_v1<T: NativeType>(
 
     let header = DataPageHeaderV1 {
         num_values: array.len() as i32,
-        encoding: Encoding::Plain.into(),
+        encoding: encoding.into(),
         definition_level_encoding: Encoding::Rle.into(),
         repetition_level_encoding: Encoding::Rle.into(),
         statistics,
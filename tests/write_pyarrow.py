@@ -122,6 +122,67 @@ def case_struct(size):
     )
 
 
+def case_encoded_required(size):
+    # sorted strings with long common prefixes, as in front-compressed columns
+    string = [
+        "https://a.com",
+        "https://a.com/a",
+        "https://a.com/ab",
+        "https://a.com/b",
+        "https://b.com",
+        "https://b.com/",
+        "https://b.com/index.html",
+        "https://bb.com",
+        "https://c.com",
+        "https://c.com/c",
+    ]
+    # values whose deltas need up to 64 bits, as nanosecond timestamps with gaps or hashes
+    int64 = [
+        0,
+        9223372036854775807,
+        -9223372036854775808,
+        1,
+        -1,
+        1700000000000000000,
+        1700000000123456789,
+        -4611686018427387904,
+        42,
+        9223372036854775806,
+    ]
+    fields = [
+        pa.field("string", pa.utf8(), nullable=False),
+        pa.field("int64", pa.int64(), nullable=False),
+    ]
+    schema = pa.schema(fields)
+    return (
+        {
+            "string": string * size,
+            "int64": int64 * size,
+        },
+        schema,
+        f"encoded_required_{size*10}.parquet",
+    )
+
+
+def write_pyarrow_encoded(case, column_encoding, size=1, page_version=1):
+    data, schema, path = case(size)
+
+    encoding = next(iter(column_encoding.values())).lower()
+    base_path = f"{PYARROW_PATH}/v{page_version}/{encoding}"
+
+    t = pa.table(data, schema=schema)
+    os.makedirs(base_path, exist_ok=True)
+    pa.parquet.write_table(
+        t,
+        f"{base_path}/{path}",
+        version=f"{page_version}.0",
+        data_page_version=f"{page_version}.0",
+        write_statistics=True,
+        use_dictionary=False,
+        column_encoding=column_encoding,
+    )
+
+
 def write_pyarrow(
     case, size=1, page_version=1, use_dictionary=False, compression=None
 ):
@@ -152,3 +213,13 @@ for case in [case_basic_nullable, case_basic_required, case_nested, case_struct]
         for use_dict in [False, True]:
             for compression in [None, "brotli", "lz4", "gzip", "snappy", "zstd"]:
                 write_pyarrow(case, 1, version, use_dict, compression)
+
+for version in [1, 2]:
+    write_pyarrow_encoded(
+        case_encoded_required, {"string": "DELTA_BYTE_ARRAY"}, 1, version
+    )
+    write_pyarrow_encoded(
+        case_encoded_required, {"int64": "DELTA_BINARY_PACKED"}, 1, version
+    )
+    for case in [case_basic_nullable, case_basic_required]:
+        write_pyarrow_encoded(case, {"float64": "BYTE_STREAM_SPLIT"}, 1, version)